@@ -25,7 +25,7 @@ use node_runtime::{
 	AuthorityDiscoveryConfig, BabeConfig, BalancesConfig, ContractsConfig, CouncilConfig,
 	DemocracyConfig,GrandpaConfig, ImOnlineConfig, SessionConfig, SessionKeys, StakerStatus,
 	StakingConfig, ElectionsConfig, IndicesConfig, SocietyConfig, SudoConfig, SystemConfig,
-	TechnicalCommitteeConfig, wasm_binary_unwrap,
+	TechnicalCommitteeConfig, AssetsConfig, wasm_binary_unwrap,
 };
 use node_runtime::Block;
 use node_runtime::constants::currency::*;
@@ -238,6 +238,13 @@ pub fn testnet_genesis(
 
 	const ENDOWMENT: Balance = 10_000_000 * DOLLARS;
 	const STASH: Balance = 100 * DOLLARS;
+	// One unit of a test stablecoin with 12 decimals.
+	const STABLE: Balance = 1_000_000_000_000;
+	const STABLE_ENDOWMENT: Balance = 1_000_000 * STABLE;
+	let stablecoins = [
+		(1, &b"Test USD"[..], &b"tUSD"[..]),
+		(2, &b"Test EUR"[..], &b"tEUR"[..]),
+	];
 
 	GenesisConfig {
 		frame_system: Some(SystemConfig {
@@ -296,7 +303,7 @@ pub fn testnet_genesis(
 			},
 		}),
 		pallet_sudo: Some(SudoConfig {
-			key: root_key,
+			key: root_key.clone(),
 		}),
 		pallet_babe: Some(BabeConfig {
 			authorities: vec![],
@@ -321,6 +328,20 @@ pub fn testnet_genesis(
 			max_members: 999,
 		}),
 		pallet_vesting: Some(Default::default()),
+		subswap_asset: Some(AssetsConfig {
+			assets: stablecoins.iter()
+				.map(|(id, _, _)| (*id, root_key.clone(), STABLE / 100))
+				.collect(),
+			metadata: stablecoins.iter()
+				.map(|(id, name, symbol)| (*id, name.to_vec(), symbol.to_vec(), 12))
+				.collect(),
+			accounts: stablecoins.iter()
+				.flat_map(|(id, _, _)| {
+					endowed_accounts.iter().map(move |k| (*id, k.clone(), STABLE_ENDOWMENT))
+				})
+				.collect(),
+		}),
+		pallet_market: Some(Default::default()),
 	}
 }
 
@@ -0,0 +1,320 @@
+//! # Asset Transaction Payment Module
+//!
+//! Lets transactors pay transaction fees in an issued asset instead of the native currency.
+//!
+//! ## Overview
+//!
+//! The module provides the `ChargeAssetTxPayment` signed extension, a replacement for
+//! `pallet_transaction_payment::ChargeTransactionPayment`. The fee is still computed by
+//! `pallet_transaction_payment`, and without an asset the extension charges it in the native
+//! currency exactly like `ChargeTransactionPayment` does.
+//!
+//! When the transactor names an asset, the fee is converted into that asset at the average price
+//! of its pair with the native currency in the market module, and withdrawn in that asset. After
+//! dispatch, the part of the fee that was not used is refunded at the same rate and the rest is
+//! paid to the `FeeCollector` account.
+//!
+//! ### Terminology
+//!
+//! * **Average price:** The time-weighted average over at least `PriceWindow` of the ratio of the
+//!   two reserves of a market pair, from the pair's price oracle. Converting at it does not trade
+//!   against the pair, so paying fees leaves the pool untouched, and a trade just before the fee
+//!   is charged cannot move it. A pair younger than `PriceWindow` cannot be used to pay fees.
+//!
+//! ## Related Modules
+//!
+//! * [`Transaction Payment`](../pallet_transaction_payment/index.html)
+//! * [`Asset`](../subswap_asset/index.html)
+//! * [`Market`](../pallet_market/index.html)
+
+// Ensure we're `no_std` when compiling for Wasm.
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use asset::MultiCurrency;
+use codec::{Decode, Encode};
+use frame_support::{
+    decl_event, decl_module,
+    dispatch::DispatchResult,
+    traits::{Currency, ExistenceRequirement, Get, Imbalance, OnUnbalanced, WithdrawReason},
+    weights::{DispatchInfo, PostDispatchInfo},
+};
+use sp_runtime::{
+    helpers_128bit::multiply_by_rational,
+    traits::{
+        DispatchInfoOf, Dispatchable, One, PostDispatchInfoOf, SaturatedConversion, Saturating,
+        SignedExtension, Zero,
+    },
+    transaction_validity::{
+        InvalidTransaction, TransactionPriority, TransactionValidity, TransactionValidityError,
+        ValidTransaction,
+    },
+    FixedPointOperand,
+};
+
+#[cfg(test)]
+mod mock;
+
+#[cfg(test)]
+mod tests;
+
+type BalanceOf<T> = <<T as pallet_transaction_payment::Trait>::Currency as Currency<
+    <T as frame_system::Trait>::AccountId,
+>>::Balance;
+type NegativeImbalanceOf<T> = <<T as pallet_transaction_payment::Trait>::Currency as Currency<
+    <T as frame_system::Trait>::AccountId,
+>>::NegativeImbalance;
+type AssetIdOf<T> = <T as asset::Trait>::AssetId;
+type AssetBalanceOf<T> = <T as asset::Trait>::Balance;
+
+/// The module configuration trait.
+pub trait Trait: market::Trait + pallet_transaction_payment::Trait {
+    /// The overarching event type.
+    type Event: From<Event<Self>> + Into<<Self as frame_system::Trait>::Event>;
+
+    /// The account that receives the fees paid in issued assets.
+    type FeeCollector: Get<Self::AccountId>;
+
+    /// The least time over which the price that converts fees into an asset is averaged.
+    type PriceWindow: Get<Self::Moment>;
+}
+
+decl_event!(
+    pub enum Event<T>
+    where
+        AccountId = <T as frame_system::Trait>::AccountId,
+        AssetId = <T as asset::Trait>::AssetId,
+        Balance = <T as asset::Trait>::Balance,
+    {
+        /// A transaction fee was paid in an issued asset, and the unused part refunded.
+        /// \[who, asset_id, actual_fee, refund\]
+        AssetTxFeePaid(AccountId, AssetId, Balance, Balance),
+    }
+);
+
+decl_module! {
+    pub struct Module<T: Trait> for enum Call where origin: T::Origin {
+        fn deposit_event() = default;
+    }
+}
+
+/// The fee withdrawn from the transactor before dispatch.
+pub enum InitialPayment<T: Trait> {
+    /// No fee was charged.
+    Nothing,
+    /// The fee was withdrawn in the native currency.
+    Native(NegativeImbalanceOf<T>),
+    /// `paid` units of asset `id` were withdrawn for the fee.
+    Asset {
+        id: AssetIdOf<T>,
+        paid: AssetBalanceOf<T>,
+    },
+}
+
+impl<T: Trait> Default for InitialPayment<T> {
+    fn default() -> Self {
+        InitialPayment::Nothing
+    }
+}
+
+/// Require the transactor pay for themselves and maybe include a tip to gain additional priority
+/// in the queue. The fee is paid in the native currency, or in `asset_id` if it is given.
+#[derive(Encode, Decode, Clone, Eq, PartialEq)]
+pub struct ChargeAssetTxPayment<T: Trait> {
+    #[codec(compact)]
+    tip: BalanceOf<T>,
+    asset_id: Option<AssetIdOf<T>>,
+}
+
+impl<T: Trait + Send + Sync> ChargeAssetTxPayment<T>
+where
+    T::Call: Dispatchable<Info = DispatchInfo, PostInfo = PostDispatchInfo>,
+    BalanceOf<T>: Send + Sync + FixedPointOperand,
+{
+    /// Utility constructor. Used only in client/factory code.
+    pub fn from(tip: BalanceOf<T>, asset_id: Option<AssetIdOf<T>>) -> Self {
+        Self { tip, asset_id }
+    }
+
+    /// Withdraw the fee for a call of `info` and `len` from `who`, in the chosen asset.
+    ///
+    /// Returns the fee in the native currency and what was withdrawn for it.
+    fn withdraw_fee(
+        &self,
+        who: &T::AccountId,
+        info: &DispatchInfoOf<T::Call>,
+        len: usize,
+    ) -> Result<(BalanceOf<T>, InitialPayment<T>), TransactionValidityError> {
+        let fee = pallet_transaction_payment::Module::<T>::compute_fee(len as u32, info, self.tip);
+        if fee.is_zero() {
+            return Ok((fee, InitialPayment::Nothing));
+        }
+
+        match self.asset_id {
+            None => {
+                let reason = if self.tip.is_zero() {
+                    WithdrawReason::TransactionPayment.into()
+                } else {
+                    WithdrawReason::TransactionPayment | WithdrawReason::Tip
+                };
+                <T as pallet_transaction_payment::Trait>::Currency::withdraw(
+                    who,
+                    fee,
+                    reason,
+                    ExistenceRequirement::KeepAlive,
+                )
+                .map(|imbalance| (fee, InitialPayment::Native(imbalance)))
+                .map_err(|_| InvalidTransaction::Payment.into())
+            }
+            Some(id) => {
+                // A fee never rounds down to nothing, however valuable the asset.
+                let paid = market::Module::<T>::average_price(
+                    <T as asset::Trait>::NativeAssetId::get(),
+                    fee.saturated_into::<u128>().saturated_into(),
+                    id,
+                    T::PriceWindow::get(),
+                )
+                .ok_or(InvalidTransaction::Payment)?
+                .max(One::one());
+                <asset::Module<T> as MultiCurrency<T::AccountId>>::withdraw(id, who, paid)
+                    .map_err(|_| InvalidTransaction::Payment)?;
+                Ok((fee, InitialPayment::Asset { id, paid }))
+            }
+        }
+    }
+}
+
+impl<T: Trait + Send + Sync> sp_std::fmt::Debug for ChargeAssetTxPayment<T> {
+    #[cfg(feature = "std")]
+    fn fmt(&self, f: &mut sp_std::fmt::Formatter) -> sp_std::fmt::Result {
+        write!(f, "ChargeAssetTxPayment<{:?}, {:?}>", self.tip, self.asset_id)
+    }
+    #[cfg(not(feature = "std"))]
+    fn fmt(&self, _: &mut sp_std::fmt::Formatter) -> sp_std::fmt::Result {
+        Ok(())
+    }
+}
+
+impl<T: Trait + Send + Sync> SignedExtension for ChargeAssetTxPayment<T>
+where
+    BalanceOf<T>: Send + Sync + From<u64> + FixedPointOperand,
+    T::Call: Dispatchable<Info = DispatchInfo, PostInfo = PostDispatchInfo>,
+{
+    const IDENTIFIER: &'static str = "ChargeAssetTxPayment";
+    type AccountId = T::AccountId;
+    type Call = T::Call;
+    type AdditionalSigned = ();
+    type Pre = (
+        // tip
+        BalanceOf<T>,
+        // who paid the fee
+        Self::AccountId,
+        // what was withdrawn for the fee
+        InitialPayment<T>,
+        // the fee in the native currency
+        BalanceOf<T>,
+    );
+
+    fn additional_signed(&self) -> Result<(), TransactionValidityError> {
+        Ok(())
+    }
+
+    fn validate(
+        &self,
+        who: &Self::AccountId,
+        _call: &Self::Call,
+        info: &DispatchInfoOf<Self::Call>,
+        len: usize,
+    ) -> TransactionValidity {
+        let (fee, _) = self.withdraw_fee(who, info, len)?;
+        // The priority follows the native fee, whichever asset it is paid in.
+        Ok(ValidTransaction {
+            priority: fee.saturated_into::<TransactionPriority>(),
+            ..Default::default()
+        })
+    }
+
+    fn pre_dispatch(
+        self,
+        who: &Self::AccountId,
+        _call: &Self::Call,
+        info: &DispatchInfoOf<Self::Call>,
+        len: usize,
+    ) -> Result<Self::Pre, TransactionValidityError> {
+        let (fee, initial_payment) = self.withdraw_fee(who, info, len)?;
+        Ok((self.tip, who.clone(), initial_payment, fee))
+    }
+
+    fn post_dispatch(
+        pre: Self::Pre,
+        info: &DispatchInfoOf<Self::Call>,
+        post_info: &PostDispatchInfoOf<Self::Call>,
+        len: usize,
+        _result: &DispatchResult,
+    ) -> Result<(), TransactionValidityError> {
+        let (tip, who, initial_payment, fee) = pre;
+        let actual_fee = pallet_transaction_payment::Module::<T>::compute_actual_fee(
+            len as u32,
+            info,
+            post_info,
+            tip,
+        );
+        let refund = fee.saturating_sub(actual_fee);
+
+        match initial_payment {
+            InitialPayment::Nothing => {}
+            InitialPayment::Native(paid) => {
+                let refund_imbalance =
+                    <T as pallet_transaction_payment::Trait>::Currency::deposit_into_existing(
+                        &who, refund,
+                    );
+                let actual_payment = match refund_imbalance {
+                    Ok(refund_imbalance) => paid
+                        .offset(refund_imbalance)
+                        .map_err(|_| InvalidTransaction::Payment)?,
+                    // The account was reaped during dispatch, so the refund is burned.
+                    Err(_) => paid,
+                };
+                let (tip, fee) = actual_payment.split(tip);
+                T::OnTransactionPayment::on_unbalanceds(Some(fee).into_iter().chain(Some(tip)));
+            }
+            InitialPayment::Asset { id, paid } => {
+                // Refund at the rate the fee was charged at, whatever the pair did meanwhile.
+                let mut asset_refund: AssetBalanceOf<T> = multiply_by_rational(
+                    paid.saturated_into(),
+                    refund.saturated_into(),
+                    fee.saturated_into(),
+                )
+                .map(|amount| amount.saturated_into())
+                .unwrap_or_else(|_| Zero::zero());
+                if !asset_refund.is_zero()
+                    && <asset::Module<T> as MultiCurrency<T::AccountId>>::deposit(
+                        id,
+                        &who,
+                        asset_refund,
+                    )
+                    .is_err()
+                {
+                    asset_refund = Zero::zero();
+                }
+
+                let actual_payment = paid.saturating_sub(asset_refund);
+                if !actual_payment.is_zero() {
+                    // The transaction is already included, so a fee the collector cannot
+                    // receive is burned rather than failing it.
+                    let _ = <asset::Module<T> as MultiCurrency<T::AccountId>>::deposit(
+                        id,
+                        &T::FeeCollector::get(),
+                        actual_payment,
+                    );
+                }
+                Module::<T>::deposit_event(RawEvent::AssetTxFeePaid(
+                    who,
+                    id,
+                    actual_payment,
+                    asset_refund,
+                ));
+            }
+        }
+        Ok(())
+    }
+}
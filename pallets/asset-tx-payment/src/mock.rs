@@ -0,0 +1,182 @@
+use crate::Trait;
+use frame_support::{
+	impl_outer_dispatch, impl_outer_origin, parameter_types,
+	weights::{DispatchInfo, IdentityFee, PostDispatchInfo, Weight},
+};
+use frame_system as system;
+use sp_core::H256;
+use sp_runtime::{
+	testing::Header,
+	traits::{BlakeTwo256, ConvertInto, IdentityLookup},
+	ModuleId, Perbill, Permill,
+};
+
+impl_outer_origin! {
+	pub enum Origin for Test {}
+}
+
+impl_outer_dispatch! {
+	pub enum Call for Test where origin: Origin {
+		pallet_balances::Balances,
+	}
+}
+
+// Configure a mock runtime to test the pallet.
+
+#[derive(Clone, Eq, PartialEq)]
+pub struct Test;
+parameter_types! {
+	pub const BlockHashCount: u64 = 250;
+	pub const MaximumBlockWeight: Weight = 1024;
+	pub const MaximumBlockLength: u32 = 2 * 1024;
+	pub const AvailableBlockRatio: Perbill = Perbill::from_percent(75);
+	pub const ExistentialDeposit: u128 = 1;
+	pub const TransactionByteFee: u128 = 1;
+	pub const MinimumPeriod: u64 = 1;
+	pub const MetadataDepositBase: u128 = 10;
+	pub const MetadataDepositPerByte: u128 = 1;
+	pub const StringLimit: u32 = 16;
+	pub const NativeAssetId: u32 = u32::max_value();
+	pub const AssetModuleId: ModuleId = ModuleId(*b"py/asset");
+	pub const MaxBatchSize: u32 = 4;
+	pub const MaxAttributes: u32 = 2;
+	pub const MaxVestingSchedules: u32 = 2;
+	pub const MinVestedTransferFactor: u32 = 10;
+	pub const SwapFee: Permill = Permill::from_perthousand(3);
+	pub const MaxPathLength: u32 = 4;
+	pub const ObservationPeriod: u64 = 10;
+	pub const MaxObservations: u32 = 4;
+	pub const MaxStableAssets: u32 = 4;
+	pub const MaxAmplification: u32 = 1_000_000;
+	pub const MinRampTime: u64 = 100;
+	pub const MaxWeightedAssets: u32 = 8;
+	pub const MaxOrders: u32 = 4;
+	pub const OrderDeposit: u128 = 10;
+	pub const FeeCollector: u64 = 99;
+	pub const PriceWindow: u64 = 20;
+}
+
+impl system::Trait for Test {
+	type BaseCallFilter = ();
+	type Origin = Origin;
+	type Call = Call;
+	type Index = u64;
+	type BlockNumber = u64;
+	type Hash = H256;
+	type Hashing = BlakeTwo256;
+	type AccountId = u64;
+	type Lookup = IdentityLookup<Self::AccountId>;
+	type Header = Header;
+	type Event = ();
+	type BlockHashCount = BlockHashCount;
+	type MaximumBlockWeight = MaximumBlockWeight;
+	type DbWeight = ();
+	type BlockExecutionWeight = ();
+	type ExtrinsicBaseWeight = ();
+	type MaximumExtrinsicWeight = MaximumBlockWeight;
+	type MaximumBlockLength = MaximumBlockLength;
+	type AvailableBlockRatio = AvailableBlockRatio;
+	type Version = ();
+	type ModuleToIndex = ();
+	type AccountData = pallet_balances::AccountData<u128>;
+	type OnNewAccount = ();
+	type OnKilledAccount = ();
+	type SystemWeightInfo = ();
+}
+
+impl pallet_balances::Trait for Test {
+	type Balance = u128;
+	type Event = ();
+	type DustRemoval = ();
+	type ExistentialDeposit = ExistentialDeposit;
+	type AccountStore = System;
+	type WeightInfo = ();
+}
+
+impl pallet_timestamp::Trait for Test {
+	type Moment = u64;
+	type OnTimestampSet = ();
+	type MinimumPeriod = MinimumPeriod;
+	type WeightInfo = ();
+}
+
+impl pallet_transaction_payment::Trait for Test {
+	type Currency = Balances;
+	type OnTransactionPayment = ();
+	type TransactionByteFee = TransactionByteFee;
+	type WeightToFee = IdentityFee<u128>;
+	type FeeMultiplierUpdate = ();
+}
+
+impl asset::Trait for Test {
+	type Event = ();
+	type Balance = u128;
+	type AssetId = u32;
+	type Currency = Balances;
+	type NativeAssetId = NativeAssetId;
+	type ModuleId = AssetModuleId;
+	type MetadataDepositBase = MetadataDepositBase;
+	type MetadataDepositPerByte = MetadataDepositPerByte;
+	type StringLimit = StringLimit;
+	type OnDust = ();
+	type MaxBatchSize = MaxBatchSize;
+	type ItemId = u32;
+	type MaxAttributes = MaxAttributes;
+	type BlockNumberToBalance = ConvertInto;
+	type MaxVestingSchedules = MaxVestingSchedules;
+	type MinVestedTransferFactor = MinVestedTransferFactor;
+	type WeightInfo = ();
+}
+
+impl market::Trait for Test {
+	type Event = ();
+	type SwapFee = SwapFee;
+	type MaxPathLength = MaxPathLength;
+	type ObservationPeriod = ObservationPeriod;
+	type MaxObservations = MaxObservations;
+	type FeeToOrigin = system::EnsureRoot<u64>;
+	type MaxStableAssets = MaxStableAssets;
+	type MaxAmplification = MaxAmplification;
+	type MinRampTime = MinRampTime;
+	type MaxWeightedAssets = MaxWeightedAssets;
+	type MaxOrders = MaxOrders;
+	type OrderDeposit = OrderDeposit;
+}
+
+impl Trait for Test {
+	type Event = ();
+	type FeeCollector = FeeCollector;
+	type PriceWindow = PriceWindow;
+}
+
+pub type System = system::Module<Test>;
+pub type Balances = pallet_balances::Module<Test>;
+pub type Assets = asset::Module<Test>;
+pub type Market = market::Module<Test>;
+pub type Timestamp = pallet_timestamp::Module<Test>;
+
+pub const CALL: &<Test as system::Trait>::Call =
+	&Call::Balances(pallet_balances::Call::transfer(2, 69));
+
+/// Dispatch info of a call of weight `w`.
+pub fn info_from_weight(w: Weight) -> DispatchInfo {
+	DispatchInfo { weight: w, ..Default::default() }
+}
+
+/// Post dispatch info of a call that used weight `w`.
+pub fn post_info_from_weight(w: Weight) -> PostDispatchInfo {
+	Some(w).into()
+}
+
+// Build genesis storage according to the mock runtime.
+pub fn new_test_ext() -> sp_io::TestExternalities {
+	let mut t = system::GenesisConfig::default()
+		.build_storage::<Test>()
+		.unwrap();
+	pallet_balances::GenesisConfig::<Test> {
+		balances: vec![(1, 10_000), (3, 100)],
+	}
+	.assimilate_storage(&mut t)
+	.unwrap();
+	t.into()
+}
@@ -0,0 +1,158 @@
+use crate::{mock::*, ChargeAssetTxPayment};
+use frame_support::{assert_ok, traits::Get};
+use sp_runtime::{
+	traits::SignedExtension,
+	transaction_validity::{InvalidTransaction, TransactionValidityError},
+};
+
+/// Issue asset 0 from account 1, pool 2000 of it against 1000 of the native currency, and give
+/// 100 of it to account 2, which holds no native currency. The pair is then `PriceWindow` old.
+fn setup_pool() {
+	assert_ok!(Assets::issue(Origin::signed(1), 10_000));
+	assert_ok!(Market::mint_liquidity(
+		Origin::signed(1),
+		0,
+		2000,
+		NativeAssetId::get(),
+		1000,
+		0,
+		0,
+		u64::max_value()
+	));
+	assert_ok!(Assets::transfer(Origin::signed(1), 0, 2, 100));
+	Timestamp::set_timestamp(PriceWindow::get());
+}
+
+#[test]
+fn native_fee_should_be_charged_and_refunded() {
+	new_test_ext().execute_with(|| {
+		setup_pool();
+		assert_eq!(Balances::free_balance(1), 9_000);
+
+		// len 10 and weight 5 cost 15.
+		let pre = ChargeAssetTxPayment::<Test>::from(0, None)
+			.pre_dispatch(&1, CALL, &info_from_weight(5), 10)
+			.unwrap();
+		assert_eq!(Balances::free_balance(1), 9_000 - 15);
+
+		assert_ok!(ChargeAssetTxPayment::<Test>::post_dispatch(
+			pre,
+			&info_from_weight(5),
+			&post_info_from_weight(3),
+			10,
+			&Ok(())
+		));
+		assert_eq!(Balances::free_balance(1), 9_000 - 13);
+	});
+}
+
+#[test]
+fn asset_fee_should_be_converted_at_average_price() {
+	new_test_ext().execute_with(|| {
+		setup_pool();
+		assert_eq!(Balances::free_balance(2), 0);
+
+		// A native fee of 15 costs 30 of asset 0 at 2 asset per native unit.
+		let pre = ChargeAssetTxPayment::<Test>::from(0, Some(0))
+			.pre_dispatch(&2, CALL, &info_from_weight(5), 10)
+			.unwrap();
+		assert_eq!(Assets::balance(0, 2), 70);
+
+		// 2 of the native fee were unused, refunded as 4 of asset 0.
+		assert_ok!(ChargeAssetTxPayment::<Test>::post_dispatch(
+			pre,
+			&info_from_weight(5),
+			&post_info_from_weight(3),
+			10,
+			&Ok(())
+		));
+		assert_eq!(Assets::balance(0, 2), 74);
+		assert_eq!(Assets::balance(0, FeeCollector::get()), 26);
+		assert_eq!(Balances::free_balance(2), 0);
+		// The fee was not traded against the pool.
+		assert_eq!(Market::reserve_price(0, 2000, NativeAssetId::get()), Some(1000));
+	});
+}
+
+#[test]
+fn asset_fee_should_fail_without_pair_or_balance() {
+	new_test_ext().execute_with(|| {
+		setup_pool();
+		let payment_error = Err(TransactionValidityError::Invalid(InvalidTransaction::Payment));
+
+		// Asset 1 is the liquidity token of the pool, which has no pair with the native currency.
+		assert_ok!(Assets::transfer(Origin::signed(1), 1, 2, 100));
+		assert_eq!(
+			ChargeAssetTxPayment::<Test>::from(0, Some(1))
+				.pre_dispatch(&2, CALL, &info_from_weight(5), 10)
+				.map(drop),
+			payment_error
+		);
+		// Account 3 holds native currency but none of asset 0.
+		assert_eq!(
+			ChargeAssetTxPayment::<Test>::from(0, Some(0))
+				.validate(&3, CALL, &info_from_weight(5), 10)
+				.map(drop),
+			payment_error
+		);
+		assert_eq!(Balances::free_balance(3), 100);
+
+		// A pair younger than the price window has no average price yet.
+		assert_ok!(Assets::issue(Origin::signed(1), 10_000));
+		assert_ok!(Market::mint_liquidity(
+			Origin::signed(1),
+			2,
+			1000,
+			NativeAssetId::get(),
+			1000,
+			0,
+			0,
+			u64::max_value()
+		));
+		assert_ok!(Assets::transfer(Origin::signed(1), 2, 2, 100));
+		assert_eq!(
+			ChargeAssetTxPayment::<Test>::from(0, Some(2))
+				.pre_dispatch(&2, CALL, &info_from_weight(5), 10)
+				.map(drop),
+			payment_error
+		);
+	});
+}
+
+#[test]
+fn asset_fee_should_ignore_a_trade_just_before_it() {
+	new_test_ext().execute_with(|| {
+		setup_pool();
+		// Dumping asset 0 into the pair makes it cheap at the reserve price.
+		assert_ok!(Market::swap(
+			Origin::signed(1),
+			0,
+			2000,
+			NativeAssetId::get(),
+			0,
+			u64::max_value()
+		));
+		assert!(Market::reserve_price(NativeAssetId::get(), 15, 0).unwrap() > 100);
+
+		// The fee is still converted at the average price of 2 asset per native unit.
+		ChargeAssetTxPayment::<Test>::from(0, Some(0))
+			.pre_dispatch(&2, CALL, &info_from_weight(5), 10)
+			.unwrap();
+		assert_eq!(Assets::balance(0, 2), 70);
+	});
+}
+
+#[test]
+fn priority_should_follow_native_fee() {
+	new_test_ext().execute_with(|| {
+		setup_pool();
+		let native = ChargeAssetTxPayment::<Test>::from(5, None)
+			.validate(&1, CALL, &info_from_weight(5), 10)
+			.unwrap();
+		let asset = ChargeAssetTxPayment::<Test>::from(5, Some(0))
+			.validate(&2, CALL, &info_from_weight(5), 10)
+			.unwrap();
+		assert_eq!(native.priority, 20);
+		assert_eq!(asset.priority, 20);
+	});
+}
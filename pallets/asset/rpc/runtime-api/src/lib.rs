@@ -0,0 +1,27 @@
+//! Runtime API definition for the asset pallet.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use codec::Codec;
+use sp_std::prelude::*;
+
+sp_api::decl_runtime_apis! {
+    /// Read access to the balances and metadata of issued assets.
+    pub trait AssetApi<AccountId, AssetId, Balance> where
+        AccountId: Codec,
+        AssetId: Codec,
+        Balance: Codec,
+    {
+        /// The free balance of `who` in asset `id`.
+        fn balance(id: AssetId, who: AccountId) -> Balance;
+
+        /// The total supply of asset `id`.
+        fn total_supply(id: AssetId) -> Balance;
+
+        /// The name, symbol and decimals of asset `id`, if set.
+        fn metadata(id: AssetId) -> Option<(Vec<u8>, Vec<u8>, u8)>;
+
+        /// The free balance of `who` in every asset it holds.
+        fn account_balances(who: AccountId) -> Vec<(AssetId, Balance)>;
+    }
+}
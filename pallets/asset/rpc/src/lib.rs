@@ -0,0 +1,103 @@
+//! RPC interface for the asset pallet.
+
+use std::sync::Arc;
+
+use codec::Codec;
+use jsonrpc_core::{Error as RpcError, ErrorCode, Result};
+use jsonrpc_derive::rpc;
+use serde::{de::DeserializeOwned, Serialize};
+use sp_api::ProvideRuntimeApi;
+use sp_blockchain::HeaderBackend;
+use sp_runtime::{generic::BlockId, traits::Block as BlockT};
+
+pub use subswap_asset_rpc_runtime_api::AssetApi as AssetRuntimeApi;
+
+/// The code of the error returned when the runtime API call fails.
+const RUNTIME_ERROR: i64 = 1;
+
+/// Asset RPC methods.
+#[rpc]
+pub trait AssetApi<BlockHash, AccountId, AssetId, Balance> {
+    /// Returns the free balance of `who` in asset `id`.
+    #[rpc(name = "asset_balance")]
+    fn balance(&self, id: AssetId, who: AccountId, at: Option<BlockHash>) -> Result<Balance>;
+
+    /// Returns the free balance of `who` in every asset it holds.
+    #[rpc(name = "asset_accountBalances")]
+    fn account_balances(
+        &self,
+        who: AccountId,
+        at: Option<BlockHash>,
+    ) -> Result<Vec<(AssetId, Balance)>>;
+
+    /// Returns the total supply of asset `id`.
+    #[rpc(name = "asset_totalSupply")]
+    fn total_supply(&self, id: AssetId, at: Option<BlockHash>) -> Result<Balance>;
+}
+
+/// An implementation of asset specific RPC methods.
+pub struct Asset<C, B> {
+    client: Arc<C>,
+    _marker: std::marker::PhantomData<B>,
+}
+
+impl<C, B> Asset<C, B> {
+    /// Create a new `Asset` with the given reference to the client.
+    pub fn new(client: Arc<C>) -> Self {
+        Asset {
+            client,
+            _marker: Default::default(),
+        }
+    }
+}
+
+/// Map a runtime API error into an RPC error.
+fn runtime_error_into_rpc_err(err: impl std::fmt::Debug) -> RpcError {
+    RpcError {
+        code: ErrorCode::ServerError(RUNTIME_ERROR),
+        message: "Runtime trapped".into(),
+        data: Some(format!("{:?}", err).into()),
+    }
+}
+
+impl<C, Block, AccountId, AssetId, Balance>
+    AssetApi<<Block as BlockT>::Hash, AccountId, AssetId, Balance> for Asset<C, Block>
+where
+    Block: BlockT,
+    C: Send + Sync + 'static + ProvideRuntimeApi<Block> + HeaderBackend<Block>,
+    C::Api: AssetRuntimeApi<Block, AccountId, AssetId, Balance>,
+    AccountId: Codec + DeserializeOwned,
+    AssetId: Codec + Serialize + DeserializeOwned,
+    Balance: Codec + Serialize,
+{
+    fn balance(
+        &self,
+        id: AssetId,
+        who: AccountId,
+        at: Option<<Block as BlockT>::Hash>,
+    ) -> Result<Balance> {
+        let api = self.client.runtime_api();
+        let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+        api.balance(&at, id, who).map_err(runtime_error_into_rpc_err)
+    }
+
+    fn account_balances(
+        &self,
+        who: AccountId,
+        at: Option<<Block as BlockT>::Hash>,
+    ) -> Result<Vec<(AssetId, Balance)>> {
+        let api = self.client.runtime_api();
+        let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+        api.account_balances(&at, who).map_err(runtime_error_into_rpc_err)
+    }
+
+    fn total_supply(
+        &self,
+        id: AssetId,
+        at: Option<<Block as BlockT>::Hash>,
+    ) -> Result<Balance> {
+        let api = self.client.runtime_api();
+        let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+        api.total_supply(&at, id).map_err(runtime_error_into_rpc_err)
+    }
+}
@@ -0,0 +1,534 @@
+//! Benchmarks for the asset pallet.
+
+#![cfg(feature = "runtime-benchmarks")]
+
+use super::*;
+
+use frame_benchmarking::{account, benchmarks, whitelisted_caller};
+use frame_system::RawOrigin;
+use sp_runtime::traits::Bounded;
+use sp_std::vec;
+
+const SEED: u32 = 0;
+
+/// Issue `total` units of a new asset from the whitelisted caller and return the caller, its
+/// lookup source and the new asset identifier.
+fn create_default_asset<T: Trait>(
+    total: T::Balance,
+) -> (T::AccountId, <T::Lookup as StaticLookup>::Source, T::AssetId) {
+    let caller: T::AccountId = whitelisted_caller();
+    let caller_lookup = T::Lookup::unlookup(caller.clone());
+    assert!(Module::<T>::issue(RawOrigin::Signed(caller.clone()).into(), total).is_ok());
+    let id = Module::<T>::next_asset_id() - One::one();
+    (caller, caller_lookup, id)
+}
+
+/// Give `who` enough of the native currency to pay any metadata deposit.
+fn fund<T: Trait>(who: &T::AccountId) {
+    T::Currency::make_free_balance_be(who, DepositBalanceOf::<T>::max_value() / 2u32.into());
+}
+
+/// Set the largest allowed metadata on asset `id` created by `caller`.
+fn set_default_metadata<T: Trait>(caller: &T::AccountId, id: T::AssetId) {
+    let limit = T::StringLimit::get() as usize;
+    assert!(Module::<T>::set_metadata(
+        RawOrigin::Signed(caller.clone()).into(),
+        id,
+        vec![0u8; limit],
+        vec![0u8; limit],
+        12,
+    )
+    .is_ok());
+}
+
+/// Create a non-fungible class from the whitelisted caller and return the caller, its lookup
+/// source and the new class identifier.
+fn create_default_class<T: Trait>(
+) -> (T::AccountId, <T::Lookup as StaticLookup>::Source, T::AssetId) {
+    let caller: T::AccountId = whitelisted_caller();
+    let caller_lookup = T::Lookup::unlookup(caller.clone());
+    assert!(Module::<T>::create_class(RawOrigin::Signed(caller.clone()).into()).is_ok());
+    let id = Module::<T>::next_asset_id() - One::one();
+    (caller, caller_lookup, id)
+}
+
+/// Mint item zero of class `id` to its issuer `caller`.
+fn mint_default_item<T: Trait>(caller: &T::AccountId, id: T::AssetId) -> T::ItemId {
+    let item: T::ItemId = Zero::zero();
+    assert!(Module::<T>::mint_item(
+        RawOrigin::Signed(caller.clone()).into(),
+        id,
+        item,
+        T::Lookup::unlookup(caller.clone()),
+    )
+    .is_ok());
+    item
+}
+
+benchmarks! {
+    _ { }
+
+    issue {
+        let caller: T::AccountId = whitelisted_caller();
+        let total: T::Balance = 1_000_000u32.into();
+    }: _(RawOrigin::Signed(caller.clone()), total)
+    verify {
+        let id = Module::<T>::next_asset_id() - One::one();
+        assert_eq!(Module::<T>::balance(id, caller), total);
+    }
+
+    transfer {
+        let (caller, _, id) = create_default_asset::<T>(1_000_000u32.into());
+        let target: T::AccountId = account("target", 0, SEED);
+        let target_lookup = T::Lookup::unlookup(target.clone());
+        let amount: T::Balance = 500_000u32.into();
+    }: _(RawOrigin::Signed(caller.clone()), id, target_lookup, amount)
+    verify {
+        assert_eq!(Module::<T>::balance(id, target), amount);
+    }
+
+    transfer_keep_alive {
+        let (caller, _, id) = create_default_asset::<T>(1_000_000u32.into());
+        let target: T::AccountId = account("target", 0, SEED);
+        let target_lookup = T::Lookup::unlookup(target.clone());
+        let amount: T::Balance = 500_000u32.into();
+    }: _(RawOrigin::Signed(caller.clone()), id, target_lookup, amount)
+    verify {
+        assert_eq!(Module::<T>::balance(id, target), amount);
+    }
+
+    transfer_batch {
+        let n in 1 .. T::MaxBatchSize::get();
+
+        let (caller, _, id) = create_default_asset::<T>(1_000_000u32.into());
+        let amount: T::Balance = 100u32.into();
+        let targets = (0..n)
+            .map(|i| (T::Lookup::unlookup(account("target", i, SEED)), amount))
+            .collect::<Vec<_>>();
+    }: _(RawOrigin::Signed(caller.clone()), id, targets)
+    verify {
+        let last: T::AccountId = account("target", n - 1, SEED);
+        assert_eq!(Module::<T>::balance(id, last), amount);
+    }
+
+    airdrop {
+        let n in 1 .. T::MaxBatchSize::get();
+
+        let (caller, _, id) = create_default_asset::<T>(1_000_000u32.into());
+        let amount: T::Balance = 100u32.into();
+        let recipients = (0..n)
+            .map(|i| (T::Lookup::unlookup(account("target", i, SEED)), amount))
+            .collect::<Vec<_>>();
+    }: _(RawOrigin::Signed(caller.clone()), id, recipients)
+    verify {
+        let last: T::AccountId = account("target", n - 1, SEED);
+        assert_eq!(Module::<T>::balance(id, last), amount);
+    }
+
+    set_min_balance {
+        let (caller, _, id) = create_default_asset::<T>(1_000_000u32.into());
+        let min_balance: T::Balance = 100u32.into();
+    }: _(RawOrigin::Signed(caller), id, min_balance)
+    verify {
+        assert_eq!(Module::<T>::min_balance(id), min_balance);
+    }
+
+    set_team {
+        let (caller, _, id) = create_default_asset::<T>(1_000_000u32.into());
+        let issuer: T::AccountId = account("issuer", 0, SEED);
+        let admin: T::AccountId = account("admin", 0, SEED);
+        let freezer: T::AccountId = account("freezer", 0, SEED);
+    }: _(
+        RawOrigin::Signed(caller),
+        id,
+        T::Lookup::unlookup(issuer.clone()),
+        T::Lookup::unlookup(admin),
+        T::Lookup::unlookup(freezer)
+    )
+    verify {
+        assert_eq!(Module::<T>::roles(id).map(|r| r.issuer), Some(issuer));
+    }
+
+    transfer_ownership {
+        let (caller, _, id) = create_default_asset::<T>(1_000_000u32.into());
+        fund::<T>(&caller);
+        set_default_metadata::<T>(&caller, id);
+        let owner: T::AccountId = account("owner", 0, SEED);
+        fund::<T>(&owner);
+        let owner_lookup = T::Lookup::unlookup(owner.clone());
+    }: _(RawOrigin::Signed(caller), id, owner_lookup)
+    verify {
+        assert_eq!(<Creator<T>>::get(id), owner);
+    }
+
+    freeze {
+        let (caller, caller_lookup, id) = create_default_asset::<T>(1_000_000u32.into());
+    }: _(RawOrigin::Signed(caller.clone()), id, caller_lookup)
+    verify {
+        assert!(Module::<T>::is_frozen(id, &caller));
+    }
+
+    thaw {
+        let (caller, caller_lookup, id) = create_default_asset::<T>(1_000_000u32.into());
+        assert!(Module::<T>::freeze(
+            RawOrigin::Signed(caller.clone()).into(),
+            id,
+            caller_lookup.clone(),
+        )
+        .is_ok());
+    }: _(RawOrigin::Signed(caller.clone()), id, caller_lookup)
+    verify {
+        assert!(!Module::<T>::is_frozen(id, &caller));
+    }
+
+    freeze_asset {
+        let (caller, _, id) = create_default_asset::<T>(1_000_000u32.into());
+    }: _(RawOrigin::Signed(caller.clone()), id)
+    verify {
+        assert!(Module::<T>::is_frozen(id, &caller));
+    }
+
+    thaw_asset {
+        let (caller, _, id) = create_default_asset::<T>(1_000_000u32.into());
+        assert!(Module::<T>::freeze_asset(RawOrigin::Signed(caller.clone()).into(), id).is_ok());
+    }: _(RawOrigin::Signed(caller.clone()), id)
+    verify {
+        assert!(!Module::<T>::is_frozen(id, &caller));
+    }
+
+    force_transfer {
+        let (caller, caller_lookup, id) = create_default_asset::<T>(1_000_000u32.into());
+        let target: T::AccountId = account("target", 0, SEED);
+        let target_lookup = T::Lookup::unlookup(target.clone());
+        let amount: T::Balance = 500_000u32.into();
+    }: _(RawOrigin::Signed(caller), id, caller_lookup, target_lookup, amount)
+    verify {
+        assert_eq!(Module::<T>::balance(id, target), amount);
+    }
+
+    mint {
+        let (caller, _, id) = create_default_asset::<T>(1_000_000u32.into());
+        let target: T::AccountId = account("target", 0, SEED);
+        let target_lookup = T::Lookup::unlookup(target.clone());
+        let amount: T::Balance = 500_000u32.into();
+    }: _(RawOrigin::Signed(caller), id, target_lookup, amount)
+    verify {
+        assert_eq!(Module::<T>::balance(id, target), amount);
+    }
+
+    burn {
+        let (caller, caller_lookup, id) = create_default_asset::<T>(1_000_000u32.into());
+        let amount: T::Balance = 500_000u32.into();
+    }: _(RawOrigin::Signed(caller.clone()), id, caller_lookup, amount)
+    verify {
+        assert_eq!(Module::<T>::balance(id, caller), amount);
+    }
+
+    destroy {
+        let (caller, _, id) = create_default_asset::<T>(1_000_000u32.into());
+    }: _(RawOrigin::Signed(caller.clone()), id)
+    verify {
+        assert!(Module::<T>::balance(id, caller).is_zero());
+    }
+
+    approve_transfer {
+        let (caller, _, id) = create_default_asset::<T>(1_000_000u32.into());
+        let delegate: T::AccountId = account("delegate", 0, SEED);
+        let delegate_lookup = T::Lookup::unlookup(delegate.clone());
+        let amount: T::Balance = 500_000u32.into();
+    }: _(RawOrigin::Signed(caller.clone()), id, delegate_lookup, amount)
+    verify {
+        assert_eq!(Module::<T>::allowance(id, caller, delegate), amount);
+    }
+
+    cancel_approval {
+        let (caller, _, id) = create_default_asset::<T>(1_000_000u32.into());
+        let delegate: T::AccountId = account("delegate", 0, SEED);
+        let delegate_lookup = T::Lookup::unlookup(delegate.clone());
+        assert!(Module::<T>::approve_transfer(
+            RawOrigin::Signed(caller.clone()).into(),
+            id,
+            delegate_lookup.clone(),
+            500_000u32.into(),
+        )
+        .is_ok());
+    }: _(RawOrigin::Signed(caller.clone()), id, delegate_lookup)
+    verify {
+        assert!(Module::<T>::allowance(id, caller, delegate).is_zero());
+    }
+
+    transfer_approved {
+        let (caller, caller_lookup, id) = create_default_asset::<T>(1_000_000u32.into());
+        let delegate: T::AccountId = account("delegate", 0, SEED);
+        let delegate_lookup = T::Lookup::unlookup(delegate.clone());
+        let amount: T::Balance = 500_000u32.into();
+        assert!(Module::<T>::approve_transfer(
+            RawOrigin::Signed(caller.clone()).into(),
+            id,
+            delegate_lookup,
+            amount,
+        )
+        .is_ok());
+        let target: T::AccountId = account("target", 0, SEED);
+        let target_lookup = T::Lookup::unlookup(target.clone());
+    }: _(RawOrigin::Signed(delegate), id, caller_lookup, target_lookup, amount)
+    verify {
+        assert_eq!(Module::<T>::balance(id, target), amount);
+    }
+
+    set_metadata {
+        let n in 0 .. T::StringLimit::get();
+        let s in 0 .. T::StringLimit::get();
+
+        let (caller, _, id) = create_default_asset::<T>(1_000_000u32.into());
+        fund::<T>(&caller);
+        let name = vec![0u8; n as usize];
+        let symbol = vec![0u8; s as usize];
+    }: _(RawOrigin::Signed(caller), id, name.clone(), symbol, 12)
+    verify {
+        assert_eq!(Module::<T>::metadata(id).map(|m| m.name), Some(name));
+    }
+
+    clear_metadata {
+        let (caller, _, id) = create_default_asset::<T>(1_000_000u32.into());
+        fund::<T>(&caller);
+        set_default_metadata::<T>(&caller, id);
+    }: _(RawOrigin::Signed(caller), id)
+    verify {
+        assert!(Module::<T>::metadata(id).is_none());
+    }
+
+    start_destroy {
+        let (caller, _, id) = create_default_asset::<T>(1_000_000u32.into());
+    }: _(RawOrigin::Signed(caller), id)
+    verify {
+        assert!(Module::<T>::is_destroying(id));
+    }
+
+    destroy_accounts {
+        let n in 1 .. 1_000;
+
+        let (caller, _, id) = create_default_asset::<T>(1_000_000u32.into());
+        for i in 1..n {
+            let who: T::AccountId = account("holder", i, SEED);
+            assert!(Module::<T>::mint_from_system(&id, &who, &100u32.into()).is_ok());
+        }
+        assert!(Module::<T>::start_destroy(RawOrigin::Signed(caller.clone()).into(), id).is_ok());
+    }: _(RawOrigin::Signed(caller), id, n)
+    verify {
+        assert!(Module::<T>::total_supply(id).is_zero());
+    }
+
+    finish_destroy {
+        let (caller, _, id) = create_default_asset::<T>(1_000_000u32.into());
+        fund::<T>(&caller);
+        set_default_metadata::<T>(&caller, id);
+        assert!(Module::<T>::start_destroy(RawOrigin::Signed(caller.clone()).into(), id).is_ok());
+        assert!(Module::<T>::destroy_accounts(RawOrigin::Signed(caller.clone()).into(), id, 1).is_ok());
+    }: _(RawOrigin::Signed(caller), id)
+    verify {
+        assert!(Module::<T>::roles(id).is_none());
+    }
+
+    create_class {
+        let caller: T::AccountId = whitelisted_caller();
+    }: _(RawOrigin::Signed(caller))
+    verify {
+        let id = Module::<T>::next_asset_id() - One::one();
+        assert!(Module::<T>::is_non_fungible(id));
+    }
+
+    mint_item {
+        let (caller, caller_lookup, id) = create_default_class::<T>();
+    }: _(RawOrigin::Signed(caller.clone()), id, Zero::zero(), caller_lookup)
+    verify {
+        assert_eq!(Module::<T>::owner_of(id, Zero::zero()), Some(caller));
+    }
+
+    transfer_item {
+        let (caller, _, id) = create_default_class::<T>();
+        let item = mint_default_item::<T>(&caller, id);
+        let target: T::AccountId = account("target", 0, SEED);
+        let target_lookup = T::Lookup::unlookup(target.clone());
+    }: _(RawOrigin::Signed(caller), id, item, target_lookup)
+    verify {
+        assert_eq!(Module::<T>::owner_of(id, item), Some(target));
+    }
+
+    approve_item {
+        let (caller, _, id) = create_default_class::<T>();
+        let item = mint_default_item::<T>(&caller, id);
+        let delegate: T::AccountId = account("delegate", 0, SEED);
+        let delegate_lookup = T::Lookup::unlookup(delegate.clone());
+    }: _(RawOrigin::Signed(caller), id, item, delegate_lookup)
+    verify {
+        assert_eq!(Module::<T>::item(id, item).and_then(|i| i.approved), Some(delegate));
+    }
+
+    cancel_item_approval {
+        let (caller, _, id) = create_default_class::<T>();
+        let item = mint_default_item::<T>(&caller, id);
+        let delegate: T::AccountId = account("delegate", 0, SEED);
+        assert!(Module::<T>::approve_item(
+            RawOrigin::Signed(caller.clone()).into(),
+            id,
+            item,
+            T::Lookup::unlookup(delegate),
+        )
+        .is_ok());
+    }: _(RawOrigin::Signed(caller), id, item)
+    verify {
+        assert_eq!(Module::<T>::item(id, item).and_then(|i| i.approved), None);
+    }
+
+    burn_item {
+        let (caller, _, id) = create_default_class::<T>();
+        let item = mint_default_item::<T>(&caller, id);
+        for i in 0..T::MaxAttributes::get() {
+            assert!(Module::<T>::set_attribute(
+                RawOrigin::Signed(caller.clone()).into(),
+                id,
+                item,
+                i.encode(),
+                vec![0u8; T::StringLimit::get() as usize],
+            )
+            .is_ok());
+        }
+    }: _(RawOrigin::Signed(caller), id, item)
+    verify {
+        assert!(Module::<T>::owner_of(id, item).is_none());
+    }
+
+    set_attribute {
+        let k in 0 .. T::StringLimit::get();
+        let v in 0 .. T::StringLimit::get();
+
+        let (caller, _, id) = create_default_class::<T>();
+        let item = mint_default_item::<T>(&caller, id);
+        let key = vec![0u8; k as usize];
+        let value = vec![0u8; v as usize];
+    }: _(RawOrigin::Signed(caller), id, item, key.clone(), value.clone())
+    verify {
+        assert_eq!(Module::<T>::attribute((id, item), key), Some(value));
+    }
+
+    clear_attribute {
+        let (caller, _, id) = create_default_class::<T>();
+        let item = mint_default_item::<T>(&caller, id);
+        let key = vec![0u8; T::StringLimit::get() as usize];
+        assert!(Module::<T>::set_attribute(
+            RawOrigin::Signed(caller.clone()).into(),
+            id,
+            item,
+            key.clone(),
+            vec![0u8; T::StringLimit::get() as usize],
+        )
+        .is_ok());
+    }: _(RawOrigin::Signed(caller), id, item, key.clone())
+    verify {
+        assert!(Module::<T>::attribute((id, item), key).is_none());
+    }
+
+    vested_transfer {
+        let locked = Module::<T>::min_vested_transfer(Module::<T>::next_asset_id())
+            .max(1_000u32.into());
+        let total = locked * (T::MaxVestingSchedules::get() + 1).into();
+        let (caller, _, id) = create_default_asset::<T>(total);
+        let target: T::AccountId = account("target", 0, SEED);
+        let target_lookup = T::Lookup::unlookup(target.clone());
+        let schedule = VestingSchedule {
+            locked,
+            per_block: locked / 100u32.into(),
+            starting_block: 1u32.into(),
+        };
+        for _ in 1..T::MaxVestingSchedules::get() {
+            assert!(Module::<T>::vested_transfer(
+                RawOrigin::Signed(caller.clone()).into(),
+                id,
+                target_lookup.clone(),
+                schedule,
+            )
+            .is_ok());
+        }
+    }: _(RawOrigin::Signed(caller), id, target_lookup, schedule)
+    verify {
+        assert_eq!(
+            Module::<T>::vesting((id, target)).len(),
+            T::MaxVestingSchedules::get() as usize
+        );
+    }
+
+    vest {
+        let locked = Module::<T>::min_vested_transfer(Module::<T>::next_asset_id())
+            .max(1_000u32.into());
+        let total = locked * (T::MaxVestingSchedules::get() + 1).into();
+        let (caller, _, id) = create_default_asset::<T>(total);
+        let target: T::AccountId = account("target", 0, SEED);
+        let target_lookup = T::Lookup::unlookup(target.clone());
+        let schedule = VestingSchedule {
+            locked,
+            per_block: locked / 100u32.into(),
+            starting_block: 1u32.into(),
+        };
+        for _ in 0..T::MaxVestingSchedules::get() {
+            assert!(Module::<T>::vested_transfer(
+                RawOrigin::Signed(caller.clone()).into(),
+                id,
+                target_lookup.clone(),
+                schedule,
+            )
+            .is_ok());
+        }
+        frame_system::Module::<T>::set_block_number(10u32.into());
+    }: _(RawOrigin::Signed(target.clone()), id)
+    verify {
+        assert!(!Module::<T>::locks((id, target)).is_empty());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock::{new_test_ext, Test};
+    use frame_support::assert_ok;
+
+    #[test]
+    fn test_benchmarks() {
+        new_test_ext().execute_with(|| {
+            assert_ok!(test_benchmark_issue::<Test>());
+            assert_ok!(test_benchmark_transfer::<Test>());
+            assert_ok!(test_benchmark_transfer_keep_alive::<Test>());
+            assert_ok!(test_benchmark_transfer_batch::<Test>());
+            assert_ok!(test_benchmark_airdrop::<Test>());
+            assert_ok!(test_benchmark_set_min_balance::<Test>());
+            assert_ok!(test_benchmark_set_team::<Test>());
+            assert_ok!(test_benchmark_transfer_ownership::<Test>());
+            assert_ok!(test_benchmark_freeze::<Test>());
+            assert_ok!(test_benchmark_thaw::<Test>());
+            assert_ok!(test_benchmark_freeze_asset::<Test>());
+            assert_ok!(test_benchmark_thaw_asset::<Test>());
+            assert_ok!(test_benchmark_force_transfer::<Test>());
+            assert_ok!(test_benchmark_mint::<Test>());
+            assert_ok!(test_benchmark_burn::<Test>());
+            assert_ok!(test_benchmark_destroy::<Test>());
+            assert_ok!(test_benchmark_approve_transfer::<Test>());
+            assert_ok!(test_benchmark_cancel_approval::<Test>());
+            assert_ok!(test_benchmark_transfer_approved::<Test>());
+            assert_ok!(test_benchmark_set_metadata::<Test>());
+            assert_ok!(test_benchmark_clear_metadata::<Test>());
+            assert_ok!(test_benchmark_start_destroy::<Test>());
+            assert_ok!(test_benchmark_destroy_accounts::<Test>());
+            assert_ok!(test_benchmark_finish_destroy::<Test>());
+            assert_ok!(test_benchmark_create_class::<Test>());
+            assert_ok!(test_benchmark_mint_item::<Test>());
+            assert_ok!(test_benchmark_transfer_item::<Test>());
+            assert_ok!(test_benchmark_approve_item::<Test>());
+            assert_ok!(test_benchmark_cancel_item_approval::<Test>());
+            assert_ok!(test_benchmark_burn_item::<Test>());
+            assert_ok!(test_benchmark_set_attribute::<Test>());
+            assert_ok!(test_benchmark_clear_attribute::<Test>());
+            assert_ok!(test_benchmark_vested_transfer::<Test>());
+            assert_ok!(test_benchmark_vest::<Test>());
+        });
+    }
+}
@@ -27,6 +27,14 @@
 //! * Asset Issuance
 //! * Asset Transfer
 //! * Asset Destruction
+//! * Asset Metadata
+//! * Non-fungible Asset Classes and Items
+//! * Account Reaping
+//! * Asset Administration (roles, freezing, forced transfers)
+//! * Reserved and Locked Balances
+//! * Vesting Schedules
+//! * A [`MultiCurrency`](./traits/trait.MultiCurrency.html) interface over the native currency
+//!   and issued assets
 //!
 //! To use it in your runtime, you need to implement the assets [`Trait`](./trait.Trait.html).
 //!
@@ -38,8 +46,31 @@
 //!   account that issues the asset.
 //! * **Asset transfer:** The action of transferring assets from one account to another.
 //! * **Asset destruction:** The process of an account removing its entire holding of an asset.
+//! * **Class destruction:** The process of the owner retiring an asset class and all of its
+//!   holdings, approvals and metadata, in bounded steps.
+//! * **Owner:** The account that controls an asset class; initially its creator. The owner
+//!   appoints the issuer, admin and freezer and manages the asset's metadata.
+//! * **Issuer:** The account allowed to mint new units of an asset.
+//! * **Admin:** The account allowed to thaw accounts and assets and to force transfers.
+//! * **Freezer:** The account allowed to freeze accounts and assets. Frozen holdings cannot be
+//!   transferred, burned or destroyed by their holder.
+//! * **Minimum balance:** The smallest balance an account may hold of an asset. Accounts
+//!   that fall below it are reaped and their remaining dust is removed from the supply.
+//! * **Asset metadata:** The name, symbol and number of decimals of an asset, set by its creator
+//!   against a reserved deposit.
+//! * **Free balance:** The part of a holding that can be transferred, subject to locks.
+//! * **Reserved balance:** The part of a holding set aside by another pallet, e.g. as collateral.
+//!   It still counts towards the total supply.
+//! * **Lock:** A named restriction that keeps the free balance above an amount for a given set
+//!   of withdraw reasons. Locks overlap rather than add up.
+//! * **Native asset:** The currency of `Trait::Currency`, addressed as `Trait::NativeAssetId`
+//!   through the `MultiCurrency` traits. It is never issued by this module: what
+//!   `MultiCurrency::withdraw` takes is held by the module account until deposited again.
 //! * **Fungible asset:** An asset whose units are interchangeable.
 //! * **Non-fungible asset:** An asset for which each unit has unique characteristics.
+//! * **Item:** A unit of a non-fungible asset class, with an identifier unique within its class
+//!   and optional on-chain attributes. Non-fungible classes share identifiers, roles, freezing,
+//!   metadata and destruction with fungible assets, but hold no balances.
 //!
 //! ### Goals
 //!
@@ -57,8 +88,40 @@
 //! * `issue` - Issues the total supply of a new fungible asset to the account of the caller of the function.
 //! * `transfer` - Transfers an `amount` of units of fungible asset `id` from the balance of
 //! the function caller's account (`origin`) to a `target` account.
+//! * `transfer_keep_alive` - Same as `transfer`, but fails rather than reaping the caller's account.
+//! * `transfer_batch` - Transfers asset `id` from the caller to many accounts at once; either
+//! every transfer succeeds or none does.
+//! * `airdrop` - Mints asset `id` to many accounts at once; callable by the issuer.
+//! * `set_min_balance` - Sets the minimum balance of an asset `id`; callable by its creator.
+//! * `set_team` - Appoints the issuer, admin and freezer of an asset `id`; callable by its owner.
+//! * `transfer_ownership` - Hands ownership of an asset `id` to another account.
+//! * `freeze` / `thaw` - Freezes or thaws the holding of a single account.
+//! * `freeze_asset` / `thaw_asset` - Freezes or thaws every holding of an asset class.
+//! * `force_transfer` - Moves assets between two accounts on the admin's authority.
 //! * `destroy` - Destroys the entire holding of a fungible asset `id` associated with the account
 //! that called the function.
+//! * `approve_transfer` - Allows a `delegate` account to transfer up to `amount` of asset `id`
+//! on behalf of the caller.
+//! * `cancel_approval` - Revokes a previously granted approval.
+//! * `transfer_approved` - Transfers assets of `owner` to a `destination` account, using an
+//! approval granted to the caller.
+//! * `set_metadata` - Sets the name, symbol and decimals of an asset `id`, reserving a deposit
+//! from its creator.
+//! * `clear_metadata` - Removes the metadata of an asset `id` and returns the deposit to its creator.
+//! * `start_destroy` - Starts destroying an asset class `id`, after which it can no longer be
+//!   credited, withdrawn or approved.
+//! * `destroy_accounts` - Removes up to `max` holdings and approvals of an asset being destroyed.
+//! * `finish_destroy` - Removes an emptied asset being destroyed and returns its metadata deposit.
+//! * `create_class` - Creates a non-fungible asset class owned by the caller.
+//! * `mint_item` - Mints an item with a unique identifier in a non-fungible class; callable by
+//!   the issuer.
+//! * `transfer_item` - Transfers an item, by its owner or by the account it approved.
+//! * `approve_item` / `cancel_item_approval` - Approves or unapproves a delegate for an item.
+//! * `burn_item` - Burns an item owned by the caller, along with its attributes.
+//! * `set_attribute` / `clear_attribute` - Sets or clears an on-chain attribute of an item;
+//!   callable by the issuer.
+//! * `vested_transfer` - Transfers assets that stay locked under a linear vesting schedule.
+//! * `vest` - Unlocks whatever has vested of the caller's schedules in an asset.
 //!
 //! Please refer to the [`Call`](./enum.Call.html) enum and its associated variants for documentation on each function.
 //!
@@ -67,14 +130,32 @@
 //!
 //! * `balance` - Get the asset `id` balance of `who`.
 //! * `total_supply` - Get the total supply of an asset `id`.
+//! * `account_balances` - Get the free balance of `who` in every asset it holds.
+//! * `reserved` - Get the reserved balance of `who` in asset `id`.
+//! * `locks` - Get the locks on the free balance of `who` in asset `id`.
+//! * `roles` - Get the issuer, admin and freezer of an asset `id`.
+//! * `min_balance` - Get the minimum balance an account may hold of an asset `id`.
+//! * `allowance` - Get the amount of asset `id` that `delegate` may transfer on behalf of `owner`.
+//! * `metadata` - Get the name, symbol and decimals of an asset `id`, if set.
+//! * `is_destroying` - Whether an asset `id` is being destroyed.
+//! * `owner_of` - Get the owner of an item of a non-fungible asset class.
+//! * `items_of_owner` - Get every non-fungible item held by an account.
+//! * `do_airdrop` - Mint asset `id` to many accounts, e.g. from another pallet's distribution logic.
 //!
 //! Please refer to the [`Module`](./struct.Module.html) struct for details on publicly available functions.
 //!
+//! ### Traits
+//!
+//! * [`MultiCurrency`](./traits/trait.MultiCurrency.html),
+//!   [`MultiReservableCurrency`](./traits/trait.MultiReservableCurrency.html) and
+//!   [`MultiLockableCurrency`](./traits/trait.MultiLockableCurrency.html) - Implemented by
+//!   the module so that other pallets handle the native currency and issued assets uniformly.
+//!
 //! ## Usage
 //!
 //! The following example shows how to use the Assets module in your runtime by exposing public functions to:
 //!
-//! * Issue a new fungible asset for a token distribution event (airdrop).
+//! * Mint an issued asset to many accounts for a token distribution event (airdrop).
 //! * Query the fungible asset holding balance of an account.
 //! * Query the total supply of a fungible asset that has been issued.
 //!
@@ -85,7 +166,7 @@
 //! ### Simple Code Snippet
 //!
 //! ```rust,ignore
-//! use pallet_assets as assets;
+//! use subswap_asset as assets;
 //! use frame_support::{decl_module, dispatch, ensure};
 //! use frame_system::ensure_signed;
 //!
@@ -93,25 +174,21 @@
 //!
 //! decl_module! {
 //! 	pub struct Module<T: Trait> for enum Call where origin: T::Origin {
-//! 		pub fn issue_token_airdrop(origin) -> dispatch::DispatchResult {
-//! 			let sender = ensure_signed(origin).map_err(|e| e.as_str())?;
+//! 		#[weight = 0]
+//! 		pub fn token_airdrop(origin, asset_id: T::AssetId) -> dispatch::DispatchResult {
+//! 			let sender = ensure_signed(origin)?;
+//! 			let roles = <assets::Module<T>>::roles(asset_id).ok_or("Unknown asset.")?;
+//! 			ensure!(roles.issuer == sender, "Only the issuer can airdrop.");
 //!
 //! 			const ACCOUNT_ALICE: u64 = 1;
 //! 			const ACCOUNT_BOB: u64 = 2;
-//! 			const COUNT_AIRDROP_RECIPIENTS: u64 = 2;
-//! 			const TOKENS_FIXED_SUPPLY: u64 = 100;
-//!
-//! 			ensure!(!COUNT_AIRDROP_RECIPIENTS.is_zero(), "Divide by zero error.");
-//!
-//! 			let asset_id = Self::next_asset_id();
+//! 			const TOKENS_PER_RECIPIENT: u64 = 50;
 //!
-//! 			<NextAssetId<T>>::mutate(|asset_id| *asset_id += 1);
-//! 			<Balances<T>>::insert((asset_id, &ACCOUNT_ALICE), TOKENS_FIXED_SUPPLY / COUNT_AIRDROP_RECIPIENTS);
-//! 			<Balances<T>>::insert((asset_id, &ACCOUNT_BOB), TOKENS_FIXED_SUPPLY / COUNT_AIRDROP_RECIPIENTS);
-//! 			<TotalSupply<T>>::insert(asset_id, TOKENS_FIXED_SUPPLY);
-//!
-//! 			Self::deposit_event(RawEvent::Issued(asset_id, sender, TOKENS_FIXED_SUPPLY));
-//! 			Ok(())
+//! 			// Mints to every recipient and keeps the total supply in step.
+//! 			<assets::Module<T>>::do_airdrop(asset_id, vec![
+//! 				(ACCOUNT_ALICE, TOKENS_PER_RECIPIENT),
+//! 				(ACCOUNT_BOB, TOKENS_PER_RECIPIENT),
+//! 			])
 //! 		}
 //! 	}
 //! }
@@ -135,12 +212,43 @@
 
 use codec::{Decode, Encode};
 use frame_support::{
-    decl_error, decl_event, decl_module, decl_storage, dispatch, ensure, Parameter,
+    decl_error, decl_event, decl_module, decl_storage, dispatch, ensure,
+    storage::IterableStorageDoubleMap,
+    traits::{
+        BalanceStatus, Currency, ExistenceRequirement, Get, LockIdentifier, LockableCurrency,
+        ReservableCurrency, WithdrawReason, WithdrawReasons,
+    },
+    weights::Weight,
+    Parameter,
 };
 use frame_system::ensure_signed;
 use sp_runtime::traits::One;
-use sp_runtime::traits::{AtLeast32Bit, AtLeast32BitUnsigned, Member, StaticLookup, Zero};
+use sp_runtime::traits::{
+    AccountIdConversion, AtLeast32Bit, AtLeast32BitUnsigned, CheckedAdd, CheckedSub, Convert,
+    MaybeSerializeDeserialize, Member, Saturating, StaticLookup, Zero,
+};
+use sp_runtime::{ModuleId, RuntimeDebug};
 use sp_std::default::Default;
+use sp_std::prelude::*;
+
+mod default_weight;
+pub mod traits;
+pub use traits::{MultiCurrency, MultiLockableCurrency, MultiReservableCurrency};
+
+#[cfg(test)]
+mod mock;
+
+#[cfg(test)]
+mod tests;
+
+#[cfg(feature = "runtime-benchmarks")]
+mod benchmarking;
+
+/// The lock under which unvested assets are held.
+const VESTING_ID: LockIdentifier = *b"vesting ";
+
+type DepositBalanceOf<T> =
+    <<T as Trait>::Currency as Currency<<T as frame_system::Trait>::AccountId>>::Balance;
 
 /// Data storage type for each account
 #[derive(Encode, Decode, Clone, Eq, PartialEq)]
@@ -149,16 +257,194 @@ pub enum Account<AccountId> {
     System(),
 }
 
+/// A change to the balance of an account, applied through `Module::mutate_balance`.
+#[derive(Clone, Copy, Eq, PartialEq, RuntimeDebug)]
+enum BalanceChange<Balance> {
+    /// Add units to the account and to the total supply.
+    Credit(Balance),
+    /// Remove units from the account and from the total supply.
+    Debit(Balance),
+}
+
+/// Handler for the dust of an account reaped below the minimum balance of an asset.
+///
+/// The dust has already been removed from the total supply when the handler is called, so
+/// `()` simply burns it. A handler may credit it elsewhere, e.g. via `mint_from_system`.
+pub trait OnDust<AssetId, AccountId, Balance> {
+    /// Handle `amount` of asset `id` left behind by the reaped account `who`.
+    fn on_dust(id: AssetId, who: &AccountId, amount: Balance);
+}
+
+impl<AssetId, AccountId, Balance> OnDust<AssetId, AccountId, Balance> for () {
+    fn on_dust(_: AssetId, _: &AccountId, _: Balance) {}
+}
+
+/// A named restriction on the free balance of a holding.
+#[derive(Encode, Decode, Clone, Eq, PartialEq, RuntimeDebug)]
+pub struct AssetLock<Balance> {
+    /// The identifier of the lock, chosen by the pallet that sets it.
+    pub id: LockIdentifier,
+    /// The amount below which the free balance may not fall.
+    pub amount: Balance,
+    /// The kinds of withdrawal the lock applies to.
+    pub reasons: WithdrawReasons,
+}
+
+/// The privileged accounts of an asset class besides its owner.
+#[derive(Encode, Decode, Clone, Default, Eq, PartialEq, RuntimeDebug)]
+pub struct AssetRoles<AccountId> {
+    /// Can mint new units of the asset.
+    pub issuer: AccountId,
+    /// Can thaw accounts and the asset, and force transfers between accounts.
+    pub admin: AccountId,
+    /// Can freeze accounts and the asset.
+    pub freezer: AccountId,
+}
+
+/// Descriptive information about an asset class.
+#[derive(Encode, Decode, Clone, Default, Eq, PartialEq, RuntimeDebug)]
+pub struct AssetMetadata<DepositBalance> {
+    /// The balance reserved from the creator for keeping this metadata on-chain.
+    pub deposit: DepositBalance,
+    /// The user friendly name of the asset, e.g. "Vault USD".
+    pub name: Vec<u8>,
+    /// The ticker symbol of the asset, e.g. "vUSD".
+    pub symbol: Vec<u8>,
+    /// The number of decimals used to display balances of the asset.
+    pub decimals: u8,
+}
+
+/// Weight functions needed for the extrinsics of this pallet.
+pub trait WeightInfo {
+    fn issue() -> Weight;
+    fn transfer() -> Weight;
+    fn transfer_keep_alive() -> Weight;
+    fn transfer_batch(n: u32) -> Weight;
+    fn airdrop(n: u32) -> Weight;
+    fn set_min_balance() -> Weight;
+    fn set_team() -> Weight;
+    fn transfer_ownership() -> Weight;
+    fn freeze() -> Weight;
+    fn thaw() -> Weight;
+    fn freeze_asset() -> Weight;
+    fn thaw_asset() -> Weight;
+    fn force_transfer() -> Weight;
+    fn mint() -> Weight;
+    fn burn() -> Weight;
+    fn destroy() -> Weight;
+    fn approve_transfer() -> Weight;
+    fn cancel_approval() -> Weight;
+    fn transfer_approved() -> Weight;
+    fn set_metadata(n: u32, s: u32) -> Weight;
+    fn clear_metadata() -> Weight;
+    fn start_destroy() -> Weight;
+    fn destroy_accounts(n: u32) -> Weight;
+    fn finish_destroy() -> Weight;
+    fn create_class() -> Weight;
+    fn mint_item() -> Weight;
+    fn transfer_item() -> Weight;
+    fn approve_item() -> Weight;
+    fn cancel_item_approval() -> Weight;
+    fn burn_item() -> Weight;
+    fn set_attribute(k: u32, v: u32) -> Weight;
+    fn clear_attribute() -> Weight;
+    fn vested_transfer() -> Weight;
+    fn vest() -> Weight;
+}
+
+/// An item of a non-fungible asset class.
+#[derive(Encode, Decode, Clone, Eq, PartialEq, RuntimeDebug)]
+pub struct ItemDetails<AccountId> {
+    /// The account holding the item.
+    pub owner: AccountId,
+    /// The account allowed to transfer the item on behalf of its owner, if any.
+    pub approved: Option<AccountId>,
+    /// The number of attributes set on the item.
+    pub attributes: u32,
+}
+
+/// A linear release of a locked amount of an asset, one block at a time.
+#[derive(Encode, Decode, Copy, Clone, Eq, PartialEq, RuntimeDebug)]
+pub struct VestingSchedule<Balance, BlockNumber> {
+    /// The amount locked when the schedule was created.
+    pub locked: Balance,
+    /// The amount unlocked every block from `starting_block`.
+    pub per_block: Balance,
+    /// The block from which the amount starts being unlocked.
+    pub starting_block: BlockNumber,
+}
+
+impl<Balance: AtLeast32BitUnsigned + Copy, BlockNumber: AtLeast32BitUnsigned + Copy>
+    VestingSchedule<Balance, BlockNumber>
+{
+    /// The amount still locked at block `n`.
+    pub fn locked_at<BlockNumberToBalance: Convert<BlockNumber, Balance>>(
+        &self,
+        n: BlockNumber,
+    ) -> Balance {
+        let vested_blocks = BlockNumberToBalance::convert(n.saturating_sub(self.starting_block));
+        self.locked.saturating_sub(vested_blocks.saturating_mul(self.per_block))
+    }
+}
+
 /// The module configuration trait.
 pub trait Trait: frame_system::Trait {
     /// The overarching event type.
     type Event: From<Event<Self>> + Into<<Self as frame_system::Trait>::Event>;
 
     /// The units in which we record balances.
-    type Balance: Member + Parameter + AtLeast32BitUnsigned + Default + Copy;
+    type Balance: Member + Parameter + AtLeast32BitUnsigned + Default + Copy
+        + MaybeSerializeDeserialize;
 
     /// The arithmetic type of asset identifier.
-    type AssetId: Parameter + AtLeast32Bit + Default + Copy;
+    type AssetId: Parameter + AtLeast32Bit + Default + Copy + MaybeSerializeDeserialize;
+
+    /// The native currency. Metadata deposits are reserved in it, and it is exposed through
+    /// `MultiCurrency` under `NativeAssetId`.
+    type Currency: ReservableCurrency<Self::AccountId, Balance = Self::Balance>
+        + LockableCurrency<Self::AccountId, Balance = Self::Balance>;
+
+    /// The identifier under which `Currency` is addressed by `MultiCurrency`. It is skipped
+    /// when issuing new assets.
+    type NativeAssetId: Get<Self::AssetId>;
+
+    /// The id of the account that holds the native currency withdrawn through `MultiCurrency`
+    /// until it is deposited again.
+    type ModuleId: Get<ModuleId>;
+
+    /// The base deposit reserved for setting the metadata of an asset.
+    type MetadataDepositBase: Get<DepositBalanceOf<Self>>;
+
+    /// The additional deposit reserved per byte of name and symbol.
+    type MetadataDepositPerByte: Get<DepositBalanceOf<Self>>;
+
+    /// The maximum length of an asset's name or symbol.
+    type StringLimit: Get<u32>;
+
+    /// Handler for the dust of accounts reaped below an asset's minimum balance.
+    type OnDust: OnDust<Self::AssetId, Self::AccountId, Self::Balance>;
+
+    /// The maximum number of recipients of a single `transfer_batch` or `airdrop`.
+    type MaxBatchSize: Get<u32>;
+
+    /// The identifier of an item within a non-fungible asset class.
+    type ItemId: Parameter + AtLeast32BitUnsigned + Default + Copy;
+
+    /// The maximum number of attributes of a non-fungible item.
+    type MaxAttributes: Get<u32>;
+
+    /// Converts a number of blocks into a balance, to compute vested amounts.
+    type BlockNumberToBalance: Convert<Self::BlockNumber, Self::Balance>;
+
+    /// The maximum number of vesting schedules an account may have per asset.
+    type MaxVestingSchedules: Get<u32>;
+
+    /// The minimum amount a vested transfer may lock, as a multiple of the minimum balance of
+    /// its asset, or of one unit for an asset without one.
+    type MinVestedTransferFactor: Get<u32>;
+
+    /// Weight information for extrinsics in this pallet.
+    type WeightInfo: WeightInfo;
 }
 
 decl_module! {
@@ -175,16 +461,18 @@ decl_module! {
         /// - 2 storage writes (condec `O(1)`).
         /// - 1 event.
         /// # </weight>
-        #[weight = 0]
+        #[weight = T::WeightInfo::issue()]
         fn issue(origin, #[compact] total: T::Balance) {
             let origin = ensure_signed(origin)?;
 
-            let id = Self::next_asset_id();
-            <NextAssetId<T>>::mutate(|id| *id += One::one());
-
-            <Balances<T>>::insert((id, &origin), total);
-            <TotalSupply<T>>::insert(id, total);
+            let id = Self::allocate_asset_id();
+            Self::mutate_balance(id, &origin, BalanceChange::Credit(total))?;
             <Creator<T>>::insert(id, &origin);
+            <Roles<T>>::insert(id, AssetRoles {
+                issuer: origin.clone(),
+                admin: origin.clone(),
+                freezer: origin.clone(),
+            });
 
             Self::deposit_event(RawEvent::Issued(id, origin, total));
         }
@@ -197,25 +485,325 @@ decl_module! {
         /// - 2 storage mutations (codec `O(1)`).
         /// - 1 event.
         /// # </weight>
-        #[weight = 0]
+        #[weight = T::WeightInfo::transfer()]
         fn transfer(origin,
             #[compact] id: T::AssetId,
             target: <T::Lookup as StaticLookup>::Source,
             #[compact] amount: T::Balance
         ) {
             let origin = ensure_signed(origin)?;
-            let origin_account = (id, origin.clone());
-            let origin_balance = <Balances<T>>::get(&origin_account);
             let target = T::Lookup::lookup(target)?;
-            ensure!(!amount.is_zero(), Error::<T>::AmountZero);
-            ensure!(origin_balance >= amount, Error::<T>::BalanceLow);
+            Self::ensure_withdrawable(id, &origin, amount, WithdrawReason::Transfer.into())?;
+
+            Self::do_transfer(id, &origin, &target, amount)?;
+            Self::deposit_event(RawEvent::Transferred(id, origin, target, amount));
+        }
+
+        /// Move some assets from one holder to another, failing if the holder's account would be
+        /// reaped.
+        ///
+        /// # <weight>
+        /// - `O(1)`
+        /// - 1 static lookup
+        /// - 1 storage read (codec `O(1)`).
+        /// - 2 storage mutations (codec `O(1)`).
+        /// - 1 event.
+        /// # </weight>
+        #[weight = T::WeightInfo::transfer_keep_alive()]
+        fn transfer_keep_alive(origin,
+            #[compact] id: T::AssetId,
+            target: <T::Lookup as StaticLookup>::Source,
+            #[compact] amount: T::Balance
+        ) {
+            let origin = ensure_signed(origin)?;
+            let target = T::Lookup::lookup(target)?;
+            let remaining = <Balances<T>>::get((id, &origin))
+                .checked_sub(&amount)
+                .ok_or(Error::<T>::BalanceLow)?;
+            ensure!(!remaining.is_zero(), Error::<T>::WouldDie);
+            ensure!(remaining >= Self::min_balance(id), Error::<T>::WouldDie);
+            Self::ensure_withdrawable(id, &origin, amount, WithdrawReason::Transfer.into())?;
+
+            Self::do_transfer(id, &origin, &target, amount)?;
+            Self::deposit_event(RawEvent::Transferred(id, origin, target, amount));
+        }
+
+        /// Transfer asset `id` from `origin` to every `(target, amount)` in `targets`.
+        ///
+        /// Either every transfer succeeds or none does. `targets` may hold at most
+        /// `MaxBatchSize` entries; entries that target `origin` itself are skipped.
+        ///
+        /// # <weight>
+        /// - `O(N)` where `N` is `targets.len()`, bounded by `MaxBatchSize`.
+        /// - `N` static lookups
+        /// - `N + 1` storage mutations (codec `O(1)`).
+        /// - `N + 1` events.
+        /// # </weight>
+        #[weight = T::WeightInfo::transfer_batch(targets.len() as u32)]
+        fn transfer_batch(origin,
+            #[compact] id: T::AssetId,
+            targets: Vec<(<T::Lookup as StaticLookup>::Source, T::Balance)>
+        ) {
+            let origin = ensure_signed(origin)?;
+            ensure!(targets.len() <= T::MaxBatchSize::get() as usize, Error::<T>::TooManyTargets);
+            let mut recipients = Vec::with_capacity(targets.len());
+            let mut total: T::Balance = Zero::zero();
+            for (target, amount) in targets {
+                let target = T::Lookup::lookup(target)?;
+                ensure!(!amount.is_zero(), Error::<T>::AmountZero);
+                if target == origin {
+                    continue;
+                }
+                total = total.checked_add(&amount).ok_or(Error::<T>::Overflow)?;
+                recipients.push((target, amount));
+            }
+            Self::ensure_withdrawable(id, &origin, total, WithdrawReason::Transfer.into())?;
+            Self::ensure_can_credit_all(id, &recipients)?;
+
+            // Debiting once keeps any reaping of `origin` to the very end of the batch.
+            Self::mutate_balance(id, &origin, BalanceChange::Debit(total))?;
+            let count = recipients.len() as u32;
+            for (target, amount) in recipients {
+                // Cannot fail: checked by `ensure_can_credit_all` and bounded by the debit above.
+                Self::mutate_balance(id, &target, BalanceChange::Credit(amount))?;
+                Self::deposit_event(RawEvent::Transferred(id, origin.clone(), target, amount));
+            }
+            Self::deposit_event(RawEvent::BatchTransferred(id, origin, count, total));
+        }
+
+        /// Mint asset `id` to every `(target, amount)` in `recipients`. Callable by the issuer of
+        /// the asset.
+        ///
+        /// Either every mint succeeds or none does. `recipients` may hold at most
+        /// `MaxBatchSize` entries.
+        ///
+        /// # <weight>
+        /// - `O(N)` where `N` is `recipients.len()`, bounded by `MaxBatchSize`.
+        /// - `N` static lookups
+        /// - `N + 1` storage mutations (codec `O(1)`).
+        /// - `N + 1` events.
+        /// # </weight>
+        #[weight = T::WeightInfo::airdrop(recipients.len() as u32)]
+        fn airdrop(origin,
+            #[compact] id: T::AssetId,
+            recipients: Vec<(<T::Lookup as StaticLookup>::Source, T::Balance)>
+        ) {
+            let origin = ensure_signed(origin)?;
+            ensure!(recipients.len() <= T::MaxBatchSize::get() as usize, Error::<T>::TooManyTargets);
+            let roles = Self::roles(id).ok_or(Error::<T>::Unknown)?;
+            ensure!(origin == roles.issuer, Error::<T>::NoPermission);
+            let recipients = recipients
+                .into_iter()
+                .map(|(target, amount)| Ok((T::Lookup::lookup(target)?, amount)))
+                .collect::<Result<Vec<_>, dispatch::DispatchError>>()?;
+
+            Self::do_airdrop(id, recipients)?;
+        }
+
+        /// Set the minimum balance an account may hold of asset `id` created by `origin`.
+        ///
+        /// Existing accounts below the new minimum are reaped the next time they are debited.
+        ///
+        /// # <weight>
+        /// - `O(1)`
+        /// - 1 storage read (codec `O(1)`).
+        /// - 1 storage write (codec `O(1)`).
+        /// - 1 event.
+        /// # </weight>
+        #[weight = T::WeightInfo::set_min_balance()]
+        fn set_min_balance(origin,
+            #[compact] id: T::AssetId,
+            #[compact] min_balance: T::Balance
+        ) {
+            let origin = ensure_signed(origin)?;
+            ensure!(<Creator<T>>::contains_key(id), Error::<T>::Unknown);
+            ensure!(origin == <Creator<T>>::get(id), Error::<T>::NotTheCreator);
+
+            <MinBalance<T>>::insert(id, min_balance);
+            Self::deposit_event(RawEvent::MinBalanceSet(id, min_balance));
+        }
+
+        /// Appoint the `issuer`, `admin` and `freezer` of asset `id` owned by `origin`.
+        ///
+        /// # <weight>
+        /// - `O(1)`
+        /// - 3 static lookups
+        /// - 1 storage read (codec `O(1)`).
+        /// - 1 storage write (codec `O(1)`).
+        /// - 1 event.
+        /// # </weight>
+        #[weight = T::WeightInfo::set_team()]
+        fn set_team(origin,
+            #[compact] id: T::AssetId,
+            issuer: <T::Lookup as StaticLookup>::Source,
+            admin: <T::Lookup as StaticLookup>::Source,
+            freezer: <T::Lookup as StaticLookup>::Source
+        ) {
+            let origin = ensure_signed(origin)?;
+            let issuer = T::Lookup::lookup(issuer)?;
+            let admin = T::Lookup::lookup(admin)?;
+            let freezer = T::Lookup::lookup(freezer)?;
+            ensure!(<Creator<T>>::contains_key(id), Error::<T>::Unknown);
+            ensure!(origin == <Creator<T>>::get(id), Error::<T>::NotTheCreator);
+
+            <Roles<T>>::insert(id, AssetRoles {
+                issuer: issuer.clone(),
+                admin: admin.clone(),
+                freezer: freezer.clone(),
+            });
+            Self::deposit_event(RawEvent::TeamChanged(id, issuer, admin, freezer));
+        }
+
+        /// Transfer ownership of asset `id` from `origin` to `owner`.
+        ///
+        /// Any metadata deposit moves along with the ownership. The issuer, admin and freezer
+        /// are left untouched.
+        ///
+        /// # <weight>
+        /// - `O(1)`
+        /// - 1 static lookup
+        /// - 2 storage reads (codec `O(1)`).
+        /// - 1 storage write (codec `O(1)`).
+        /// - 1 reserve repatriation.
+        /// - 1 event.
+        /// # </weight>
+        #[weight = T::WeightInfo::transfer_ownership()]
+        fn transfer_ownership(origin,
+            #[compact] id: T::AssetId,
+            owner: <T::Lookup as StaticLookup>::Source
+        ) {
+            let origin = ensure_signed(origin)?;
+            let owner = T::Lookup::lookup(owner)?;
+            ensure!(<Creator<T>>::contains_key(id), Error::<T>::Unknown);
+            ensure!(origin == <Creator<T>>::get(id), Error::<T>::NotTheCreator);
+            if origin == owner {
+                return Ok(());
+            }
+
+            if let Some(metadata) = <Metadata<T>>::get(id) {
+                T::Currency::repatriate_reserved(
+                    &origin,
+                    &owner,
+                    metadata.deposit,
+                    BalanceStatus::Reserved,
+                )?;
+            }
+            <Creator<T>>::insert(id, &owner);
+            Self::deposit_event(RawEvent::OwnerChanged(id, owner));
+        }
+
+        /// Freeze the holding of `who` in asset `id`. Callable by the freezer of the asset.
+        ///
+        /// # <weight>
+        /// - `O(1)`
+        /// - 1 static lookup
+        /// - 1 storage read (codec `O(1)`).
+        /// - 1 storage write (codec `O(1)`).
+        /// - 1 event.
+        /// # </weight>
+        #[weight = T::WeightInfo::freeze()]
+        fn freeze(origin,
+            #[compact] id: T::AssetId,
+            who: <T::Lookup as StaticLookup>::Source
+        ) {
+            let origin = ensure_signed(origin)?;
+            let who = T::Lookup::lookup(who)?;
+            let roles = Self::roles(id).ok_or(Error::<T>::Unknown)?;
+            ensure!(origin == roles.freezer, Error::<T>::NoPermission);
+
+            <FrozenAccounts<T>>::insert((id, &who), true);
+            Self::deposit_event(RawEvent::Frozen(id, who));
+        }
+
+        /// Thaw the holding of `who` in asset `id`. Callable by the admin of the asset.
+        ///
+        /// # <weight>
+        /// - `O(1)`
+        /// - 1 static lookup
+        /// - 1 storage read (codec `O(1)`).
+        /// - 1 storage deletion (codec `O(1)`).
+        /// - 1 event.
+        /// # </weight>
+        #[weight = T::WeightInfo::thaw()]
+        fn thaw(origin,
+            #[compact] id: T::AssetId,
+            who: <T::Lookup as StaticLookup>::Source
+        ) {
+            let origin = ensure_signed(origin)?;
+            let who = T::Lookup::lookup(who)?;
+            let roles = Self::roles(id).ok_or(Error::<T>::Unknown)?;
+            ensure!(origin == roles.admin, Error::<T>::NoPermission);
+
+            <FrozenAccounts<T>>::remove((id, &who));
+            Self::deposit_event(RawEvent::Thawed(id, who));
+        }
+
+        /// Freeze every holding of asset `id`. Callable by the freezer of the asset.
+        ///
+        /// # <weight>
+        /// - `O(1)`
+        /// - 1 storage read (codec `O(1)`).
+        /// - 1 storage write (codec `O(1)`).
+        /// - 1 event.
+        /// # </weight>
+        #[weight = T::WeightInfo::freeze_asset()]
+        fn freeze_asset(origin, #[compact] id: T::AssetId) {
+            let origin = ensure_signed(origin)?;
+            let roles = Self::roles(id).ok_or(Error::<T>::Unknown)?;
+            ensure!(origin == roles.freezer, Error::<T>::NoPermission);
+
+            <FrozenAssets<T>>::insert(id, true);
+            Self::deposit_event(RawEvent::AssetFrozen(id));
+        }
+
+        /// Thaw every holding of asset `id`. Callable by the admin of the asset.
+        ///
+        /// Accounts frozen individually stay frozen.
+        ///
+        /// # <weight>
+        /// - `O(1)`
+        /// - 1 storage read (codec `O(1)`).
+        /// - 1 storage deletion (codec `O(1)`).
+        /// - 1 event.
+        /// # </weight>
+        #[weight = T::WeightInfo::thaw_asset()]
+        fn thaw_asset(origin, #[compact] id: T::AssetId) {
+            let origin = ensure_signed(origin)?;
+            let roles = Self::roles(id).ok_or(Error::<T>::Unknown)?;
+            ensure!(origin == roles.admin, Error::<T>::NoPermission);
+
+            <FrozenAssets<T>>::remove(id);
+            Self::deposit_event(RawEvent::AssetThawed(id));
+        }
+
+        /// Move `amount` of asset `id` from `source` to `dest`. Callable by the admin of the
+        /// asset; freezes do not apply.
+        ///
+        /// # <weight>
+        /// - `O(1)`
+        /// - 2 static lookups
+        /// - 1 storage read (codec `O(1)`).
+        /// - 2 storage mutations (codec `O(1)`).
+        /// - 1 event.
+        /// # </weight>
+        #[weight = T::WeightInfo::force_transfer()]
+        fn force_transfer(origin,
+            #[compact] id: T::AssetId,
+            source: <T::Lookup as StaticLookup>::Source,
+            dest: <T::Lookup as StaticLookup>::Source,
+            #[compact] amount: T::Balance
+        ) {
+            let origin = ensure_signed(origin)?;
+            let source = T::Lookup::lookup(source)?;
+            let dest = T::Lookup::lookup(dest)?;
+            let roles = Self::roles(id).ok_or(Error::<T>::Unknown)?;
+            ensure!(origin == roles.admin, Error::<T>::NoPermission);
 
-            Self::deposit_event(RawEvent::Transferred(id, origin, target.clone(), amount));
-            <Balances<T>>::insert(origin_account, origin_balance - amount);
-            <Balances<T>>::mutate((id, target), |balance| *balance += amount);
+            Self::do_transfer(id, &source, &dest, amount)?;
+            Self::deposit_event(RawEvent::ForceTransferred(id, source, dest, amount));
         }
 
-        /// Mint any assets of `id` owned by `origin`.
+        /// Mint `amount` of asset `id` to `target`. Callable by the issuer of the asset.
         ///
         /// # <weight>
         /// - `O(1)`
@@ -223,7 +811,7 @@ decl_module! {
         /// - 1 storage deletion (codec `O(1)`).
         /// - 1 event.
         /// # </weight>
-        #[weight = 0]
+        #[weight = T::WeightInfo::mint()]
         fn mint(origin,
              #[compact] id: T::AssetId,
             target: <T::Lookup as StaticLookup>::Source,
@@ -231,12 +819,12 @@ decl_module! {
         ){
             let origin = ensure_signed(origin)?;
             let target = T::Lookup::lookup(target)?;
-            let creator = <Creator<T>>::get(id);
-            ensure!(origin == creator, Error::<T>::NotTheCreator);
+            let roles = Self::roles(id).ok_or(Error::<T>::Unknown)?;
+            ensure!(origin == roles.issuer, Error::<T>::NoPermission);
             ensure!(!amount.is_zero(), Error::<T>::AmountZero);
 
-            Self::deposit_event(RawEvent::Minted(id, target.clone(), amount));
-            <Balances<T>>::mutate((id, target), |balance| *balance += amount);
+            Self::mutate_balance(id, &target, BalanceChange::Credit(amount))?;
+            Self::deposit_event(RawEvent::Minted(id, target, amount));
         }
 
         /// Burn any assets of `id` owned by `origin`.
@@ -247,20 +835,18 @@ decl_module! {
         /// - 1 storage deletion (codec `O(1)`).
         /// - 1 event.
         /// # </weight>
-        #[weight = 0]
+        #[weight = T::WeightInfo::burn()]
         fn burn(origin,
             #[compact] id: T::AssetId,
            target: <T::Lookup as StaticLookup>::Source,
            #[compact] amount: T::Balance
        ){
            let origin = ensure_signed(origin)?;
-           let origin_account = (id, origin.clone());
-           let origin_balance = <Balances<T>>::get(&origin_account);
            ensure!(!amount.is_zero(), Error::<T>::AmountZero);
-           ensure!(origin_balance >= amount, Error::<T>::BalanceLow);
+           Self::ensure_withdrawable(id, &origin, amount, WithdrawReason::Transfer.into())?;
 
+           Self::mutate_balance(id, &origin, BalanceChange::Debit(amount))?;
            Self::deposit_event(RawEvent::Burned(id, origin, amount));
-           <Balances<T>>::insert(origin_account, origin_balance - amount);
        }
 
         /// Destroy any assets of `id` owned by `origin`.
@@ -271,89 +857,895 @@ decl_module! {
         /// - 1 storage deletion (codec `O(1)`).
         /// - 1 event.
         /// # </weight>
-        #[weight = 0]
+        #[weight = T::WeightInfo::destroy()]
         fn destroy(origin, #[compact] id: T::AssetId) {
             let origin = ensure_signed(origin)?;
-            let balance = <Balances<T>>::take((id, &origin));
+            let balance = <Balances<T>>::get((id, &origin));
             ensure!(!balance.is_zero(), Error::<T>::BalanceZero);
+            Self::ensure_withdrawable(id, &origin, balance, WithdrawReason::Transfer.into())?;
 
-            <TotalSupply<T>>::mutate(id, |total_supply| *total_supply -= balance);
+            Self::mutate_balance(id, &origin, BalanceChange::Debit(balance))?;
             Self::deposit_event(RawEvent::Destroyed(id, origin, balance));
         }
-    }
-}
 
-decl_event! {
-    pub enum Event<T> where
-        <T as frame_system::Trait>::AccountId,
-        <T as Trait>::Balance,
-        <T as Trait>::AssetId,
-    {
-        /// Some assets were issued. \[asset_id, owner, total_supply\]
-        Issued(AssetId, AccountId, Balance),
-        /// Some assets were transferred. \[asset_id, from, to, amount\]
-        Transferred(AssetId, AccountId, AccountId, Balance),
-        /// Some assets were minted. \[asset_id, owner, balance]
-        Minted(AssetId, AccountId, Balance),
-        /// Some assets were burned. \[asset_id, owner, balance]
-        Burned(AssetId, AccountId, Balance),
-        /// Some assets were destroyed. \[asset_id, owner, balance\]
-        Destroyed(AssetId, AccountId, Balance),
-    }
-}
+        /// Approve `delegate` to transfer up to `amount` of asset `id` from the account of `origin`.
+        ///
+        /// Any existing approval for the same delegate is replaced, not topped up.
+        ///
+        /// # <weight>
+        /// - `O(1)`
+        /// - 1 static lookup
+        /// - 1 storage write (codec `O(1)`).
+        /// - 1 event.
+        /// # </weight>
+        #[weight = T::WeightInfo::approve_transfer()]
+        fn approve_transfer(origin,
+            #[compact] id: T::AssetId,
+            delegate: <T::Lookup as StaticLookup>::Source,
+            #[compact] amount: T::Balance
+        ) {
+            let origin = ensure_signed(origin)?;
+            let delegate = T::Lookup::lookup(delegate)?;
+            ensure!(!amount.is_zero(), Error::<T>::AmountZero);
+            ensure!(<Creator<T>>::contains_key(id), Error::<T>::Unknown);
+            ensure!(!Self::is_destroying(id), Error::<T>::Destroying);
 
-decl_error! {
-    pub enum Error for Module<T: Trait> {
-        /// Transfer amount should be non-zero
-        AmountZero,
-        /// Account balance must be greater than or equal to the transfer amount
-        BalanceLow,
-        /// Balance should be non-zero
-        BalanceZero,
-        /// Not the creator of the asset
-        NotTheCreator,
-        /// Not the approver for the account
-        NotApproved,
-        /// Created by System
-        CreatedBySystem,
-    }
-}
+            <Approvals<T>>::insert(id, (&origin, &delegate), amount);
+            Self::deposit_event(RawEvent::ApprovedTransfer(id, origin, delegate, amount));
+        }
 
-decl_storage! {
-    trait Store for Module<T: Trait> as Assets {
-        /// The number of units of assets held by any given account.
-        Balances: map hasher(blake2_128_concat) (T::AssetId, T::AccountId) => T::Balance;
-        /// The next asset identifier up for grabs.
-        pub NextAssetId get(fn next_asset_id): T::AssetId;
-        /// The total unit supply of an asset.
+        /// Cancel the approval of `delegate` to transfer asset `id` from the account of `origin`.
         ///
-        /// TWOX-NOTE: `AssetId` is trusted, so this is safe.
-        TotalSupply: map hasher(twox_64_concat) T::AssetId => T::Balance;
-        Creator: map hasher(blake2_128_concat) T::AssetId => T::AccountId;
-    }
-}
+        /// # <weight>
+        /// - `O(1)`
+        /// - 1 static lookup
+        /// - 1 storage deletion (codec `O(1)`).
+        /// - 1 event.
+        /// # </weight>
+        #[weight = T::WeightInfo::cancel_approval()]
+        fn cancel_approval(origin,
+            #[compact] id: T::AssetId,
+            delegate: <T::Lookup as StaticLookup>::Source
+        ) {
+            let origin = ensure_signed(origin)?;
+            let delegate = T::Lookup::lookup(delegate)?;
+            let key = (origin.clone(), delegate.clone());
+            ensure!(<Approvals<T>>::contains_key(id, &key), Error::<T>::NotApproved);
 
-// The main implementation block for the module.
-impl<T: Trait> Module<T> {
-    // Public immutables
-    /// Get the asset `id` balance of `who`.
-    pub fn balance(id: T::AssetId, who: T::AccountId) -> T::Balance {
-        <Balances<T>>::get((id, who))
-    }
+            <Approvals<T>>::remove(id, &key);
+            Self::deposit_event(RawEvent::ApprovalCancelled(id, origin, delegate));
+        }
+
+        /// Transfer `amount` of asset `id` from `owner` to `destination`, spending the approval
+        /// `owner` granted to `origin`.
+        ///
+        /// The approval is decremented by `amount` and removed once it reaches zero.
+        ///
+        /// # <weight>
+        /// - `O(1)`
+        /// - 2 static lookups
+        /// - 1 storage mutation of the approval (codec `O(1)`).
+        /// - 2 storage mutations of balances (codec `O(1)`).
+        /// - 1 event.
+        /// # </weight>
+        #[weight = T::WeightInfo::transfer_approved()]
+        fn transfer_approved(origin,
+            #[compact] id: T::AssetId,
+            owner: <T::Lookup as StaticLookup>::Source,
+            destination: <T::Lookup as StaticLookup>::Source,
+            #[compact] amount: T::Balance
+        ) {
+            let delegate = ensure_signed(origin)?;
+            let owner = T::Lookup::lookup(owner)?;
+            let destination = T::Lookup::lookup(destination)?;
+            Self::transfer_from_delegate(&id, &owner, &delegate, &destination, &amount)?;
+        }
+
+        /// Set the name, symbol and decimals of an asset `id` created by `origin`.
+        ///
+        /// A deposit of `MetadataDepositBase` plus `MetadataDepositPerByte` for every byte of
+        /// `name` and `symbol` is reserved from the creator. Overwriting existing metadata
+        /// reserves or unreserves only the difference.
+        ///
+        /// # <weight>
+        /// - `O(N)` where `N` is `name.len() + symbol.len()`, bounded by `StringLimit`.
+        /// - 2 storage reads (codec `O(1)`, `O(N)`).
+        /// - 1 storage write (codec `O(N)`).
+        /// - 1 reserve or unreserve.
+        /// - 1 event.
+        /// # </weight>
+        #[weight = T::WeightInfo::set_metadata(name.len() as u32, symbol.len() as u32)]
+        fn set_metadata(origin,
+            #[compact] id: T::AssetId,
+            name: Vec<u8>,
+            symbol: Vec<u8>,
+            decimals: u8
+        ) {
+            let origin = ensure_signed(origin)?;
+            let limit = T::StringLimit::get() as usize;
+            ensure!(name.len() <= limit, Error::<T>::BadMetadata);
+            ensure!(symbol.len() <= limit, Error::<T>::BadMetadata);
+            ensure!(<Creator<T>>::contains_key(id), Error::<T>::Unknown);
+            ensure!(origin == <Creator<T>>::get(id), Error::<T>::NotTheCreator);
+
+            let old_deposit = <Metadata<T>>::get(id).map_or_else(Zero::zero, |m| m.deposit);
+            let bytes = (name.len() + symbol.len()) as u32;
+            let new_deposit = T::MetadataDepositPerByte::get()
+                .saturating_mul(bytes.into())
+                .saturating_add(T::MetadataDepositBase::get());
+
+            if new_deposit > old_deposit {
+                T::Currency::reserve(&origin, new_deposit - old_deposit)?;
+            } else {
+                T::Currency::unreserve(&origin, old_deposit - new_deposit);
+            }
+
+            <Metadata<T>>::insert(id, AssetMetadata {
+                deposit: new_deposit,
+                name: name.clone(),
+                symbol: symbol.clone(),
+                decimals,
+            });
+            Self::deposit_event(RawEvent::MetadataSet(id, name, symbol, decimals));
+        }
+
+        /// Clear the metadata of an asset `id` created by `origin` and return its deposit.
+        ///
+        /// # <weight>
+        /// - `O(1)`
+        /// - 1 storage read (codec `O(1)`).
+        /// - 1 storage deletion (codec `O(1)`).
+        /// - 1 unreserve.
+        /// - 1 event.
+        /// # </weight>
+        #[weight = T::WeightInfo::clear_metadata()]
+        fn clear_metadata(origin, #[compact] id: T::AssetId) {
+            let origin = ensure_signed(origin)?;
+            ensure!(<Creator<T>>::contains_key(id), Error::<T>::Unknown);
+            ensure!(origin == <Creator<T>>::get(id), Error::<T>::NotTheCreator);
+
+            let metadata = <Metadata<T>>::take(id).ok_or(Error::<T>::Unknown)?;
+            T::Currency::unreserve(&origin, metadata.deposit);
+            Self::deposit_event(RawEvent::MetadataCleared(id));
+        }
+
+        /// Start destroying asset `id` created by `origin`.
+        ///
+        /// From now on the asset can no longer be credited, withdrawn or approved. Its holdings
+        /// and approvals are then removed with `destroy_accounts`, and the asset itself with
+        /// `finish_destroy`.
+        ///
+        /// # <weight>
+        /// - `O(1)`
+        /// - 2 storage reads (codec `O(1)`).
+        /// - 1 storage write (codec `O(1)`).
+        /// - 1 event.
+        /// # </weight>
+        #[weight = T::WeightInfo::start_destroy()]
+        fn start_destroy(origin, #[compact] id: T::AssetId) {
+            let origin = ensure_signed(origin)?;
+            ensure!(<Creator<T>>::contains_key(id), Error::<T>::Unknown);
+            ensure!(origin == <Creator<T>>::get(id), Error::<T>::NotTheCreator);
+            ensure!(!Self::is_destroying(id), Error::<T>::Destroying);
+
+            <Destroying<T>>::insert(id, true);
+            Self::deposit_event(RawEvent::DestructionStarted(id));
+        }
+
+        /// Remove up to `max` holdings, approvals and items of asset `id`, which `origin` is
+        /// destroying.
+        ///
+        /// Holdings go first. Their free and reserved balances leave the total supply without
+        /// being handed to `OnDust`, and their locks and freezes are dropped. Items of a
+        /// non-fungible class go last, along with their attributes.
+        ///
+        /// # <weight>
+        /// - `O(N)` where `N` is `max`.
+        /// - 3 storage reads (codec `O(1)`).
+        /// - `N` storage iterations (codec `O(1)`).
+        /// - up to `7 * N` storage deletions (codec `O(1)`).
+        /// - up to `MaxAttributes * N` attribute deletions (codec `O(1)`).
+        /// - 1 event.
+        /// # </weight>
+        #[weight = T::WeightInfo::destroy_accounts(*max).saturating_add(
+            T::DbWeight::get()
+                .writes((*max as Weight).saturating_mul(T::MaxAttributes::get() as Weight))
+        )]
+        fn destroy_accounts(origin, #[compact] id: T::AssetId, max: u32) {
+            let origin = ensure_signed(origin)?;
+            ensure!(<Creator<T>>::contains_key(id), Error::<T>::Unknown);
+            ensure!(origin == <Creator<T>>::get(id), Error::<T>::NotTheCreator);
+            ensure!(Self::is_destroying(id), Error::<T>::NotDestroying);
+
+            let holders = <Accounts<T>>::iter_prefix(id)
+                .take(max as usize)
+                .map(|(who, _)| who)
+                .collect::<Vec<_>>();
+            let mut supply = <TotalSupply<T>>::get(id);
+            for who in holders.iter() {
+                let key = (id, who);
+                supply = supply
+                    .saturating_sub(<Balances<T>>::take(&key))
+                    .saturating_sub(<Reserved<T>>::take(&key));
+                <Locks<T>>::remove(&key);
+                <FrozenAccounts<T>>::remove(&key);
+                <Vesting<T>>::remove(&key);
+                <Accounts<T>>::remove(id, who);
+                <AccountAssets<T>>::remove(who, id);
+            }
+            <TotalSupply<T>>::insert(id, supply);
+
+            let approvals = <Approvals<T>>::iter_prefix(id)
+                .take(max as usize - holders.len())
+                .map(|(key, _)| key)
+                .collect::<Vec<_>>();
+            for key in approvals.iter() {
+                <Approvals<T>>::remove(id, key);
+            }
+
+            let items = <Items<T>>::iter_prefix(id)
+                .take(max as usize - holders.len() - approvals.len())
+                .collect::<Vec<_>>();
+            for (item, details) in items.iter() {
+                Self::do_burn_item(id, *item, details);
+            }
+
+            Self::deposit_event(RawEvent::AccountsDestroyed(
+                id,
+                holders.len() as u32,
+                approvals.len() as u32,
+                items.len() as u32,
+            ));
+        }
+
+        /// Remove asset `id`, which `origin` is destroying and which has no holdings, approvals
+        /// or items left, and return its metadata deposit.
+        ///
+        /// # <weight>
+        /// - `O(1)`
+        /// - 5 storage reads (codec `O(1)`).
+        /// - 7 storage deletions (codec `O(1)`).
+        /// - 1 unreserve.
+        /// - 1 event.
+        /// # </weight>
+        #[weight = T::WeightInfo::finish_destroy()]
+        fn finish_destroy(origin, #[compact] id: T::AssetId) {
+            let origin = ensure_signed(origin)?;
+            ensure!(<Creator<T>>::contains_key(id), Error::<T>::Unknown);
+            ensure!(origin == <Creator<T>>::get(id), Error::<T>::NotTheCreator);
+            ensure!(Self::is_destroying(id), Error::<T>::NotDestroying);
+            ensure!(<Accounts<T>>::iter_prefix(id).next().is_none(), Error::<T>::InUse);
+            ensure!(<Approvals<T>>::iter_prefix(id).next().is_none(), Error::<T>::InUse);
+            ensure!(Self::item_count(id) == 0, Error::<T>::InUse);
+
+            if let Some(metadata) = <Metadata<T>>::take(id) {
+                T::Currency::unreserve(&origin, metadata.deposit);
+            }
+            <TotalSupply<T>>::remove(id);
+            <MinBalance<T>>::remove(id);
+            <Creator<T>>::remove(id);
+            <Roles<T>>::remove(id);
+            <FrozenAssets<T>>::remove(id);
+            <NonFungible<T>>::remove(id);
+            <ItemCount<T>>::remove(id);
+            <Destroying<T>>::remove(id);
+            Self::deposit_event(RawEvent::AssetDestroyed(id));
+        }
+
+        /// Create a new non-fungible asset class owned by `origin`.
+        ///
+        /// The class shares its identifier space, roles, freezing and metadata with fungible
+        /// assets, but holds items minted with `mint_item` instead of balances.
+        ///
+        /// # <weight>
+        /// - `O(1)`
+        /// - 1 storage read (codec `O(1)`).
+        /// - 4 storage writes (codec `O(1)`).
+        /// - 1 event.
+        /// # </weight>
+        #[weight = T::WeightInfo::create_class()]
+        fn create_class(origin) {
+            let origin = ensure_signed(origin)?;
+
+            let id = Self::allocate_asset_id();
+            <Creator<T>>::insert(id, &origin);
+            <Roles<T>>::insert(id, AssetRoles {
+                issuer: origin.clone(),
+                admin: origin.clone(),
+                freezer: origin.clone(),
+            });
+            <NonFungible<T>>::insert(id, true);
+
+            Self::deposit_event(RawEvent::Issued(id, origin, Zero::zero()));
+        }
+
+        /// Mint item `item` of non-fungible class `id` to `owner`. Callable by the issuer of
+        /// the class.
+        ///
+        /// # <weight>
+        /// - `O(1)`
+        /// - 1 static lookup
+        /// - 4 storage reads (codec `O(1)`).
+        /// - 3 storage writes (codec `O(1)`).
+        /// - 1 event.
+        /// # </weight>
+        #[weight = T::WeightInfo::mint_item()]
+        fn mint_item(origin,
+            #[compact] id: T::AssetId,
+            #[compact] item: T::ItemId,
+            owner: <T::Lookup as StaticLookup>::Source
+        ) {
+            let origin = ensure_signed(origin)?;
+            let owner = T::Lookup::lookup(owner)?;
+            let roles = Self::roles(id).ok_or(Error::<T>::Unknown)?;
+            ensure!(origin == roles.issuer, Error::<T>::NoPermission);
+            ensure!(Self::is_non_fungible(id), Error::<T>::Fungible);
+            ensure!(!Self::is_destroying(id), Error::<T>::Destroying);
+            ensure!(!<Items<T>>::contains_key(id, item), Error::<T>::ItemExists);
+            let count = Self::item_count(id).checked_add(1).ok_or(Error::<T>::Overflow)?;
+
+            <Items<T>>::insert(id, item, ItemDetails {
+                owner: owner.clone(),
+                approved: None,
+                attributes: 0,
+            });
+            <ItemsOfOwner<T>>::insert(&owner, (id, item), ());
+            <ItemCount<T>>::insert(id, count);
+            Self::deposit_event(RawEvent::ItemMinted(id, item, owner));
+        }
+
+        /// Transfer item `item` of class `id` to `dest`. Callable by the owner of the item or
+        /// the account it approved; the approval is cleared.
+        ///
+        /// # <weight>
+        /// - `O(1)`
+        /// - 1 static lookup
+        /// - 4 storage reads (codec `O(1)`).
+        /// - 3 storage writes (codec `O(1)`).
+        /// - 1 event.
+        /// # </weight>
+        #[weight = T::WeightInfo::transfer_item()]
+        fn transfer_item(origin,
+            #[compact] id: T::AssetId,
+            #[compact] item: T::ItemId,
+            dest: <T::Lookup as StaticLookup>::Source
+        ) {
+            let origin = ensure_signed(origin)?;
+            let dest = T::Lookup::lookup(dest)?;
+            let mut details = <Items<T>>::get(id, item).ok_or(Error::<T>::UnknownItem)?;
+            let owner = details.owner.clone();
+            ensure!(
+                origin == owner || details.approved.as_ref() == Some(&origin),
+                Error::<T>::NoPermission
+            );
+            ensure!(!Self::is_destroying(id), Error::<T>::Destroying);
+            ensure!(!Self::is_frozen(id, &owner), Error::<T>::Frozen);
+
+            <ItemsOfOwner<T>>::remove(&owner, (id, item));
+            <ItemsOfOwner<T>>::insert(&dest, (id, item), ());
+            details.owner = dest.clone();
+            details.approved = None;
+            <Items<T>>::insert(id, item, details);
+            Self::deposit_event(RawEvent::ItemTransferred(id, item, owner, dest));
+        }
+
+        /// Approve `delegate` to transfer item `item` of class `id` owned by `origin`,
+        /// replacing any earlier approval.
+        ///
+        /// # <weight>
+        /// - `O(1)`
+        /// - 1 static lookup
+        /// - 2 storage reads (codec `O(1)`).
+        /// - 1 storage write (codec `O(1)`).
+        /// - 1 event.
+        /// # </weight>
+        #[weight = T::WeightInfo::approve_item()]
+        fn approve_item(origin,
+            #[compact] id: T::AssetId,
+            #[compact] item: T::ItemId,
+            delegate: <T::Lookup as StaticLookup>::Source
+        ) {
+            let origin = ensure_signed(origin)?;
+            let delegate = T::Lookup::lookup(delegate)?;
+            let mut details = <Items<T>>::get(id, item).ok_or(Error::<T>::UnknownItem)?;
+            ensure!(origin == details.owner, Error::<T>::NoPermission);
+            ensure!(!Self::is_destroying(id), Error::<T>::Destroying);
+
+            details.approved = Some(delegate.clone());
+            <Items<T>>::insert(id, item, details);
+            Self::deposit_event(RawEvent::ItemApproved(id, item, origin, delegate));
+        }
+
+        /// Cancel the approval on item `item` of class `id` owned by `origin`.
+        ///
+        /// # <weight>
+        /// - `O(1)`
+        /// - 1 storage read (codec `O(1)`).
+        /// - 1 storage write (codec `O(1)`).
+        /// - 1 event.
+        /// # </weight>
+        #[weight = T::WeightInfo::cancel_item_approval()]
+        fn cancel_item_approval(origin, #[compact] id: T::AssetId, #[compact] item: T::ItemId) {
+            let origin = ensure_signed(origin)?;
+            let mut details = <Items<T>>::get(id, item).ok_or(Error::<T>::UnknownItem)?;
+            ensure!(origin == details.owner, Error::<T>::NoPermission);
+            ensure!(details.approved.is_some(), Error::<T>::NotApproved);
+
+            details.approved = None;
+            <Items<T>>::insert(id, item, details);
+            Self::deposit_event(RawEvent::ItemApprovalCancelled(id, item, origin));
+        }
+
+        /// Burn item `item` of class `id` owned by `origin`, along with its attributes.
+        ///
+        /// # <weight>
+        /// - `O(A)` where `A` is the number of attributes, bounded by `MaxAttributes`.
+        /// - 4 storage reads (codec `O(1)`).
+        /// - `A + 3` storage deletions or writes (codec `O(1)`).
+        /// - 1 event.
+        /// # </weight>
+        #[weight = T::WeightInfo::burn_item()]
+        fn burn_item(origin, #[compact] id: T::AssetId, #[compact] item: T::ItemId) {
+            let origin = ensure_signed(origin)?;
+            let details = <Items<T>>::get(id, item).ok_or(Error::<T>::UnknownItem)?;
+            ensure!(origin == details.owner, Error::<T>::NoPermission);
+            ensure!(!Self::is_destroying(id), Error::<T>::Destroying);
+            ensure!(!Self::is_frozen(id, &origin), Error::<T>::Frozen);
+
+            Self::do_burn_item(id, item, &details);
+            Self::deposit_event(RawEvent::ItemBurned(id, item, origin));
+        }
+
+        /// Set the attribute `key` of item `item` of class `id` to `value`. Callable by the
+        /// issuer of the class.
+        ///
+        /// # <weight>
+        /// - `O(K + V)` where `K` and `V` are the lengths of `key` and `value`, bounded by
+        ///   `StringLimit`.
+        /// - 3 storage reads (codec `O(1)`, `O(V)`).
+        /// - 2 storage writes (codec `O(1)`, `O(V)`).
+        /// - 1 event.
+        /// # </weight>
+        #[weight = T::WeightInfo::set_attribute(key.len() as u32, value.len() as u32)]
+        fn set_attribute(origin,
+            #[compact] id: T::AssetId,
+            #[compact] item: T::ItemId,
+            key: Vec<u8>,
+            value: Vec<u8>
+        ) {
+            let origin = ensure_signed(origin)?;
+            let limit = T::StringLimit::get() as usize;
+            ensure!(key.len() <= limit && value.len() <= limit, Error::<T>::BadMetadata);
+            let roles = Self::roles(id).ok_or(Error::<T>::Unknown)?;
+            ensure!(origin == roles.issuer, Error::<T>::NoPermission);
+            let mut details = <Items<T>>::get(id, item).ok_or(Error::<T>::UnknownItem)?;
+
+            if !<Attributes<T>>::contains_key((id, item), &key) {
+                ensure!(details.attributes < T::MaxAttributes::get(), Error::<T>::TooManyAttributes);
+                details.attributes += 1;
+                <Items<T>>::insert(id, item, details);
+            }
+            <Attributes<T>>::insert((id, item), &key, &value);
+            Self::deposit_event(RawEvent::AttributeSet(id, item, key, value));
+        }
+
+        /// Clear the attribute `key` of item `item` of class `id`. Callable by the issuer of
+        /// the class.
+        ///
+        /// # <weight>
+        /// - `O(1)`
+        /// - 3 storage reads (codec `O(1)`).
+        /// - 1 storage deletion (codec `O(1)`).
+        /// - 1 storage write (codec `O(1)`).
+        /// - 1 event.
+        /// # </weight>
+        #[weight = T::WeightInfo::clear_attribute()]
+        fn clear_attribute(origin,
+            #[compact] id: T::AssetId,
+            #[compact] item: T::ItemId,
+            key: Vec<u8>
+        ) {
+            let origin = ensure_signed(origin)?;
+            let roles = Self::roles(id).ok_or(Error::<T>::Unknown)?;
+            ensure!(origin == roles.issuer, Error::<T>::NoPermission);
+            let mut details = <Items<T>>::get(id, item).ok_or(Error::<T>::UnknownItem)?;
+            ensure!(<Attributes<T>>::contains_key((id, item), &key), Error::<T>::UnknownAttribute);
+
+            <Attributes<T>>::remove((id, item), &key);
+            details.attributes -= 1;
+            <Items<T>>::insert(id, item, details);
+            Self::deposit_event(RawEvent::AttributeCleared(id, item, key));
+        }
+
+        /// Transfer `schedule.locked` of asset `id` from `origin` to `target`, locked under a
+        /// vesting schedule that releases `schedule.per_block` every block from
+        /// `schedule.starting_block`.
+        ///
+        /// `target` may have up to `MaxVestingSchedules` schedules per asset, and each must lock
+        /// at least `min_vested_transfer(id)`, so filling them costs the sender real funds.
+        ///
+        /// # <weight>
+        /// - `O(S)` where `S` is the number of schedules of `target`, bounded by
+        ///   `MaxVestingSchedules`.
+        /// - 1 static lookup
+        /// - 2 storage mutations of balances (codec `O(1)`).
+        /// - 1 storage mutation of schedules (codec `O(S)`).
+        /// - 1 storage mutation of locks (codec `O(1)`).
+        /// - 2 events.
+        /// # </weight>
+        #[weight = T::WeightInfo::vested_transfer()]
+        fn vested_transfer(origin,
+            #[compact] id: T::AssetId,
+            target: <T::Lookup as StaticLookup>::Source,
+            schedule: VestingSchedule<T::Balance, T::BlockNumber>
+        ) {
+            let origin = ensure_signed(origin)?;
+            let target = T::Lookup::lookup(target)?;
+            ensure!(!schedule.locked.is_zero(), Error::<T>::AmountZero);
+            ensure!(schedule.locked >= Self::min_vested_transfer(id), Error::<T>::AmountLow);
+            ensure!(!schedule.per_block.is_zero(), Error::<T>::InvalidSchedule);
+            let mut schedules = Self::vesting((id, &target));
+            ensure!(
+                schedules.len() < T::MaxVestingSchedules::get() as usize,
+                Error::<T>::TooManySchedules
+            );
+            let amount = schedule.locked;
+            Self::ensure_withdrawable(id, &origin, amount, WithdrawReason::Transfer.into())?;
+
+            Self::do_transfer(id, &origin, &target, amount)?;
+            schedules.push(schedule);
+            <Vesting<T>>::insert((id, &target), schedules);
+            Self::deposit_event(RawEvent::VestingScheduleAdded(id, origin, target.clone(), amount));
+            Self::update_vesting_lock(id, &target);
+        }
+
+        /// Unlock the vested part of every vesting schedule of `origin` in asset `id`, and drop
+        /// the schedules that have fully vested.
+        ///
+        /// # <weight>
+        /// - `O(S)` where `S` is the number of schedules of `origin`, bounded by
+        ///   `MaxVestingSchedules`.
+        /// - 1 storage mutation of schedules (codec `O(S)`).
+        /// - 1 storage mutation of locks (codec `O(1)`).
+        /// - 1 event.
+        /// # </weight>
+        #[weight = T::WeightInfo::vest()]
+        fn vest(origin, #[compact] id: T::AssetId) {
+            let origin = ensure_signed(origin)?;
+            ensure!(<Vesting<T>>::contains_key((id, &origin)), Error::<T>::NotVesting);
+
+            Self::update_vesting_lock(id, &origin);
+        }
+    }
+}
+
+decl_event! {
+    pub enum Event<T> where
+        <T as frame_system::Trait>::AccountId,
+        <T as Trait>::Balance,
+        <T as Trait>::AssetId,
+        <T as Trait>::ItemId,
+    {
+        /// Some assets were issued. \[asset_id, owner, total_supply\]
+        Issued(AssetId, AccountId, Balance),
+        /// Some assets were transferred. \[asset_id, from, to, amount\]
+        Transferred(AssetId, AccountId, AccountId, Balance),
+        /// Some assets were minted. \[asset_id, owner, balance]
+        Minted(AssetId, AccountId, Balance),
+        /// Some assets were burned. \[asset_id, owner, balance]
+        Burned(AssetId, AccountId, Balance),
+        /// Some assets were destroyed. \[asset_id, owner, balance\]
+        Destroyed(AssetId, AccountId, Balance),
+        /// An account fell below the minimum balance and was removed; its dust was handed to
+        /// `OnDust`. \[asset_id, who, dust\]
+        AccountReaped(AssetId, AccountId, Balance),
+        /// The issuer, admin and freezer of an asset were changed.
+        /// \[asset_id, issuer, admin, freezer\]
+        TeamChanged(AssetId, AccountId, AccountId, AccountId),
+        /// The owner of an asset was changed. \[asset_id, owner\]
+        OwnerChanged(AssetId, AccountId),
+        /// The holding of an account was frozen. \[asset_id, who\]
+        Frozen(AssetId, AccountId),
+        /// The holding of an account was thawed. \[asset_id, who\]
+        Thawed(AssetId, AccountId),
+        /// Every holding of an asset was frozen. \[asset_id\]
+        AssetFrozen(AssetId),
+        /// Every holding of an asset was thawed. \[asset_id\]
+        AssetThawed(AssetId),
+        /// Some assets were transferred by the admin. \[asset_id, from, to, amount\]
+        ForceTransferred(AssetId, AccountId, AccountId, Balance),
+        /// The minimum balance of an asset was changed. \[asset_id, min_balance\]
+        MinBalanceSet(AssetId, Balance),
+        /// A batch of transfers succeeded; a `Transferred` event was emitted for each of them.
+        /// \[asset_id, from, count, total\]
+        BatchTransferred(AssetId, AccountId, u32, Balance),
+        /// An asset was minted to many accounts; a `Minted` event was emitted for each of them.
+        /// \[asset_id, count, total\]
+        Airdropped(AssetId, u32, Balance),
+        /// A delegate was approved to transfer assets of an owner. \[asset_id, owner, delegate, amount\]
+        ApprovedTransfer(AssetId, AccountId, AccountId, Balance),
+        /// An approval was cancelled by its owner. \[asset_id, owner, delegate\]
+        ApprovalCancelled(AssetId, AccountId, AccountId),
+        /// A delegate transferred assets of an owner using its approval.
+        /// \[asset_id, owner, delegate, destination, amount\]
+        TransferredApproved(AssetId, AccountId, AccountId, AccountId, Balance),
+        /// New metadata has been set for an asset. \[asset_id, name, symbol, decimals\]
+        MetadataSet(AssetId, Vec<u8>, Vec<u8>, u8),
+        /// Metadata has been cleared for an asset. \[asset_id\]
+        MetadataCleared(AssetId),
+        /// The destruction of an asset has started. \[asset_id\]
+        DestructionStarted(AssetId),
+        /// Holdings, approvals and items of an asset being destroyed were removed.
+        /// \[asset_id, accounts, approvals, items\]
+        AccountsDestroyed(AssetId, u32, u32, u32),
+        /// An asset and all of its state were removed. \[asset_id\]
+        AssetDestroyed(AssetId),
+        /// An item of a non-fungible class was minted. \[asset_id, item_id, owner\]
+        ItemMinted(AssetId, ItemId, AccountId),
+        /// An item of a non-fungible class was transferred. \[asset_id, item_id, from, to\]
+        ItemTransferred(AssetId, ItemId, AccountId, AccountId),
+        /// An item of a non-fungible class was burned. \[asset_id, item_id, owner\]
+        ItemBurned(AssetId, ItemId, AccountId),
+        /// A delegate was approved to transfer an item. \[asset_id, item_id, owner, delegate\]
+        ItemApproved(AssetId, ItemId, AccountId, AccountId),
+        /// The approval on an item was cancelled by its owner. \[asset_id, item_id, owner\]
+        ItemApprovalCancelled(AssetId, ItemId, AccountId),
+        /// An attribute of an item was set. \[asset_id, item_id, key, value\]
+        AttributeSet(AssetId, ItemId, Vec<u8>, Vec<u8>),
+        /// An attribute of an item was cleared. \[asset_id, item_id, key\]
+        AttributeCleared(AssetId, ItemId, Vec<u8>),
+        /// Some assets were transferred under a vesting schedule. \[asset_id, from, to, locked\]
+        VestingScheduleAdded(AssetId, AccountId, AccountId, Balance),
+        /// The vesting lock of an account was updated. \[asset_id, who, unvested\]
+        VestingUpdated(AssetId, AccountId, Balance),
+        /// Every vesting schedule of an account has fully vested. \[asset_id, who\]
+        VestingCompleted(AssetId, AccountId),
+    }
+}
+
+decl_error! {
+    pub enum Error for Module<T: Trait> {
+        /// Transfer amount should be non-zero
+        AmountZero,
+        /// Account balance must be greater than or equal to the transfer amount
+        BalanceLow,
+        /// Balance should be non-zero
+        BalanceZero,
+        /// Not the creator of the asset
+        NotTheCreator,
+        /// Not the approver for the account
+        NotApproved,
+        /// Created by System
+        CreatedBySystem,
+        /// The given asset identifier has not been issued
+        Unknown,
+        /// Name or symbol is longer than `StringLimit`
+        BadMetadata,
+        /// A balance or the total supply would overflow
+        Overflow,
+        /// The resulting balance of a new account would be below the asset's minimum balance
+        BelowMinimum,
+        /// The transfer would reap the sender's account
+        WouldDie,
+        /// The origin does not hold the role required for this operation
+        NoPermission,
+        /// The account or the asset is frozen
+        Frozen,
+        /// The free balance would fall below a lock
+        LiquidityRestrictions,
+        /// A batch holds more than `MaxBatchSize` entries
+        TooManyTargets,
+        /// The asset is being destroyed
+        Destroying,
+        /// The asset is not being destroyed
+        NotDestroying,
+        /// The asset still has holdings, approvals or items
+        InUse,
+        /// The operation is not available for a non-fungible asset class
+        NonFungible,
+        /// The operation is only available for a non-fungible asset class
+        Fungible,
+        /// The given item does not exist
+        UnknownItem,
+        /// The item identifier is already in use in its class
+        ItemExists,
+        /// The item already has `MaxAttributes` attributes
+        TooManyAttributes,
+        /// The item has no attribute under the given key
+        UnknownAttribute,
+        /// The account has no vesting schedule for the asset
+        NotVesting,
+        /// The account already has `MaxVestingSchedules` schedules for the asset
+        TooManySchedules,
+        /// A vesting schedule must unlock a non-zero amount per block
+        InvalidSchedule,
+        /// The vested amount is below the minimum vested transfer of the asset
+        AmountLow,
+    }
+}
+
+decl_storage! {
+    trait Store for Module<T: Trait> as Assets {
+        /// The number of units of assets held by any given account, excluding reserved units.
+        Balances: map hasher(blake2_128_concat) (T::AssetId, T::AccountId) => T::Balance;
+        /// The number of units of assets of an account that are reserved by other pallets.
+        pub Reserved get(fn reserved): map hasher(blake2_128_concat) (T::AssetId, T::AccountId) => T::Balance;
+        /// The locks on the free balance of an account.
+        pub Locks get(fn locks): map hasher(blake2_128_concat) (T::AssetId, T::AccountId) => Vec<AssetLock<T::Balance>>;
+        /// The next asset identifier up for grabs.
+        pub NextAssetId get(fn next_asset_id): T::AssetId;
+        /// The total unit supply of an asset.
+        ///
+        /// TWOX-NOTE: `AssetId` is trusted, so this is safe.
+        TotalSupply: map hasher(twox_64_concat) T::AssetId => T::Balance;
+        /// The minimum balance an account may hold of an asset. Zero unless set by the creator.
+        ///
+        /// TWOX-NOTE: `AssetId` is trusted, so this is safe.
+        pub MinBalance get(fn min_balance): map hasher(twox_64_concat) T::AssetId => T::Balance;
+        Creator: map hasher(blake2_128_concat) T::AssetId => T::AccountId;
+        /// The issuer, admin and freezer of an asset.
+        ///
+        /// TWOX-NOTE: `AssetId` is trusted, so this is safe.
+        pub Roles get(fn roles): map hasher(twox_64_concat) T::AssetId => Option<AssetRoles<T::AccountId>>;
+        /// Asset classes whose holdings are all frozen.
+        ///
+        /// TWOX-NOTE: `AssetId` is trusted, so this is safe.
+        FrozenAssets: map hasher(twox_64_concat) T::AssetId => bool;
+        /// Individually frozen holdings.
+        FrozenAccounts: map hasher(blake2_128_concat) (T::AssetId, T::AccountId) => bool;
+        /// The amount of an asset that a delegate may transfer on behalf of an owner.
+        /// Keyed by `asset_id` and `(owner, delegate)`.
+        ///
+        /// TWOX-NOTE: `AssetId` is trusted, so this is safe.
+        Approvals: double_map hasher(twox_64_concat) T::AssetId, hasher(blake2_128_concat) (T::AccountId, T::AccountId) => T::Balance;
+        /// The name, symbol and decimals of an asset, set by its creator.
+        ///
+        /// TWOX-NOTE: `AssetId` is trusted, so this is safe.
+        pub Metadata get(fn metadata): map hasher(twox_64_concat) T::AssetId => Option<AssetMetadata<DepositBalanceOf<T>>>;
+        /// The accounts holding a free or reserved balance of an asset.
+        ///
+        /// TWOX-NOTE: `AssetId` is trusted, so this is safe.
+        Accounts: double_map hasher(twox_64_concat) T::AssetId, hasher(blake2_128_concat) T::AccountId => ();
+        /// The assets an account holds a free or reserved balance of. The reverse of `Accounts`.
+        ///
+        /// TWOX-NOTE: `AssetId` is trusted, so this is safe.
+        AccountAssets: double_map hasher(blake2_128_concat) T::AccountId, hasher(twox_64_concat) T::AssetId => ();
+        /// Assets being destroyed. They can no longer be credited, withdrawn or approved.
+        ///
+        /// TWOX-NOTE: `AssetId` is trusted, so this is safe.
+        pub Destroying get(fn is_destroying): map hasher(twox_64_concat) T::AssetId => bool;
+        /// Asset classes holding non-fungible items rather than balances.
+        ///
+        /// TWOX-NOTE: `AssetId` is trusted, so this is safe.
+        pub NonFungible get(fn is_non_fungible): map hasher(twox_64_concat) T::AssetId => bool;
+        /// The number of items in a non-fungible asset class.
+        ///
+        /// TWOX-NOTE: `AssetId` is trusted, so this is safe.
+        pub ItemCount get(fn item_count): map hasher(twox_64_concat) T::AssetId => u32;
+        /// The items of non-fungible asset classes.
+        ///
+        /// TWOX-NOTE: `AssetId` is trusted, so this is safe.
+        pub Items get(fn item): double_map hasher(twox_64_concat) T::AssetId, hasher(blake2_128_concat) T::ItemId => Option<ItemDetails<T::AccountId>>;
+        /// The items held by an account, keyed by `(asset_id, item_id)`.
+        ItemsOfOwner: double_map hasher(blake2_128_concat) T::AccountId, hasher(blake2_128_concat) (T::AssetId, T::ItemId) => ();
+        /// The attributes of an item, keyed by `(asset_id, item_id)` and attribute key.
+        pub Attributes get(fn attribute): double_map hasher(blake2_128_concat) (T::AssetId, T::ItemId), hasher(blake2_128_concat) Vec<u8> => Option<Vec<u8>>;
+        /// The vesting schedules of an account in an asset.
+        pub Vesting get(fn vesting): map hasher(blake2_128_concat) (T::AssetId, T::AccountId) => Vec<VestingSchedule<T::Balance, T::BlockNumber>>;
+    }
+    add_extra_genesis {
+        /// Asset classes to create: `(id, creator, min_balance)`. The creator also takes the
+        /// issuer, admin and freezer roles.
+        config(assets): Vec<(T::AssetId, T::AccountId, T::Balance)>;
+        /// Metadata of genesis assets: `(id, name, symbol, decimals)`. No deposit is reserved.
+        config(metadata): Vec<(T::AssetId, Vec<u8>, Vec<u8>, u8)>;
+        /// Initial holdings of genesis assets: `(id, who, amount)`.
+        config(accounts): Vec<(T::AssetId, T::AccountId, T::Balance)>;
+        build(|config: &GenesisConfig<T>| {
+            for (id, creator, min_balance) in &config.assets {
+                assert!(*id != T::NativeAssetId::get(), "Asset id is reserved for the native currency");
+                assert!(!<Creator<T>>::contains_key(id), "Asset id already in use");
+                <Creator<T>>::insert(id, creator);
+                <Roles<T>>::insert(id, AssetRoles {
+                    issuer: creator.clone(),
+                    admin: creator.clone(),
+                    freezer: creator.clone(),
+                });
+                <MinBalance<T>>::insert(id, min_balance);
+                if *id >= <NextAssetId<T>>::get() {
+                    <NextAssetId<T>>::put(*id + One::one());
+                }
+            }
+
+            let limit = T::StringLimit::get() as usize;
+            for (id, name, symbol, decimals) in &config.metadata {
+                assert!(<Creator<T>>::contains_key(id), "Metadata for an unknown asset");
+                assert!(name.len() <= limit && symbol.len() <= limit, "Asset name or symbol too long");
+                <Metadata<T>>::insert(id, AssetMetadata {
+                    deposit: Zero::zero(),
+                    name: name.clone(),
+                    symbol: symbol.clone(),
+                    decimals: *decimals,
+                });
+            }
+
+            for (id, who, amount) in &config.accounts {
+                assert!(<Creator<T>>::contains_key(id), "Holding of an unknown asset");
+                <Module<T>>::mutate_balance(*id, who, BalanceChange::Credit(*amount))
+                    .expect("Genesis holdings must be at least the minimum balance");
+            }
+        })
+    }
+}
+
+// The main implementation block for the module.
+impl<T: Trait> Module<T> {
+    // Public immutables
+    /// Get the asset `id` balance of `who`.
+    pub fn balance(id: T::AssetId, who: T::AccountId) -> T::Balance {
+        <Balances<T>>::get((id, who))
+    }
 
     /// Get the total supply of an asset `id`.
     pub fn total_supply(id: T::AssetId) -> T::Balance {
         <TotalSupply<T>>::get(id)
     }
 
+    /// Get the free balance of `who` in every asset it holds.
+    pub fn account_balances(who: &T::AccountId) -> Vec<(T::AssetId, T::Balance)> {
+        <AccountAssets<T>>::iter_prefix(who)
+            .map(|(id, _)| (id, <Balances<T>>::get((id, who))))
+            .collect()
+    }
+
+    /// Get the amount of asset `id` that `delegate` may transfer on behalf of `owner`.
+    pub fn allowance(id: T::AssetId, owner: T::AccountId, delegate: T::AccountId) -> T::Balance {
+        <Approvals<T>>::get(id, (owner, delegate))
+    }
+
+    /// Transfer `amount` of asset `id` from `owner` to `destination` on behalf of `delegate`,
+    /// decrementing the approval `owner` granted to `delegate`.
+    ///
+    /// This lets other pallets pull funds that a user has approved them to spend.
+    pub fn transfer_from_delegate(
+        id: &T::AssetId,
+        owner: &T::AccountId,
+        delegate: &T::AccountId,
+        destination: &T::AccountId,
+        amount: &T::Balance,
+    ) -> dispatch::DispatchResult {
+        ensure!(!amount.is_zero(), Error::<T>::AmountZero);
+        let approval_key = (owner.clone(), delegate.clone());
+        let approved = <Approvals<T>>::get(id, &approval_key);
+        ensure!(approved >= *amount, Error::<T>::NotApproved);
+        Self::ensure_withdrawable(*id, owner, *amount, WithdrawReason::Transfer.into())?;
+
+        Self::do_transfer(*id, owner, destination, *amount)?;
+        let remaining = approved - *amount;
+        if remaining.is_zero() {
+            <Approvals<T>>::remove(id, &approval_key);
+        } else {
+            <Approvals<T>>::insert(id, &approval_key, remaining);
+        }
+
+        Self::deposit_event(RawEvent::TransferredApproved(
+            *id,
+            owner.clone(),
+            delegate.clone(),
+            destination.clone(),
+            *amount,
+        ));
+        Ok(())
+    }
+
     pub fn mint_from_system(
         id: &T::AssetId,
         target: &T::AccountId,
         amount: &T::Balance,
     ) -> dispatch::DispatchResult {
         ensure!(!amount.is_zero(), Error::<T>::AmountZero);
+        Self::mutate_balance(*id, target, BalanceChange::Credit(*amount))?;
         Self::deposit_event(RawEvent::Minted(*id, target.clone(), *amount));
-        <Balances<T>>::mutate((*id, target), |balance| *balance += *amount);
         Ok(())
     }
 
@@ -363,20 +1755,570 @@ impl<T: Trait> Module<T> {
         amount: &T::Balance,
     ) -> dispatch::DispatchResult {
         ensure!(!amount.is_zero(), Error::<T>::AmountZero);
+        Self::ensure_withdrawable(*id, target, *amount, WithdrawReason::Transfer.into())?;
+        Self::mutate_balance(*id, target, BalanceChange::Debit(*amount))?;
         Self::deposit_event(RawEvent::Burned(*id, target.clone(), *amount));
-        <Balances<T>>::mutate((*id, target), |balance| *balance -= *amount);
         Ok(())
     }
 
     pub fn issue_from_system(total: T::Balance) -> dispatch::DispatchResult {
-        let id = Self::next_asset_id();
-        <NextAssetId<T>>::mutate(|id| *id += One::one());
-
-        <Balances<T>>::insert((id, &T::AccountId::default()), total);
-        <TotalSupply<T>>::insert(id, total);
+        let id = Self::allocate_asset_id();
+        Self::mutate_balance(id, &T::AccountId::default(), BalanceChange::Credit(total))?;
         <Creator<T>>::insert(id, &T::AccountId::default());
+        <Roles<T>>::insert(id, AssetRoles::default());
 
         Self::deposit_event(RawEvent::Issued(id, T::AccountId::default(), total));
         Ok(())
     }
+
+    /// Take the next free asset identifier, skipping `NativeAssetId`.
+    fn allocate_asset_id() -> T::AssetId {
+        let mut id = Self::next_asset_id();
+        if id == T::NativeAssetId::get() {
+            id += One::one();
+        }
+        <NextAssetId<T>>::put(id + One::one());
+        id
+    }
+
+    /// Get the owner of item `item` of non-fungible class `id`, if it exists.
+    pub fn owner_of(id: T::AssetId, item: T::ItemId) -> Option<T::AccountId> {
+        <Items<T>>::get(id, item).map(|details| details.owner)
+    }
+
+    /// Get the `(asset_id, item_id)` of every item held by `who`.
+    pub fn items_of_owner(who: &T::AccountId) -> Vec<(T::AssetId, T::ItemId)> {
+        <ItemsOfOwner<T>>::iter_prefix(who).map(|(key, _)| key).collect()
+    }
+
+    /// The account that holds the native currency withdrawn through `MultiCurrency`.
+    pub fn account_id() -> T::AccountId {
+        T::ModuleId::get().into_account()
+    }
+
+    /// The least a vested transfer of asset `id` may lock: `MinVestedTransferFactor` times its
+    /// minimum balance, or times one unit if it has none.
+    pub fn min_vested_transfer(id: T::AssetId) -> T::Balance {
+        Self::min_balance(id)
+            .max(One::one())
+            .saturating_mul(T::MinVestedTransferFactor::get().into())
+    }
+
+    /// Whether the holding of `who` in asset `id` is frozen, individually or with its asset.
+    pub fn is_frozen(id: T::AssetId, who: &T::AccountId) -> bool {
+        <FrozenAssets<T>>::get(id) || <FrozenAccounts<T>>::get((id, who))
+    }
+
+    /// Ensure that `amount` of asset `id` can leave the free balance of `who` for `reasons`,
+    /// i.e. that the holding is not frozen and that no lock for `reasons` would be broken.
+    pub fn ensure_withdrawable(
+        id: T::AssetId,
+        who: &T::AccountId,
+        amount: T::Balance,
+        reasons: WithdrawReasons,
+    ) -> dispatch::DispatchResult {
+        ensure!(!Self::is_destroying(id), Error::<T>::Destroying);
+        ensure!(!Self::is_frozen(id, who), Error::<T>::Frozen);
+        let new_balance = <Balances<T>>::get((id, who))
+            .checked_sub(&amount)
+            .ok_or(Error::<T>::BalanceLow)?;
+        let locked = Self::locks(id, who)
+            .iter()
+            .filter(|lock| lock.reasons.intersects(reasons))
+            .map(|lock| lock.amount)
+            .max()
+            .unwrap_or_else(Zero::zero);
+        ensure!(new_balance >= locked, Error::<T>::LiquidityRestrictions);
+        Ok(())
+    }
+
+    /// Mint asset `id` to every `(target, amount)` in `recipients`, or to none of them if any
+    /// mint would fail.
+    ///
+    /// Emits a `Minted` event per recipient and a final `Airdropped` event.
+    pub fn do_airdrop(
+        id: T::AssetId,
+        recipients: Vec<(T::AccountId, T::Balance)>,
+    ) -> dispatch::DispatchResult {
+        ensure!(<Creator<T>>::contains_key(id), Error::<T>::Unknown);
+        let mut total: T::Balance = Zero::zero();
+        for (_, amount) in recipients.iter() {
+            ensure!(!amount.is_zero(), Error::<T>::AmountZero);
+            total = total.checked_add(amount).ok_or(Error::<T>::Overflow)?;
+        }
+        // Every balance is bounded by the supply, so this also rules out per-account overflow.
+        Self::total_supply(id).checked_add(&total).ok_or(Error::<T>::Overflow)?;
+        Self::ensure_can_credit_all(id, &recipients)?;
+
+        let count = recipients.len() as u32;
+        for (target, amount) in recipients {
+            // Cannot fail: checked above.
+            Self::mutate_balance(id, &target, BalanceChange::Credit(amount))?;
+            Self::deposit_event(RawEvent::Minted(id, target, amount));
+        }
+        Self::deposit_event(RawEvent::Airdropped(id, count, total));
+        Ok(())
+    }
+
+    /// Ensure that crediting every `(target, amount)` of asset `id` would not create an account
+    /// below the minimum balance.
+    fn ensure_can_credit_all(
+        id: T::AssetId,
+        credits: &[(T::AccountId, T::Balance)],
+    ) -> dispatch::DispatchResult {
+        let min_balance = Self::min_balance(id);
+        for (target, amount) in credits {
+            ensure!(
+                *amount >= min_balance || Self::account_exists(id, target),
+                Error::<T>::BelowMinimum
+            );
+        }
+        Ok(())
+    }
+
+    /// Whether `who` holds any free or reserved balance of asset `id`.
+    fn account_exists(id: T::AssetId, who: &T::AccountId) -> bool {
+        <Balances<T>>::contains_key((id, who)) || <Reserved<T>>::contains_key((id, who))
+    }
+
+    /// Lock what is still unvested of the schedules of `who` in asset `id` and drop the
+    /// schedules that have fully vested.
+    fn update_vesting_lock(id: T::AssetId, who: &T::AccountId) {
+        let now = <frame_system::Module<T>>::block_number();
+        let mut unvested: T::Balance = Zero::zero();
+        let mut schedules = Self::vesting((id, who));
+        schedules.retain(|schedule| {
+            let locked = schedule.locked_at::<T::BlockNumberToBalance>(now);
+            unvested = unvested.saturating_add(locked);
+            !locked.is_zero()
+        });
+
+        if schedules.is_empty() {
+            <Vesting<T>>::remove((id, who));
+            Self::do_remove_lock(VESTING_ID, id, who);
+            Self::deposit_event(RawEvent::VestingCompleted(id, who.clone()));
+        } else {
+            <Vesting<T>>::insert((id, who), schedules);
+            Self::do_set_lock(VESTING_ID, id, who, unvested, WithdrawReasons::all());
+            Self::deposit_event(RawEvent::VestingUpdated(id, who.clone(), unvested));
+        }
+    }
+
+    /// Remove item `item` of class `id`, described by `details`, and its attributes.
+    fn do_burn_item(id: T::AssetId, item: T::ItemId, details: &ItemDetails<T::AccountId>) {
+        <Attributes<T>>::remove_prefix((id, item));
+        <ItemsOfOwner<T>>::remove(&details.owner, (id, item));
+        <Items<T>>::remove(id, item);
+        <ItemCount<T>>::mutate(id, |count| *count = count.saturating_sub(1));
+    }
+
+    /// Keep the entry of `who` in the holders of asset `id` in line with its balances.
+    fn update_holder(id: T::AssetId, who: &T::AccountId) {
+        if Self::account_exists(id, who) {
+            <Accounts<T>>::insert(id, who, ());
+            <AccountAssets<T>>::insert(who, id, ());
+        } else {
+            <Accounts<T>>::remove(id, who);
+            <AccountAssets<T>>::remove(who, id);
+        }
+    }
+
+    /// Move `amount` of asset `id` from the free to the reserved balance of `who`.
+    fn do_reserve(id: T::AssetId, who: &T::AccountId, amount: T::Balance) -> dispatch::DispatchResult {
+        Self::ensure_withdrawable(id, who, amount, WithdrawReason::Reserve.into())?;
+        let new_reserved = Self::reserved(id, who)
+            .checked_add(&amount)
+            .ok_or(Error::<T>::Overflow)?;
+        // Cannot underflow: `ensure_withdrawable` proved the free balance covers `amount`.
+        let new_balance = <Balances<T>>::get((id, who)) - amount;
+
+        if new_balance.is_zero() {
+            <Balances<T>>::remove((id, who));
+        } else {
+            <Balances<T>>::insert((id, who), new_balance);
+        }
+        <Reserved<T>>::insert((id, who), new_reserved);
+        Ok(())
+    }
+
+    /// Move up to `amount` of asset `id` from the reserved to the free balance of `who`.
+    ///
+    /// Returns the amount that could not be unreserved.
+    fn do_unreserve(id: T::AssetId, who: &T::AccountId, amount: T::Balance) -> T::Balance {
+        let reserved = Self::reserved(id, who);
+        let actual = amount.min(reserved);
+        if actual.is_zero() {
+            return amount;
+        }
+
+        if reserved == actual {
+            <Reserved<T>>::remove((id, who));
+        } else {
+            <Reserved<T>>::insert((id, who), reserved - actual);
+        }
+        // Cannot overflow: free and reserved balances together are bounded by the total supply.
+        <Balances<T>>::mutate((id, who), |balance| *balance += actual);
+        amount - actual
+    }
+
+    /// Move up to `amount` of asset `id` from the reserved balance of `slashed` to the free or
+    /// reserved balance of `beneficiary`, as selected by `status`.
+    ///
+    /// Returns the amount that could not be moved.
+    fn do_repatriate_reserved(
+        id: T::AssetId,
+        slashed: &T::AccountId,
+        beneficiary: &T::AccountId,
+        amount: T::Balance,
+        status: BalanceStatus,
+    ) -> Result<T::Balance, dispatch::DispatchError> {
+        if slashed == beneficiary {
+            return Ok(match status {
+                BalanceStatus::Free => Self::do_unreserve(id, slashed, amount),
+                BalanceStatus::Reserved => amount.saturating_sub(Self::reserved(id, slashed)),
+            });
+        }
+
+        let reserved = Self::reserved(id, slashed);
+        let actual = amount.min(reserved);
+        ensure!(!Self::is_destroying(id), Error::<T>::Destroying);
+        ensure!(
+            Self::account_exists(id, beneficiary) || actual >= Self::min_balance(id),
+            Error::<T>::BelowMinimum
+        );
+
+        if reserved == actual {
+            <Reserved<T>>::remove((id, slashed));
+        } else {
+            <Reserved<T>>::insert((id, slashed), reserved - actual);
+        }
+        // Cannot overflow: both balances together are bounded by the total supply.
+        match status {
+            BalanceStatus::Free => <Balances<T>>::mutate((id, beneficiary), |b| *b += actual),
+            BalanceStatus::Reserved => <Reserved<T>>::mutate((id, beneficiary), |b| *b += actual),
+        }
+        Self::update_holder(id, slashed);
+        Self::update_holder(id, beneficiary);
+        Ok(amount - actual)
+    }
+
+    /// Add or replace the lock `lock_id` on the free balance of `who` in asset `id`.
+    fn do_set_lock(
+        lock_id: LockIdentifier,
+        id: T::AssetId,
+        who: &T::AccountId,
+        amount: T::Balance,
+        reasons: WithdrawReasons,
+    ) {
+        let mut locks = Self::locks(id, who);
+        let lock = AssetLock { id: lock_id, amount, reasons };
+        match locks.iter_mut().find(|l| l.id == lock_id) {
+            Some(existing) => *existing = lock,
+            None => locks.push(lock),
+        }
+        <Locks<T>>::insert((id, who), locks);
+    }
+
+    /// Extend the lock `lock_id` on the free balance of `who` in asset `id` to at least
+    /// `amount` and `reasons`, creating it if it does not exist.
+    fn do_extend_lock(
+        lock_id: LockIdentifier,
+        id: T::AssetId,
+        who: &T::AccountId,
+        amount: T::Balance,
+        reasons: WithdrawReasons,
+    ) {
+        let mut locks = Self::locks(id, who);
+        match locks.iter_mut().find(|l| l.id == lock_id) {
+            Some(existing) => {
+                existing.amount = existing.amount.max(amount);
+                existing.reasons = existing.reasons | reasons;
+            }
+            None => locks.push(AssetLock { id: lock_id, amount, reasons }),
+        }
+        <Locks<T>>::insert((id, who), locks);
+    }
+
+    /// Remove the lock `lock_id` from the free balance of `who` in asset `id`.
+    fn do_remove_lock(lock_id: LockIdentifier, id: T::AssetId, who: &T::AccountId) {
+        let mut locks = Self::locks(id, who);
+        locks.retain(|l| l.id != lock_id);
+        if locks.is_empty() {
+            <Locks<T>>::remove((id, who));
+        } else {
+            <Locks<T>>::insert((id, who), locks);
+        }
+    }
+
+    /// Move `amount` of asset `id` from `source` to `dest` without emitting an event.
+    fn do_transfer(
+        id: T::AssetId,
+        source: &T::AccountId,
+        dest: &T::AccountId,
+        amount: T::Balance,
+    ) -> dispatch::DispatchResult {
+        ensure!(!amount.is_zero(), Error::<T>::AmountZero);
+        if source == dest {
+            return Ok(());
+        }
+        // Check the credit up front so that a failure cannot follow a successful debit.
+        ensure!(
+            Self::account_exists(id, dest) || amount >= Self::min_balance(id),
+            Error::<T>::BelowMinimum
+        );
+
+        Self::mutate_balance(id, source, BalanceChange::Debit(amount))?;
+        // Cannot fail: the debit above proves `amount <= TotalSupply`, which bounds `dest`.
+        Self::mutate_balance(id, dest, BalanceChange::Credit(amount))
+    }
+
+    /// Apply `change` to the balance of `who` in asset `id` and to the asset's total supply.
+    ///
+    /// Outside of destroying an asset, this is the only place where `TotalSupply` is written,
+    /// and reserving only moves units between `Balances` and `Reserved`, which keeps
+    /// `TotalSupply == sum(Balances) + sum(Reserved)` for every asset. Both new values are
+    /// computed before anything is written, so an error leaves storage untouched.
+    ///
+    /// A debit that leaves an account without reserved balance, locks or vesting schedules below
+    /// the asset's minimum balance reaps it: the dust is removed from the total supply and passed
+    /// to `T::OnDust`, and any freeze of the account is lifted.
+    fn mutate_balance(
+        id: T::AssetId,
+        who: &T::AccountId,
+        change: BalanceChange<T::Balance>,
+    ) -> dispatch::DispatchResult {
+        let balance = <Balances<T>>::get((id, who));
+        let supply = <TotalSupply<T>>::get(id);
+        let min_balance = Self::min_balance(id);
+        let has_reserved = <Reserved<T>>::contains_key((id, who));
+        // Locked funds stay with the account, so it is not reaped while any lock remains.
+        let is_held = has_reserved
+            || !Self::locks((id, who)).is_empty()
+            || !Self::vesting((id, who)).is_empty();
+        let (new_balance, new_supply, dust) = match change {
+            BalanceChange::Credit(amount) => {
+                ensure!(!Self::is_destroying(id), Error::<T>::Destroying);
+                ensure!(!Self::is_non_fungible(id), Error::<T>::NonFungible);
+                let new_balance = balance.checked_add(&amount).ok_or(Error::<T>::Overflow)?;
+                ensure!(
+                    !balance.is_zero() || has_reserved || new_balance >= min_balance,
+                    Error::<T>::BelowMinimum
+                );
+                let new_supply = supply.checked_add(&amount).ok_or(Error::<T>::Overflow)?;
+                (new_balance, new_supply, Zero::zero())
+            }
+            BalanceChange::Debit(amount) => {
+                let mut new_balance = balance.checked_sub(&amount).ok_or(Error::<T>::BalanceLow)?;
+                let mut dust = Zero::zero();
+                if new_balance < min_balance && !is_held {
+                    dust = new_balance;
+                    new_balance = Zero::zero();
+                }
+                let new_supply = supply
+                    .checked_sub(&amount)
+                    .and_then(|s| s.checked_sub(&dust))
+                    .ok_or(Error::<T>::Overflow)?;
+                (new_balance, new_supply, dust)
+            }
+        };
+
+        if new_balance.is_zero() {
+            <Balances<T>>::remove((id, who));
+        } else {
+            <Balances<T>>::insert((id, who), new_balance);
+        }
+        <TotalSupply<T>>::insert(id, new_supply);
+        Self::update_holder(id, who);
+
+        if new_balance.is_zero() && !balance.is_zero() && !is_held {
+            <FrozenAccounts<T>>::remove((id, who));
+            if !dust.is_zero() {
+                T::OnDust::on_dust(id, who, dust);
+            }
+            Self::deposit_event(RawEvent::AccountReaped(id, who.clone(), dust));
+        }
+        Ok(())
+    }
+}
+
+impl<T: Trait> MultiCurrency<T::AccountId> for Module<T> {
+    type CurrencyId = T::AssetId;
+    type Balance = T::Balance;
+
+    fn minimum_balance(currency_id: T::AssetId) -> T::Balance {
+        if currency_id == T::NativeAssetId::get() {
+            T::Currency::minimum_balance()
+        } else {
+            Self::min_balance(currency_id)
+        }
+    }
+
+    fn total_issuance(currency_id: T::AssetId) -> T::Balance {
+        if currency_id == T::NativeAssetId::get() {
+            T::Currency::total_issuance()
+        } else {
+            Self::total_supply(currency_id)
+        }
+    }
+
+    fn free_balance(currency_id: T::AssetId, who: &T::AccountId) -> T::Balance {
+        if currency_id == T::NativeAssetId::get() {
+            T::Currency::free_balance(who)
+        } else {
+            <Balances<T>>::get((currency_id, who))
+        }
+    }
+
+    fn ensure_can_withdraw(
+        currency_id: T::AssetId,
+        who: &T::AccountId,
+        amount: T::Balance,
+    ) -> dispatch::DispatchResult {
+        if amount.is_zero() {
+            return Ok(());
+        }
+        let new_balance = Self::free_balance(currency_id, who)
+            .checked_sub(&amount)
+            .ok_or(Error::<T>::BalanceLow)?;
+        if currency_id == T::NativeAssetId::get() {
+            T::Currency::ensure_can_withdraw(who, amount, WithdrawReasons::all(), new_balance)
+        } else {
+            Self::ensure_withdrawable(currency_id, who, amount, WithdrawReasons::all())
+        }
+    }
+
+    fn transfer(
+        currency_id: T::AssetId,
+        from: &T::AccountId,
+        to: &T::AccountId,
+        amount: T::Balance,
+    ) -> dispatch::DispatchResult {
+        if currency_id == T::NativeAssetId::get() {
+            T::Currency::transfer(from, to, amount, ExistenceRequirement::AllowDeath)
+        } else {
+            Self::ensure_withdrawable(currency_id, from, amount, WithdrawReason::Transfer.into())?;
+            Self::do_transfer(currency_id, from, to, amount)?;
+            Self::deposit_event(RawEvent::Transferred(currency_id, from.clone(), to.clone(), amount));
+            Ok(())
+        }
+    }
+
+    fn deposit(
+        currency_id: T::AssetId,
+        who: &T::AccountId,
+        amount: T::Balance,
+    ) -> dispatch::DispatchResult {
+        if currency_id == T::NativeAssetId::get() {
+            // Paid out of what `withdraw` took in, so the total issuance stays put, and a deposit
+            // that cannot be credited fails rather than vanishing. The module account is kept
+            // alive, so it is never reaped along with the dust it holds.
+            T::Currency::transfer(&Self::account_id(), who, amount, ExistenceRequirement::KeepAlive)
+        } else {
+            Self::mint_from_system(&currency_id, who, &amount)
+        }
+    }
+
+    fn withdraw(
+        currency_id: T::AssetId,
+        who: &T::AccountId,
+        amount: T::Balance,
+    ) -> dispatch::DispatchResult {
+        if currency_id == T::NativeAssetId::get() {
+            // Held by the module account until deposited again.
+            T::Currency::transfer(who, &Self::account_id(), amount, ExistenceRequirement::AllowDeath)
+        } else {
+            Self::burn_from_system(&currency_id, who, &amount)
+        }
+    }
+}
+
+impl<T: Trait> MultiReservableCurrency<T::AccountId> for Module<T> {
+    fn can_reserve(currency_id: T::AssetId, who: &T::AccountId, value: T::Balance) -> bool {
+        if currency_id == T::NativeAssetId::get() {
+            T::Currency::can_reserve(who, value)
+        } else {
+            Self::ensure_withdrawable(currency_id, who, value, WithdrawReason::Reserve.into())
+                .is_ok()
+        }
+    }
+
+    fn reserved_balance(currency_id: T::AssetId, who: &T::AccountId) -> T::Balance {
+        if currency_id == T::NativeAssetId::get() {
+            T::Currency::reserved_balance(who)
+        } else {
+            Self::reserved(currency_id, who)
+        }
+    }
+
+    fn reserve(
+        currency_id: T::AssetId,
+        who: &T::AccountId,
+        value: T::Balance,
+    ) -> dispatch::DispatchResult {
+        if currency_id == T::NativeAssetId::get() {
+            T::Currency::reserve(who, value)
+        } else {
+            Self::do_reserve(currency_id, who, value)
+        }
+    }
+
+    fn unreserve(currency_id: T::AssetId, who: &T::AccountId, value: T::Balance) -> T::Balance {
+        if currency_id == T::NativeAssetId::get() {
+            T::Currency::unreserve(who, value)
+        } else {
+            Self::do_unreserve(currency_id, who, value)
+        }
+    }
+
+    fn repatriate_reserved(
+        currency_id: T::AssetId,
+        slashed: &T::AccountId,
+        beneficiary: &T::AccountId,
+        value: T::Balance,
+        status: BalanceStatus,
+    ) -> Result<T::Balance, dispatch::DispatchError> {
+        if currency_id == T::NativeAssetId::get() {
+            T::Currency::repatriate_reserved(slashed, beneficiary, value, status)
+        } else {
+            Self::do_repatriate_reserved(currency_id, slashed, beneficiary, value, status)
+        }
+    }
+}
+
+impl<T: Trait> MultiLockableCurrency<T::AccountId> for Module<T> {
+    fn set_lock(
+        lock_id: LockIdentifier,
+        currency_id: T::AssetId,
+        who: &T::AccountId,
+        amount: T::Balance,
+        reasons: WithdrawReasons,
+    ) {
+        if currency_id == T::NativeAssetId::get() {
+            T::Currency::set_lock(lock_id, who, amount, reasons)
+        } else {
+            Self::do_set_lock(lock_id, currency_id, who, amount, reasons)
+        }
+    }
+
+    fn extend_lock(
+        lock_id: LockIdentifier,
+        currency_id: T::AssetId,
+        who: &T::AccountId,
+        amount: T::Balance,
+        reasons: WithdrawReasons,
+    ) {
+        if currency_id == T::NativeAssetId::get() {
+            T::Currency::extend_lock(lock_id, who, amount, reasons)
+        } else {
+            Self::do_extend_lock(lock_id, currency_id, who, amount, reasons)
+        }
+    }
+
+    fn remove_lock(lock_id: LockIdentifier, currency_id: T::AssetId, who: &T::AccountId) {
+        if currency_id == T::NativeAssetId::get() {
+            T::Currency::remove_lock(lock_id, who)
+        } else {
+            Self::do_remove_lock(lock_id, currency_id, who)
+        }
+    }
 }
@@ -1,12 +1,11 @@
 use crate::{Module, Trait};
 use frame_support::{impl_outer_origin, parameter_types, weights::Weight};
 use frame_system as system;
-use pallet_assets;
 use sp_core::H256;
 use sp_runtime::{
 	testing::Header,
-	traits::{BlakeTwo256, IdentityLookup},
-	Perbill,
+	traits::{BlakeTwo256, ConvertInto, IdentityLookup},
+	ModuleId, Perbill,
 };
 
 impl_outer_origin! {
@@ -22,6 +21,16 @@ parameter_types! {
 	pub const MaximumBlockWeight: Weight = 1024;
 	pub const MaximumBlockLength: u32 = 2 * 1024;
 	pub const AvailableBlockRatio: Perbill = Perbill::from_percent(75);
+	pub const ExistentialDeposit: u128 = 1;
+	pub const MetadataDepositBase: u128 = 10;
+	pub const MetadataDepositPerByte: u128 = 1;
+	pub const StringLimit: u32 = 16;
+	pub const NativeAssetId: u32 = u32::max_value();
+	pub const AssetModuleId: ModuleId = ModuleId(*b"py/asset");
+	pub const MaxBatchSize: u32 = 4;
+	pub const MaxAttributes: u32 = 2;
+	pub const MaxVestingSchedules: u32 = 2;
+	pub const MinVestedTransferFactor: u32 = 10;
 }
 
 impl system::Trait for Test {
@@ -46,28 +55,71 @@ impl system::Trait for Test {
 	type AvailableBlockRatio = AvailableBlockRatio;
 	type Version = ();
 	type ModuleToIndex = ();
-	type AccountData = ();
+	type AccountData = pallet_balances::AccountData<u128>;
 	type OnNewAccount = ();
 	type OnKilledAccount = ();
 	type SystemWeightInfo = ();
 }
 
-impl pallet_assets::Trait for Test {
-	type Event = ();
+impl pallet_balances::Trait for Test {
 	type Balance = u128;
-	type AssetId = u128;
+	type Event = ();
+	type DustRemoval = ();
+	type ExistentialDeposit = ExistentialDeposit;
+	type AccountStore = System;
+	type WeightInfo = ();
 }
 
 impl Trait for Test {
 	type Event = ();
+	type Balance = u128;
+	type AssetId = u32;
+	type Currency = Balances;
+	type NativeAssetId = NativeAssetId;
+	type ModuleId = AssetModuleId;
+	type MetadataDepositBase = MetadataDepositBase;
+	type MetadataDepositPerByte = MetadataDepositPerByte;
+	type StringLimit = StringLimit;
+	type OnDust = ();
+	type MaxBatchSize = MaxBatchSize;
+	type ItemId = u32;
+	type MaxAttributes = MaxAttributes;
+	type BlockNumberToBalance = ConvertInto;
+	type MaxVestingSchedules = MaxVestingSchedules;
+	type MinVestedTransferFactor = MinVestedTransferFactor;
+	type WeightInfo = ();
 }
 
-pub type TemplateModule = Module<Test>;
+pub type System = system::Module<Test>;
+pub type Balances = pallet_balances::Module<Test>;
+pub type Assets = Module<Test>;
 
 // Build genesis storage according to the mock runtime.
 pub fn new_test_ext() -> sp_io::TestExternalities {
-	system::GenesisConfig::default()
+	genesis_storage().into()
+}
+
+// Build genesis storage with two assets already issued to the test accounts.
+pub fn new_test_ext_with_assets() -> sp_io::TestExternalities {
+	let mut t = genesis_storage();
+	crate::GenesisConfig::<Test> {
+		assets: vec![(0, 1, 5), (1, 2, 0)],
+		metadata: vec![(0, b"Vault USD".to_vec(), b"vUSD".to_vec(), 12)],
+		accounts: vec![(0, 1, 100), (0, 2, 50), (1, 3, 1000)],
+	}
+	.assimilate_storage(&mut t)
+	.unwrap();
+	t.into()
+}
+
+fn genesis_storage() -> sp_runtime::Storage {
+	let mut t = system::GenesisConfig::default()
 		.build_storage::<Test>()
-		.unwrap()
-		.into()
+		.unwrap();
+	pallet_balances::GenesisConfig::<Test> {
+		balances: vec![(1, 100), (2, 100), (3, 100)],
+	}
+	.assimilate_storage(&mut t)
+	.unwrap();
+	t
 }
@@ -1,23 +1,795 @@
-use crate::{Error, mock::*};
-use frame_support::{assert_ok, assert_noop};
+use crate::{
+	mock::*, AssetMetadata, Error, MultiCurrency, MultiLockableCurrency, MultiReservableCurrency,
+	VestingSchedule,
+};
+use frame_support::{
+	assert_noop, assert_ok,
+	traits::{BalanceStatus, WithdrawReason, WithdrawReasons},
+	IterableStorageMap, StorageMap,
+};
+
+/// Assert that the total supply of every issued asset equals the sum of its free and
+/// reserved balances.
+fn assert_supply_invariant() {
+	for id in 0..Assets::next_asset_id() {
+		let free: u128 = crate::Balances::<Test>::iter()
+			.filter(|((asset, _), _)| *asset == id)
+			.map(|(_, balance)| balance)
+			.sum();
+		let reserved: u128 = crate::Reserved::<Test>::iter()
+			.filter(|((asset, _), _)| *asset == id)
+			.map(|(_, balance)| balance)
+			.sum();
+		assert_eq!(crate::TotalSupply::<Test>::get(id), free + reserved);
+	}
+}
+
+/// A small xorshift generator so the randomized tests are reproducible without extra deps.
+struct Rng(u64);
+
+impl Rng {
+	fn next(&mut self) -> u64 {
+		self.0 ^= self.0 << 13;
+		self.0 ^= self.0 >> 7;
+		self.0 ^= self.0 << 17;
+		self.0
+	}
+
+	fn below(&mut self, n: u64) -> u64 {
+		self.next() % n
+	}
+}
+
+#[test]
+fn issuing_asset_units_to_issuer_should_work() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Assets::issue(Origin::signed(1), 100));
+		assert_eq!(Assets::balance(0, 1), 100);
+		assert_eq!(Assets::total_supply(0), 100);
+	});
+}
 
 #[test]
-fn it_works_for_default_value() {
+fn set_metadata_should_reserve_deposit() {
 	new_test_ext().execute_with(|| {
-		// Dispatch a signed extrinsic.
-		assert_ok!(TemplateModule::do_something(Origin::signed(1), 42));
-		// Read pallet storage and assert an expected result.
-		assert_eq!(TemplateModule::something(), Some(42));
+		assert_ok!(Assets::issue(Origin::signed(1), 100));
+		assert_ok!(Assets::set_metadata(Origin::signed(1), 0, b"Vault USD".to_vec(), b"vUSD".to_vec(), 12));
+		assert_eq!(
+			Assets::metadata(0),
+			Some(AssetMetadata {
+				deposit: 23,
+				name: b"Vault USD".to_vec(),
+				symbol: b"vUSD".to_vec(),
+				decimals: 12,
+			})
+		);
+		assert_eq!(Balances::reserved_balance(1), 23);
+
+		// Shrinking the metadata returns the difference.
+		assert_ok!(Assets::set_metadata(Origin::signed(1), 0, b"vUSD".to_vec(), b"vUSD".to_vec(), 12));
+		assert_eq!(Balances::reserved_balance(1), 18);
 	});
 }
 
 #[test]
-fn correct_error_for_none_value() {
+fn set_metadata_should_fail_for_non_creator_or_unknown_asset() {
 	new_test_ext().execute_with(|| {
-		// Ensure the expected error is thrown when no value is present.
 		assert_noop!(
-			TemplateModule::cause_error(Origin::signed(1)),
-			Error::<Test>::NoneValue
+			Assets::set_metadata(Origin::signed(1), 0, b"Vault USD".to_vec(), b"vUSD".to_vec(), 12),
+			Error::<Test>::Unknown
+		);
+		assert_ok!(Assets::issue(Origin::signed(1), 100));
+		assert_noop!(
+			Assets::set_metadata(Origin::signed(2), 0, b"Vault USD".to_vec(), b"vUSD".to_vec(), 12),
+			Error::<Test>::NotTheCreator
+		);
+		assert_noop!(
+			Assets::set_metadata(Origin::signed(1), 0, vec![0u8; 17], b"vUSD".to_vec(), 12),
+			Error::<Test>::BadMetadata
 		);
 	});
 }
+
+#[test]
+fn clear_metadata_should_unreserve_deposit() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Assets::issue(Origin::signed(1), 100));
+		assert_noop!(Assets::clear_metadata(Origin::signed(1), 0), Error::<Test>::Unknown);
+		assert_ok!(Assets::set_metadata(Origin::signed(1), 0, b"Vault USD".to_vec(), b"vUSD".to_vec(), 12));
+		assert_noop!(Assets::clear_metadata(Origin::signed(2), 0), Error::<Test>::NotTheCreator);
+		assert_ok!(Assets::clear_metadata(Origin::signed(1), 0));
+		assert_eq!(Assets::metadata(0), None);
+		assert_eq!(Balances::reserved_balance(1), 0);
+	});
+}
+
+#[test]
+fn approval_lifecycle_should_work() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Assets::issue(Origin::signed(1), 100));
+		assert_ok!(Assets::approve_transfer(Origin::signed(1), 0, 2, 50));
+		assert_eq!(Assets::allowance(0, 1, 2), 50);
+		assert_ok!(Assets::cancel_approval(Origin::signed(1), 0, 2));
+		assert_eq!(Assets::allowance(0, 1, 2), 0);
+		assert_noop!(Assets::cancel_approval(Origin::signed(1), 0, 2), Error::<Test>::NotApproved);
+	});
+}
+
+#[test]
+fn transfer_approved_should_decrement_allowance() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Assets::issue(Origin::signed(1), 100));
+		assert_ok!(Assets::approve_transfer(Origin::signed(1), 0, 2, 50));
+		assert_ok!(Assets::transfer_approved(Origin::signed(2), 0, 1, 3, 30));
+		assert_eq!(Assets::allowance(0, 1, 2), 20);
+		assert_eq!(Assets::balance(0, 1), 70);
+		assert_eq!(Assets::balance(0, 3), 30);
+
+		assert_noop!(
+			Assets::transfer_approved(Origin::signed(2), 0, 1, 3, 21),
+			Error::<Test>::NotApproved
+		);
+		assert_ok!(Assets::transfer_approved(Origin::signed(2), 0, 1, 2, 20));
+		assert_eq!(Assets::allowance(0, 1, 2), 0);
+		assert_eq!(Assets::balance(0, 2), 20);
+	});
+}
+
+#[test]
+fn transfer_approved_should_fail_without_approval_or_balance() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Assets::issue(Origin::signed(1), 100));
+		assert_noop!(
+			Assets::transfer_approved(Origin::signed(2), 0, 1, 3, 10),
+			Error::<Test>::NotApproved
+		);
+		assert_ok!(Assets::approve_transfer(Origin::signed(1), 0, 2, 500));
+		assert_noop!(
+			Assets::transfer_approved(Origin::signed(2), 0, 1, 3, 101),
+			Error::<Test>::BalanceLow
+		);
+	});
+}
+
+#[test]
+fn mint_and_burn_should_update_total_supply() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Assets::issue(Origin::signed(1), 100));
+		assert_ok!(Assets::mint(Origin::signed(1), 0, 2, 50));
+		assert_eq!(Assets::total_supply(0), 150);
+		assert_ok!(Assets::burn(Origin::signed(2), 0, 2, 20));
+		assert_eq!(Assets::total_supply(0), 130);
+		assert_ok!(Assets::mint_from_system(&0, &3, &10));
+		assert_ok!(Assets::burn_from_system(&0, &3, &5));
+		assert_eq!(Assets::total_supply(0), 135);
+		assert_supply_invariant();
+	});
+}
+
+#[test]
+fn burn_from_system_should_not_underflow() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Assets::issue(Origin::signed(1), 100));
+		assert_noop!(Assets::burn_from_system(&0, &2, &1), Error::<Test>::BalanceLow);
+		assert_noop!(Assets::burn(Origin::signed(1), 0, 1, 101), Error::<Test>::BalanceLow);
+	});
+}
+
+#[test]
+fn mint_should_not_overflow() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Assets::issue(Origin::signed(1), u128::max_value()));
+		assert_noop!(Assets::mint(Origin::signed(1), 0, 2, 1), Error::<Test>::Overflow);
+		assert_noop!(Assets::mint_from_system(&0, &1, &1), Error::<Test>::Overflow);
+	});
+}
+
+#[test]
+fn random_operations_should_preserve_total_supply() {
+	for seed in 1..=20u64 {
+		new_test_ext().execute_with(|| {
+			let mut rng = Rng(seed.wrapping_mul(0x9E37_79B9_7F4A_7C15));
+			assert_ok!(Assets::issue(Origin::signed(1), 1_000));
+			assert_ok!(Assets::issue(Origin::signed(2), 1_000));
+
+			for _ in 0..200 {
+				let id = rng.below(2) as u32;
+				let who = 1 + rng.below(4);
+				let other = 1 + rng.below(4);
+				let amount = rng.below(300) as u128;
+				// Individual operations may fail; the invariant must hold either way.
+				let _ = match rng.below(9) {
+					0 => Assets::transfer(Origin::signed(who), id, other, amount),
+					1 => Assets::mint(Origin::signed(who), id, other, amount),
+					2 => Assets::burn(Origin::signed(who), id, who, amount),
+					3 => Assets::destroy(Origin::signed(who), id),
+					4 => Assets::mint_from_system(&id, &who, &amount),
+					5 => <Assets as MultiReservableCurrency<u64>>::reserve(id, &who, amount),
+					6 => {
+						<Assets as MultiReservableCurrency<u64>>::unreserve(id, &who, amount);
+						Ok(())
+					}
+					7 => <Assets as MultiReservableCurrency<u64>>::repatriate_reserved(
+						id, &who, &other, amount, BalanceStatus::Free,
+					).map(|_| ()),
+					_ => Assets::burn_from_system(&id, &who, &amount),
+				};
+				assert_supply_invariant();
+			}
+		});
+	}
+}
+
+#[test]
+fn debit_below_min_balance_should_reap_account() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Assets::issue(Origin::signed(1), 100));
+		assert_ok!(Assets::set_min_balance(Origin::signed(1), 0, 10));
+		assert_ok!(Assets::transfer(Origin::signed(1), 0, 2, 20));
+		// Leaves 5 behind for account 2, which is dust.
+		assert_ok!(Assets::transfer(Origin::signed(2), 0, 3, 15));
+		assert_eq!(Assets::balance(0, 2), 0);
+		assert!(!crate::Balances::<Test>::contains_key((0, 2)));
+		assert_eq!(Assets::total_supply(0), 95);
+		assert_supply_invariant();
+	});
+}
+
+#[test]
+fn debit_below_min_balance_should_not_reap_locked_account() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		assert_ok!(Assets::issue(Origin::signed(1), 1000));
+		let schedule = VestingSchedule { locked: 100, per_block: 10, starting_block: 5 };
+		assert_ok!(Assets::vested_transfer(Origin::signed(1), 0, 2, schedule));
+		System::set_block_number(8);
+		assert_ok!(Assets::vest(Origin::signed(2), 0));
+		assert_ok!(Assets::set_min_balance(Origin::signed(1), 0, 80));
+
+		// Leaves 70 behind, all of it still locked, so nothing is swept as dust.
+		assert_ok!(Assets::transfer(Origin::signed(2), 0, 1, 30));
+		assert_eq!(Assets::balance(0, 2), 70);
+		assert!(!Assets::locks((0, 2)).is_empty());
+		assert_eq!(Assets::total_supply(0), 1000);
+
+		// Once fully vested, the account is reaped as usual.
+		System::set_block_number(15);
+		assert_ok!(Assets::vest(Origin::signed(2), 0));
+		assert_ok!(Assets::transfer(Origin::signed(2), 0, 1, 1));
+		assert_eq!(Assets::balance(0, 2), 0);
+		assert_eq!(Assets::total_supply(0), 1000 - 69);
+		assert_supply_invariant();
+	});
+}
+
+#[test]
+fn credit_below_min_balance_should_fail_for_new_accounts() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Assets::issue(Origin::signed(1), 100));
+		assert_ok!(Assets::set_min_balance(Origin::signed(1), 0, 10));
+		assert_noop!(Assets::transfer(Origin::signed(1), 0, 2, 9), Error::<Test>::BelowMinimum);
+		assert_ok!(Assets::transfer(Origin::signed(1), 0, 2, 10));
+		// Existing accounts may receive any amount.
+		assert_ok!(Assets::transfer(Origin::signed(1), 0, 2, 1));
+		assert_eq!(Assets::balance(0, 2), 11);
+	});
+}
+
+#[test]
+fn transfer_keep_alive_should_not_reap_sender() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Assets::issue(Origin::signed(1), 100));
+		assert_ok!(Assets::set_min_balance(Origin::signed(1), 0, 10));
+		assert_noop!(Assets::transfer_keep_alive(Origin::signed(1), 0, 2, 95), Error::<Test>::WouldDie);
+		assert_noop!(Assets::transfer_keep_alive(Origin::signed(1), 0, 2, 100), Error::<Test>::WouldDie);
+		assert_ok!(Assets::transfer_keep_alive(Origin::signed(1), 0, 2, 90));
+		assert_eq!(Assets::balance(0, 1), 10);
+	});
+}
+
+#[test]
+fn set_min_balance_should_require_creator() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(Assets::set_min_balance(Origin::signed(1), 0, 10), Error::<Test>::Unknown);
+		assert_ok!(Assets::issue(Origin::signed(1), 100));
+		assert_noop!(Assets::set_min_balance(Origin::signed(2), 0, 10), Error::<Test>::NotTheCreator);
+	});
+}
+
+#[test]
+fn set_team_should_move_minting_rights() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Assets::issue(Origin::signed(1), 100));
+		assert_noop!(Assets::set_team(Origin::signed(2), 0, 2, 2, 2), Error::<Test>::NotTheCreator);
+		assert_ok!(Assets::set_team(Origin::signed(1), 0, 2, 3, 3));
+		assert_noop!(Assets::mint(Origin::signed(1), 0, 1, 10), Error::<Test>::NoPermission);
+		assert_ok!(Assets::mint(Origin::signed(2), 0, 1, 10));
+		assert_eq!(Assets::balance(0, 1), 110);
+	});
+}
+
+#[test]
+fn frozen_accounts_cannot_transfer() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Assets::issue(Origin::signed(1), 100));
+		assert_ok!(Assets::transfer(Origin::signed(1), 0, 2, 50));
+		assert_noop!(Assets::freeze(Origin::signed(2), 0, 2), Error::<Test>::NoPermission);
+		assert_ok!(Assets::freeze(Origin::signed(1), 0, 2));
+		assert_noop!(Assets::transfer(Origin::signed(2), 0, 1, 10), Error::<Test>::Frozen);
+		assert_noop!(Assets::burn(Origin::signed(2), 0, 2, 10), Error::<Test>::Frozen);
+		// Frozen accounts can still receive funds.
+		assert_ok!(Assets::transfer(Origin::signed(1), 0, 2, 10));
+		assert_ok!(Assets::thaw(Origin::signed(1), 0, 2));
+		assert_ok!(Assets::transfer(Origin::signed(2), 0, 1, 10));
+	});
+}
+
+#[test]
+fn frozen_assets_cannot_be_transferred() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Assets::issue(Origin::signed(1), 100));
+		assert_ok!(Assets::freeze_asset(Origin::signed(1), 0));
+		assert_noop!(Assets::transfer(Origin::signed(1), 0, 2, 10), Error::<Test>::Frozen);
+		assert_ok!(Assets::thaw_asset(Origin::signed(1), 0));
+		assert_ok!(Assets::transfer(Origin::signed(1), 0, 2, 10));
+	});
+}
+
+#[test]
+fn force_transfer_should_ignore_freezes() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Assets::issue(Origin::signed(1), 100));
+		assert_ok!(Assets::transfer(Origin::signed(1), 0, 2, 50));
+		assert_ok!(Assets::freeze(Origin::signed(1), 0, 2));
+		assert_noop!(Assets::force_transfer(Origin::signed(2), 0, 2, 3, 10), Error::<Test>::NoPermission);
+		assert_ok!(Assets::force_transfer(Origin::signed(1), 0, 2, 3, 10));
+		assert_eq!(Assets::balance(0, 2), 40);
+		assert_eq!(Assets::balance(0, 3), 10);
+	});
+}
+
+#[test]
+fn transfer_ownership_should_move_metadata_deposit() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Assets::issue(Origin::signed(1), 100));
+		assert_ok!(Assets::set_metadata(Origin::signed(1), 0, b"Vault USD".to_vec(), b"vUSD".to_vec(), 12));
+		assert_noop!(Assets::transfer_ownership(Origin::signed(2), 0, 2), Error::<Test>::NotTheCreator);
+		assert_ok!(Assets::transfer_ownership(Origin::signed(1), 0, 2));
+		assert_eq!(Balances::reserved_balance(1), 0);
+		assert_eq!(Balances::reserved_balance(2), 23);
+		assert_ok!(Assets::clear_metadata(Origin::signed(2), 0));
+		assert_eq!(Balances::reserved_balance(2), 0);
+	});
+}
+
+#[test]
+fn multi_currency_should_route_native_asset_to_balances() {
+	new_test_ext().execute_with(|| {
+		let native = NativeAssetId::get();
+		assert_eq!(<Assets as MultiCurrency<u64>>::free_balance(native, &1), 100);
+		assert_ok!(<Assets as MultiCurrency<u64>>::transfer(native, &1, &2, 30));
+		assert_eq!(Balances::free_balance(1), 70);
+		assert_eq!(Balances::free_balance(2), 130);
+		// Withdrawn native currency moves to the module account, so its issuance stays put.
+		assert_ok!(<Assets as MultiCurrency<u64>>::withdraw(native, &2, 30));
+		assert_ok!(<Assets as MultiCurrency<u64>>::deposit(native, &3, 5));
+		assert_eq!(Balances::free_balance(Assets::account_id()), 25);
+		assert_eq!(<Assets as MultiCurrency<u64>>::total_issuance(native), 300);
+		// Nothing is deposited beyond what was withdrawn.
+		assert_noop!(
+			<Assets as MultiCurrency<u64>>::deposit(native, &3, 26),
+			pallet_balances::Error::<Test, pallet_balances::DefaultInstance>::InsufficientBalance
+		);
+		// The module account keeps the existential deposit rather than being reaped.
+		assert_noop!(
+			<Assets as MultiCurrency<u64>>::deposit(native, &3, 25),
+			pallet_balances::Error::<Test, pallet_balances::DefaultInstance>::KeepAlive
+		);
+		assert_ok!(<Assets as MultiCurrency<u64>>::deposit(native, &3, 24));
+		assert_eq!(Balances::free_balance(Assets::account_id()), 1);
+		assert_ok!(<Assets as MultiReservableCurrency<u64>>::reserve(native, &1, 20));
+		assert_eq!(<Assets as MultiReservableCurrency<u64>>::reserved_balance(native, &1), 20);
+	});
+}
+
+#[test]
+fn multi_currency_should_handle_issued_assets() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Assets::issue(Origin::signed(1), 100));
+		assert_ok!(<Assets as MultiCurrency<u64>>::transfer(0, &1, &2, 30));
+		assert_ok!(<Assets as MultiCurrency<u64>>::deposit(0, &2, 10));
+		assert_ok!(<Assets as MultiCurrency<u64>>::withdraw(0, &1, 20));
+		assert_eq!(<Assets as MultiCurrency<u64>>::free_balance(0, &1), 50);
+		assert_eq!(<Assets as MultiCurrency<u64>>::free_balance(0, &2), 40);
+		assert_eq!(<Assets as MultiCurrency<u64>>::total_issuance(0), 90);
+		assert_noop!(<Assets as MultiCurrency<u64>>::withdraw(0, &3, 1), Error::<Test>::BalanceLow);
+		assert_ok!(Assets::freeze(Origin::signed(1), 0, 1));
+		assert_noop!(<Assets as MultiCurrency<u64>>::ensure_can_withdraw(0, &1, 1), Error::<Test>::Frozen);
+	});
+}
+
+#[test]
+fn reserve_and_unreserve_should_keep_total_supply() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Assets::issue(Origin::signed(1), 100));
+		assert_ok!(<Assets as MultiReservableCurrency<u64>>::reserve(0, &1, 40));
+		assert_eq!(Assets::balance(0, 1), 60);
+		assert_eq!(Assets::reserved(0, 1), 40);
+		assert_eq!(Assets::total_supply(0), 100);
+		assert_noop!(Assets::transfer(Origin::signed(1), 0, 2, 61), Error::<Test>::BalanceLow);
+
+		assert_eq!(<Assets as MultiReservableCurrency<u64>>::unreserve(0, &1, 50), 10);
+		assert_eq!(Assets::balance(0, 1), 100);
+		assert_eq!(Assets::reserved(0, 1), 0);
+		assert_supply_invariant();
+	});
+}
+
+#[test]
+fn repatriate_reserved_should_move_to_beneficiary() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Assets::issue(Origin::signed(1), 100));
+		assert_ok!(<Assets as MultiReservableCurrency<u64>>::reserve(0, &1, 40));
+		assert_eq!(
+			<Assets as MultiReservableCurrency<u64>>::repatriate_reserved(0, &1, &2, 30, BalanceStatus::Reserved),
+			Ok(0)
+		);
+		assert_eq!(
+			<Assets as MultiReservableCurrency<u64>>::repatriate_reserved(0, &1, &3, 30, BalanceStatus::Free),
+			Ok(20)
+		);
+		assert_eq!(Assets::reserved(0, 1), 0);
+		assert_eq!(Assets::reserved(0, 2), 30);
+		assert_eq!(Assets::balance(0, 3), 10);
+		assert_supply_invariant();
+	});
+}
+
+#[test]
+fn locks_should_restrict_transfers_and_destroy() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Assets::issue(Origin::signed(1), 100));
+		<Assets as MultiLockableCurrency<u64>>::set_lock(*b"testlock", 0, &1, 70, WithdrawReasons::all());
+		assert_noop!(Assets::transfer(Origin::signed(1), 0, 2, 31), Error::<Test>::LiquidityRestrictions);
+		assert_noop!(Assets::destroy(Origin::signed(1), 0), Error::<Test>::LiquidityRestrictions);
+		assert_ok!(Assets::transfer(Origin::signed(1), 0, 2, 30));
+
+		// Locks for other reasons do not restrict transfers.
+		<Assets as MultiLockableCurrency<u64>>::set_lock(
+			*b"testlock", 0, &1, 70, WithdrawReason::TransactionPayment.into(),
+		);
+		assert_ok!(Assets::transfer(Origin::signed(1), 0, 2, 30));
+
+		<Assets as MultiLockableCurrency<u64>>::extend_lock(*b"testlock", 0, &1, 30, WithdrawReasons::all());
+		assert_noop!(Assets::transfer(Origin::signed(1), 0, 2, 1), Error::<Test>::LiquidityRestrictions);
+		<Assets as MultiLockableCurrency<u64>>::remove_lock(*b"testlock", 0, &1);
+		assert_ok!(Assets::destroy(Origin::signed(1), 0));
+	});
+}
+
+#[test]
+fn transfer_batch_should_work() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Assets::issue(Origin::signed(1), 100));
+		assert_ok!(Assets::transfer_batch(Origin::signed(1), 0, vec![(2, 10), (3, 20), (1, 5)]));
+		assert_eq!(Assets::balance(0, 1), 70);
+		assert_eq!(Assets::balance(0, 2), 10);
+		assert_eq!(Assets::balance(0, 3), 20);
+		assert_supply_invariant();
+	});
+}
+
+#[test]
+fn transfer_batch_should_be_all_or_nothing() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Assets::issue(Origin::signed(1), 100));
+		assert_noop!(
+			Assets::transfer_batch(Origin::signed(1), 0, vec![(2, 60), (3, 41)]),
+			Error::<Test>::BalanceLow
+		);
+		assert_ok!(Assets::set_min_balance(Origin::signed(1), 0, 10));
+		assert_noop!(
+			Assets::transfer_batch(Origin::signed(1), 0, vec![(2, 60), (3, 5)]),
+			Error::<Test>::BelowMinimum
+		);
+		assert_noop!(
+			Assets::transfer_batch(Origin::signed(1), 0, vec![(2, 1), (2, 1), (2, 1), (2, 1), (2, 1)]),
+			Error::<Test>::TooManyTargets
+		);
+	});
+}
+
+#[test]
+fn airdrop_should_mint_to_every_recipient() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Assets::issue(Origin::signed(1), 100));
+		assert_noop!(Assets::airdrop(Origin::signed(2), 0, vec![(2, 10)]), Error::<Test>::NoPermission);
+		assert_ok!(Assets::airdrop(Origin::signed(1), 0, vec![(2, 10), (3, 20)]));
+		assert_eq!(Assets::balance(0, 2), 10);
+		assert_eq!(Assets::balance(0, 3), 20);
+		assert_eq!(Assets::total_supply(0), 130);
+		assert_noop!(
+			Assets::airdrop(Origin::signed(1), 0, vec![(2, 10), (3, u128::max_value())]),
+			Error::<Test>::Overflow
+		);
+		assert_supply_invariant();
+	});
+}
+
+#[test]
+fn genesis_config_should_issue_assets() {
+	new_test_ext_with_assets().execute_with(|| {
+		assert_eq!(Assets::balance(0, 1), 100);
+		assert_eq!(Assets::balance(0, 2), 50);
+		assert_eq!(Assets::balance(1, 3), 1000);
+		assert_eq!(Assets::total_supply(0), 150);
+		assert_eq!(Assets::total_supply(1), 1000);
+		assert_eq!(Assets::min_balance(0), 5);
+		assert_eq!(Assets::roles(1).map(|r| r.issuer), Some(2));
+		assert_eq!(
+			Assets::metadata(0).map(|m| (m.symbol, m.decimals, m.deposit)),
+			Some((b"vUSD".to_vec(), 12, 0))
+		);
+		assert_supply_invariant();
+
+		// New assets are issued after the genesis ones, and genesis assets behave like issued ones.
+		assert_ok!(Assets::issue(Origin::signed(1), 10));
+		assert_eq!(Assets::balance(2, 1), 10);
+		assert_ok!(Assets::mint(Origin::signed(2), 1, 1, 5));
+		assert_noop!(Assets::transfer(Origin::signed(2), 0, 3, 2), Error::<Test>::BelowMinimum);
+	});
+}
+
+#[test]
+fn destroying_an_asset_should_remove_all_of_its_state() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Assets::issue(Origin::signed(1), 100));
+		assert_ok!(Assets::set_metadata(Origin::signed(1), 0, b"Vault USD".to_vec(), b"vUSD".to_vec(), 12));
+		assert_eq!(Balances::reserved_balance(1), 23);
+		assert_ok!(Assets::transfer(Origin::signed(1), 0, 2, 30));
+		assert_ok!(Assets::transfer(Origin::signed(1), 0, 3, 20));
+		assert_ok!(<Assets as MultiReservableCurrency<u64>>::reserve(0, &2, 10));
+		<Assets as MultiLockableCurrency<u64>>::set_lock(*b"testlock", 0, &3, 5, WithdrawReasons::all());
+		assert_ok!(Assets::freeze(Origin::signed(1), 0, 3));
+		assert_ok!(Assets::approve_transfer(Origin::signed(1), 0, 2, 10));
+
+		assert_noop!(Assets::destroy_accounts(Origin::signed(1), 0, 10), Error::<Test>::NotDestroying);
+		assert_noop!(Assets::start_destroy(Origin::signed(2), 0), Error::<Test>::NotTheCreator);
+		assert_ok!(Assets::start_destroy(Origin::signed(1), 0));
+		assert_noop!(Assets::start_destroy(Origin::signed(1), 0), Error::<Test>::Destroying);
+
+		// Nothing moves in or out of an asset being destroyed.
+		assert_noop!(Assets::transfer(Origin::signed(1), 0, 2, 10), Error::<Test>::Destroying);
+		assert_noop!(Assets::mint(Origin::signed(1), 0, 2, 10), Error::<Test>::Destroying);
+		assert_noop!(Assets::approve_transfer(Origin::signed(1), 0, 3, 10), Error::<Test>::Destroying);
+
+		assert_ok!(Assets::destroy_accounts(Origin::signed(1), 0, 2));
+		assert_noop!(Assets::finish_destroy(Origin::signed(1), 0), Error::<Test>::InUse);
+		assert_ok!(Assets::destroy_accounts(Origin::signed(1), 0, 1));
+		assert_noop!(Assets::finish_destroy(Origin::signed(1), 0), Error::<Test>::InUse);
+		assert_eq!(Assets::total_supply(0), 0);
+		assert_supply_invariant();
+		assert_ok!(Assets::destroy_accounts(Origin::signed(1), 0, 1));
+
+		assert_noop!(Assets::finish_destroy(Origin::signed(2), 0), Error::<Test>::NotTheCreator);
+		assert_ok!(Assets::finish_destroy(Origin::signed(1), 0));
+		assert_eq!(Balances::reserved_balance(1), 0);
+		for who in 1..=3 {
+			assert_eq!(Assets::balance(0, who), 0);
+			assert_eq!(Assets::reserved(0, who), 0);
+			assert!(Assets::locks(0, who).is_empty());
+			assert!(!Assets::is_frozen(0, &who));
+		}
+		assert_eq!(Assets::allowance(0, 1, 2), 0);
+		assert!(Assets::metadata(0).is_none());
+		assert!(Assets::roles(0).is_none());
+		assert!(!Assets::is_destroying(0));
+		assert_noop!(Assets::start_destroy(Origin::signed(1), 0), Error::<Test>::Unknown);
+		assert_eq!(crate::Balances::<Test>::iter().count(), 0);
+		assert_eq!(crate::TotalSupply::<Test>::iter().count(), 0);
+
+		// Identifiers of destroyed assets are not handed out again.
+		assert_ok!(Assets::issue(Origin::signed(1), 100));
+		assert_eq!(Assets::balance(1, 1), 100);
+	});
+}
+
+#[test]
+fn non_fungible_items_should_work() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Assets::issue(Origin::signed(1), 100));
+		assert_ok!(Assets::create_class(Origin::signed(1)));
+		assert!(Assets::is_non_fungible(1));
+		assert_noop!(Assets::mint_item(Origin::signed(1), 0, 7, 2), Error::<Test>::Fungible);
+		assert_noop!(Assets::mint_item(Origin::signed(2), 1, 7, 2), Error::<Test>::NoPermission);
+
+		assert_ok!(Assets::mint_item(Origin::signed(1), 1, 7, 2));
+		assert_ok!(Assets::mint_item(Origin::signed(1), 1, 8, 2));
+		assert_noop!(Assets::mint_item(Origin::signed(1), 1, 7, 3), Error::<Test>::ItemExists);
+		assert_eq!(Assets::owner_of(1, 7), Some(2));
+		assert_eq!(Assets::item_count(1), 2);
+		let mut items = Assets::items_of_owner(&2);
+		items.sort();
+		assert_eq!(items, vec![(1, 7), (1, 8)]);
+
+		assert_noop!(Assets::transfer_item(Origin::signed(3), 1, 7, 3), Error::<Test>::NoPermission);
+		assert_ok!(Assets::transfer_item(Origin::signed(2), 1, 7, 3));
+		assert_eq!(Assets::owner_of(1, 7), Some(3));
+		assert_eq!(Assets::items_of_owner(&2), vec![(1, 8)]);
+		assert_eq!(Assets::items_of_owner(&3), vec![(1, 7)]);
+
+		// An approval lets the delegate transfer once.
+		assert_ok!(Assets::approve_item(Origin::signed(3), 1, 7, 1));
+		assert_ok!(Assets::transfer_item(Origin::signed(1), 1, 7, 2));
+		assert_eq!(Assets::owner_of(1, 7), Some(2));
+		assert_noop!(Assets::transfer_item(Origin::signed(1), 1, 7, 3), Error::<Test>::NoPermission);
+		assert_ok!(Assets::approve_item(Origin::signed(2), 1, 7, 1));
+		assert_ok!(Assets::cancel_item_approval(Origin::signed(2), 1, 7));
+		assert_noop!(Assets::cancel_item_approval(Origin::signed(2), 1, 7), Error::<Test>::NotApproved);
+		assert_noop!(Assets::transfer_item(Origin::signed(1), 1, 7, 3), Error::<Test>::NoPermission);
+
+		// Freezes apply to items too.
+		assert_ok!(Assets::freeze(Origin::signed(1), 1, 2));
+		assert_noop!(Assets::transfer_item(Origin::signed(2), 1, 7, 3), Error::<Test>::Frozen);
+		assert_ok!(Assets::thaw(Origin::signed(1), 1, 2));
+
+		// Fungible balances cannot be created in a non-fungible class.
+		assert_noop!(Assets::mint(Origin::signed(1), 1, 2, 10), Error::<Test>::NonFungible);
+		assert_noop!(Assets::airdrop(Origin::signed(1), 1, vec![(2, 10)]), Error::<Test>::NonFungible);
+
+		assert_noop!(Assets::burn_item(Origin::signed(1), 1, 7), Error::<Test>::NoPermission);
+		assert_ok!(Assets::burn_item(Origin::signed(2), 1, 7));
+		assert_eq!(Assets::owner_of(1, 7), None);
+		assert_eq!(Assets::item_count(1), 1);
+		assert_eq!(Assets::items_of_owner(&2), vec![(1, 8)]);
+		assert_noop!(Assets::burn_item(Origin::signed(2), 1, 7), Error::<Test>::UnknownItem);
+	});
+}
+
+#[test]
+fn item_attributes_should_work() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Assets::create_class(Origin::signed(1)));
+		assert_ok!(Assets::mint_item(Origin::signed(1), 0, 1, 2));
+		assert_noop!(
+			Assets::set_attribute(Origin::signed(2), 0, 1, b"color".to_vec(), b"red".to_vec()),
+			Error::<Test>::NoPermission
+		);
+		assert_noop!(
+			Assets::set_attribute(Origin::signed(1), 0, 1, vec![0u8; 17], b"red".to_vec()),
+			Error::<Test>::BadMetadata
+		);
+		assert_ok!(Assets::set_attribute(Origin::signed(1), 0, 1, b"color".to_vec(), b"red".to_vec()));
+		assert_ok!(Assets::set_attribute(Origin::signed(1), 0, 1, b"color".to_vec(), b"blue".to_vec()));
+		assert_ok!(Assets::set_attribute(Origin::signed(1), 0, 1, b"size".to_vec(), b"xl".to_vec()));
+		assert_eq!(Assets::attribute((0, 1), b"color".to_vec()), Some(b"blue".to_vec()));
+		assert_noop!(
+			Assets::set_attribute(Origin::signed(1), 0, 1, b"shape".to_vec(), b"round".to_vec()),
+			Error::<Test>::TooManyAttributes
+		);
+
+		assert_ok!(Assets::clear_attribute(Origin::signed(1), 0, 1, b"size".to_vec()));
+		assert_noop!(
+			Assets::clear_attribute(Origin::signed(1), 0, 1, b"size".to_vec()),
+			Error::<Test>::UnknownAttribute
+		);
+		assert_ok!(Assets::set_attribute(Origin::signed(1), 0, 1, b"shape".to_vec(), b"round".to_vec()));
+
+		assert_ok!(Assets::burn_item(Origin::signed(2), 0, 1));
+		assert_eq!(Assets::attribute((0, 1), b"color".to_vec()), None);
+		assert_eq!(Assets::attribute((0, 1), b"shape".to_vec()), None);
+	});
+}
+
+#[test]
+fn destroying_a_non_fungible_class_should_burn_its_items() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Assets::create_class(Origin::signed(1)));
+		assert_ok!(Assets::mint_item(Origin::signed(1), 0, 1, 2));
+		assert_ok!(Assets::mint_item(Origin::signed(1), 0, 2, 3));
+		assert_ok!(Assets::set_attribute(Origin::signed(1), 0, 1, b"color".to_vec(), b"red".to_vec()));
+
+		assert_ok!(Assets::start_destroy(Origin::signed(1), 0));
+		assert_noop!(Assets::transfer_item(Origin::signed(2), 0, 1, 3), Error::<Test>::Destroying);
+		assert_ok!(Assets::destroy_accounts(Origin::signed(1), 0, 1));
+		assert_noop!(Assets::finish_destroy(Origin::signed(1), 0), Error::<Test>::InUse);
+		assert_ok!(Assets::destroy_accounts(Origin::signed(1), 0, 1));
+		assert_ok!(Assets::finish_destroy(Origin::signed(1), 0));
+
+		assert!(Assets::items_of_owner(&2).is_empty());
+		assert!(Assets::items_of_owner(&3).is_empty());
+		assert_eq!(Assets::attribute((0, 1), b"color".to_vec()), None);
+		assert!(!Assets::is_non_fungible(0));
+		assert_eq!(Assets::item_count(0), 0);
+	});
+}
+
+#[test]
+fn account_balances_should_list_held_assets() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Assets::issue(Origin::signed(1), 100));
+		assert_ok!(Assets::issue(Origin::signed(2), 50));
+		assert_ok!(Assets::transfer(Origin::signed(2), 1, 1, 20));
+		let mut balances = Assets::account_balances(&1);
+		balances.sort();
+		assert_eq!(balances, vec![(0, 100), (1, 20)]);
+
+		// A fully reserved holding is still held, with no free balance.
+		assert_ok!(<Assets as MultiReservableCurrency<u64>>::reserve(1, &1, 20));
+		let mut balances = Assets::account_balances(&1);
+		balances.sort();
+		assert_eq!(balances, vec![(0, 100), (1, 0)]);
+		assert_eq!(<Assets as MultiReservableCurrency<u64>>::unreserve(1, &1, 20), 0);
+
+		assert_ok!(Assets::transfer(Origin::signed(1), 0, 3, 100));
+		assert_eq!(Assets::account_balances(&1), vec![(1, 20)]);
+		assert_eq!(Assets::account_balances(&3), vec![(0, 100)]);
+	});
+}
+
+#[test]
+fn vested_transfer_should_release_linearly() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		assert_ok!(Assets::issue(Origin::signed(1), 1000));
+		let schedule = VestingSchedule { locked: 100, per_block: 10, starting_block: 5 };
+		assert_noop!(
+			Assets::vested_transfer(Origin::signed(1), 0, 2, VestingSchedule { per_block: 0, ..schedule }),
+			Error::<Test>::InvalidSchedule
+		);
+		assert_noop!(
+			Assets::vested_transfer(Origin::signed(1), 0, 2, VestingSchedule { locked: 9, ..schedule }),
+			Error::<Test>::AmountLow
+		);
+		// The minimum scales with the minimum balance of the asset.
+		assert_ok!(Assets::set_min_balance(Origin::signed(1), 0, 5));
+		assert_noop!(
+			Assets::vested_transfer(Origin::signed(1), 0, 2, VestingSchedule { locked: 49, ..schedule }),
+			Error::<Test>::AmountLow
+		);
+		assert_ok!(Assets::set_min_balance(Origin::signed(1), 0, 0));
+		assert_ok!(Assets::vested_transfer(Origin::signed(1), 0, 2, schedule));
+		assert_eq!(Assets::balance(0, 2), 100);
+		assert_noop!(Assets::transfer(Origin::signed(2), 0, 3, 1), Error::<Test>::LiquidityRestrictions);
+		assert_noop!(Assets::vest(Origin::signed(3), 0), Error::<Test>::NotVesting);
+
+		// Nothing vests before the starting block.
+		System::set_block_number(5);
+		assert_ok!(Assets::vest(Origin::signed(2), 0));
+		assert_noop!(Assets::transfer(Origin::signed(2), 0, 3, 1), Error::<Test>::LiquidityRestrictions);
+
+		System::set_block_number(8);
+		assert_ok!(Assets::vest(Origin::signed(2), 0));
+		assert_ok!(Assets::transfer(Origin::signed(2), 0, 3, 30));
+		assert_noop!(Assets::transfer(Origin::signed(2), 0, 3, 1), Error::<Test>::LiquidityRestrictions);
+
+		System::set_block_number(15);
+		assert_ok!(Assets::vest(Origin::signed(2), 0));
+		assert!(Assets::vesting((0, 2)).is_empty());
+		assert!(Assets::locks((0, 2)).is_empty());
+		assert_ok!(Assets::transfer(Origin::signed(2), 0, 3, 70));
+		assert_noop!(Assets::vest(Origin::signed(2), 0), Error::<Test>::NotVesting);
+	});
+}
+
+#[test]
+fn multiple_vesting_schedules_should_add_up() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		assert_ok!(Assets::issue(Origin::signed(1), 1000));
+		let fast = VestingSchedule { locked: 100, per_block: 50, starting_block: 1 };
+		let slow = VestingSchedule { locked: 100, per_block: 10, starting_block: 1 };
+		assert_ok!(Assets::vested_transfer(Origin::signed(1), 0, 2, fast));
+		assert_ok!(Assets::vested_transfer(Origin::signed(1), 0, 2, slow));
+		assert_noop!(Assets::vested_transfer(Origin::signed(1), 0, 2, slow), Error::<Test>::TooManySchedules);
+		assert_eq!(Assets::locks((0, 2))[0].amount, 200);
+
+		System::set_block_number(3);
+		assert_ok!(Assets::vest(Origin::signed(2), 0));
+		// The fast schedule has fully vested and is dropped; 80 of the slow one remain locked.
+		assert_eq!(Assets::vesting((0, 2)), vec![slow]);
+		assert_eq!(Assets::locks((0, 2))[0].amount, 80);
+		assert_ok!(Assets::transfer(Origin::signed(2), 0, 3, 120));
+		assert_noop!(Assets::transfer(Origin::signed(2), 0, 3, 1), Error::<Test>::LiquidityRestrictions);
+	});
+}
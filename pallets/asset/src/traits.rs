@@ -0,0 +1,116 @@
+//! Traits for handling the native currency and issued assets through one interface.
+
+use codec::FullCodec;
+use frame_support::{
+    dispatch,
+    traits::{BalanceStatus, LockIdentifier, WithdrawReasons},
+};
+use sp_runtime::traits::{AtLeast32BitUnsigned, MaybeSerializeDeserialize};
+use sp_std::fmt::Debug;
+
+/// A currency system that holds many currencies, addressed by an identifier.
+///
+/// This mirrors `frame_support::traits::Currency` without imbalances: deposits and withdrawals
+/// change the total issuance directly.
+pub trait MultiCurrency<AccountId> {
+    /// The identifier of a currency.
+    type CurrencyId: FullCodec + Eq + PartialEq + Copy + MaybeSerializeDeserialize + Debug;
+
+    /// The balance of an account.
+    type Balance: AtLeast32BitUnsigned + FullCodec + Copy + MaybeSerializeDeserialize + Debug + Default;
+
+    /// The minimum balance an account must hold of `currency_id` to exist.
+    fn minimum_balance(currency_id: Self::CurrencyId) -> Self::Balance;
+
+    /// The total amount of `currency_id` in existence.
+    fn total_issuance(currency_id: Self::CurrencyId) -> Self::Balance;
+
+    /// The balance of `who` in `currency_id` that can be transferred or withdrawn.
+    fn free_balance(currency_id: Self::CurrencyId, who: &AccountId) -> Self::Balance;
+
+    /// Ensure that `amount` of `currency_id` can be withdrawn from `who`.
+    fn ensure_can_withdraw(
+        currency_id: Self::CurrencyId,
+        who: &AccountId,
+        amount: Self::Balance,
+    ) -> dispatch::DispatchResult;
+
+    /// Move `amount` of `currency_id` from `from` to `to`.
+    fn transfer(
+        currency_id: Self::CurrencyId,
+        from: &AccountId,
+        to: &AccountId,
+        amount: Self::Balance,
+    ) -> dispatch::DispatchResult;
+
+    /// Create `amount` of `currency_id` in the account of `who`, increasing the total issuance.
+    fn deposit(
+        currency_id: Self::CurrencyId,
+        who: &AccountId,
+        amount: Self::Balance,
+    ) -> dispatch::DispatchResult;
+
+    /// Remove `amount` of `currency_id` from the account of `who`, decreasing the total issuance.
+    fn withdraw(
+        currency_id: Self::CurrencyId,
+        who: &AccountId,
+        amount: Self::Balance,
+    ) -> dispatch::DispatchResult;
+}
+
+/// A `MultiCurrency` whose balances can be set aside from the free balance.
+pub trait MultiReservableCurrency<AccountId>: MultiCurrency<AccountId> {
+    /// Whether `value` of `currency_id` can be reserved from `who`.
+    fn can_reserve(currency_id: Self::CurrencyId, who: &AccountId, value: Self::Balance) -> bool;
+
+    /// The reserved balance of `who` in `currency_id`.
+    fn reserved_balance(currency_id: Self::CurrencyId, who: &AccountId) -> Self::Balance;
+
+    /// Move `value` of `currency_id` from the free to the reserved balance of `who`.
+    fn reserve(
+        currency_id: Self::CurrencyId,
+        who: &AccountId,
+        value: Self::Balance,
+    ) -> dispatch::DispatchResult;
+
+    /// Move up to `value` of `currency_id` from the reserved to the free balance of `who`.
+    ///
+    /// Returns the amount that could not be unreserved.
+    fn unreserve(currency_id: Self::CurrencyId, who: &AccountId, value: Self::Balance) -> Self::Balance;
+
+    /// Move up to `value` of `currency_id` from the reserved balance of `slashed` to the balance
+    /// of `beneficiary` selected by `status`.
+    ///
+    /// Returns the amount that could not be moved.
+    fn repatriate_reserved(
+        currency_id: Self::CurrencyId,
+        slashed: &AccountId,
+        beneficiary: &AccountId,
+        value: Self::Balance,
+        status: BalanceStatus,
+    ) -> Result<Self::Balance, dispatch::DispatchError>;
+}
+
+/// A `MultiCurrency` whose free balances can be locked under named identifiers.
+pub trait MultiLockableCurrency<AccountId>: MultiCurrency<AccountId> {
+    /// Create or replace the lock `lock_id` on the free balance of `who` in `currency_id`.
+    fn set_lock(
+        lock_id: LockIdentifier,
+        currency_id: Self::CurrencyId,
+        who: &AccountId,
+        amount: Self::Balance,
+        reasons: WithdrawReasons,
+    );
+
+    /// Raise the lock `lock_id` to at least `amount` and `reasons`, creating it if needed.
+    fn extend_lock(
+        lock_id: LockIdentifier,
+        currency_id: Self::CurrencyId,
+        who: &AccountId,
+        amount: Self::Balance,
+        reasons: WithdrawReasons,
+    );
+
+    /// Remove the lock `lock_id` from the free balance of `who` in `currency_id`.
+    fn remove_lock(lock_id: LockIdentifier, currency_id: Self::CurrencyId, who: &AccountId);
+}
@@ -0,0 +1,31 @@
+//! Runtime API definition for the market pallet.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use codec::Codec;
+use sp_std::prelude::*;
+
+sp_api::decl_runtime_apis! {
+    /// Swap quotes from the pairs of the market, and the state of their order books.
+    pub trait MarketApi<AssetId, Balance> where
+        AssetId: Codec,
+        Balance: Codec,
+    {
+        /// The amounts of each asset of `path` when swapping exactly `amount_in` of its first
+        /// asset, or `None` if the swap is not possible.
+        fn get_amounts_out(amount_in: Balance, path: Vec<AssetId>) -> Option<Vec<Balance>>;
+
+        /// The amounts of each asset of `path` when swapping for exactly `amount_out` of its last
+        /// asset, or `None` if the swap is not possible.
+        fn get_amounts_in(amount_out: Balance, path: Vec<AssetId>) -> Option<Vec<Balance>>;
+
+        /// The bids and asks of the pair of `token0` and `token1` as the amounts of its first
+        /// asset at each price, for at most `depth` prices on each side, best first. Prices are
+        /// the bits of `UQ64x64` numbers of the second asset per unit of the first.
+        fn order_book(
+            token0: AssetId,
+            token1: AssetId,
+            depth: u32,
+        ) -> (Vec<(u128, Balance)>, Vec<(u128, Balance)>);
+    }
+}
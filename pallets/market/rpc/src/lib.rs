@@ -0,0 +1,123 @@
+//! RPC interface for the market pallet.
+
+use std::sync::Arc;
+
+use codec::Codec;
+use jsonrpc_core::{Error as RpcError, ErrorCode, Result};
+use jsonrpc_derive::rpc;
+use serde::{de::DeserializeOwned, Serialize};
+use sp_api::ProvideRuntimeApi;
+use sp_blockchain::HeaderBackend;
+use sp_runtime::{generic::BlockId, traits::Block as BlockT};
+
+pub use pallet_market_rpc_runtime_api::MarketApi as MarketRuntimeApi;
+
+/// The code of the error returned when the runtime API call fails.
+const RUNTIME_ERROR: i64 = 1;
+
+/// Market RPC methods.
+#[rpc]
+pub trait MarketApi<BlockHash, AssetId, Balance> {
+    /// Returns the amounts of each asset of `path` when swapping exactly `amount_in` of its
+    /// first asset, or `None` if the swap is not possible.
+    #[rpc(name = "market_getAmountsOut")]
+    fn get_amounts_out(
+        &self,
+        amount_in: Balance,
+        path: Vec<AssetId>,
+        at: Option<BlockHash>,
+    ) -> Result<Option<Vec<Balance>>>;
+
+    /// Returns the amounts of each asset of `path` when swapping for exactly `amount_out` of its
+    /// last asset, or `None` if the swap is not possible.
+    #[rpc(name = "market_getAmountsIn")]
+    fn get_amounts_in(
+        &self,
+        amount_out: Balance,
+        path: Vec<AssetId>,
+        at: Option<BlockHash>,
+    ) -> Result<Option<Vec<Balance>>>;
+
+    /// Returns the bids and asks of the pair of `token0` and `token1` as the amounts of its first
+    /// asset at each price, for at most `depth` prices on each side, best first.
+    #[rpc(name = "market_getOrderBook")]
+    fn order_book(
+        &self,
+        token0: AssetId,
+        token1: AssetId,
+        depth: u32,
+        at: Option<BlockHash>,
+    ) -> Result<(Vec<(u128, Balance)>, Vec<(u128, Balance)>)>;
+}
+
+/// An implementation of market specific RPC methods.
+pub struct Market<C, B> {
+    client: Arc<C>,
+    _marker: std::marker::PhantomData<B>,
+}
+
+impl<C, B> Market<C, B> {
+    /// Create a new `Market` with the given reference to the client.
+    pub fn new(client: Arc<C>) -> Self {
+        Market {
+            client,
+            _marker: Default::default(),
+        }
+    }
+}
+
+/// Map a runtime API error into an RPC error.
+fn runtime_error_into_rpc_err(err: impl std::fmt::Debug) -> RpcError {
+    RpcError {
+        code: ErrorCode::ServerError(RUNTIME_ERROR),
+        message: "Runtime trapped".into(),
+        data: Some(format!("{:?}", err).into()),
+    }
+}
+
+impl<C, Block, AssetId, Balance> MarketApi<<Block as BlockT>::Hash, AssetId, Balance>
+    for Market<C, Block>
+where
+    Block: BlockT,
+    C: Send + Sync + 'static + ProvideRuntimeApi<Block> + HeaderBackend<Block>,
+    C::Api: MarketRuntimeApi<Block, AssetId, Balance>,
+    AssetId: Codec + DeserializeOwned,
+    Balance: Codec + Serialize + DeserializeOwned,
+{
+    fn get_amounts_out(
+        &self,
+        amount_in: Balance,
+        path: Vec<AssetId>,
+        at: Option<<Block as BlockT>::Hash>,
+    ) -> Result<Option<Vec<Balance>>> {
+        let api = self.client.runtime_api();
+        let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+        api.get_amounts_out(&at, amount_in, path)
+            .map_err(runtime_error_into_rpc_err)
+    }
+
+    fn get_amounts_in(
+        &self,
+        amount_out: Balance,
+        path: Vec<AssetId>,
+        at: Option<<Block as BlockT>::Hash>,
+    ) -> Result<Option<Vec<Balance>>> {
+        let api = self.client.runtime_api();
+        let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+        api.get_amounts_in(&at, amount_out, path)
+            .map_err(runtime_error_into_rpc_err)
+    }
+
+    fn order_book(
+        &self,
+        token0: AssetId,
+        token1: AssetId,
+        depth: u32,
+        at: Option<<Block as BlockT>::Hash>,
+    ) -> Result<(Vec<(u128, Balance)>, Vec<(u128, Balance)>)> {
+        let api = self.client.runtime_api();
+        let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+        api.order_book(&at, token0, token1, depth)
+            .map_err(runtime_error_into_rpc_err)
+    }
+}
@@ -38,6 +38,27 @@
 //! * **Liquidity provider token:** The creation of a new asset by providing liquidity between two fungible assets. Liquidity provider token act as the share of the pool and gets the profit created from exchange fee.
 //! * **Asset exchange:** The process of an account transferring an asset to exchange with other kind of fungible asset.
 //! * **Fungible asset:** An asset whose units are interchangeable.
+//! * **Pair:** The pool of two fungible assets, identified by its liquidity provider token. Its
+//!   assets are kept in ascending order of their ids, so the same two assets always make the same
+//!   pair, whichever order they are given in.
+//! * **Price oracle:** The prices of the assets of each pair accumulated over time, and a ring
+//!   buffer of observations of them, from which `consult` averages prices over a time window.
+//!   Each accumulation happens before a trade moves the price, so a trade cannot move the average
+//!   of its own block. Prices are [`UQ64x64`](./uq64x64/struct.UQ64x64.html) fixed-point numbers,
+//!   and their accumulators wrap around rather than saturate.
+//! * **Protocol fee:** A share of the swap fees of every pair, minted as liquidity tokens to the
+//!   `FeeTo` account when liquidity is minted or burned. It is off until governance sets `FeeTo`.
+//! * **StableSwap pool:** A pool of two or more assets meant to trade near par, priced on the
+//!   StableSwap invariant of Curve. Its amplification sets how flat the price stays around the
+//!   balanced point, and governance may ramp it linearly over time.
+//! * **Weighted pool:** A pool of two or more assets with arbitrary weights adding up to one,
+//!   priced on the weighted product of its balances like Balancer. A pair is the weighted pool of
+//!   two assets with half the weight each. Liquidity may be added or removed in a single asset, in
+//!   which case the part beyond the weight of the asset pays the swap fee.
+//! * **Order book:** The resting limit orders of a pair, buying and selling its first asset for
+//!   its second at a price of their own, in order of price and then of time. An order that
+//!   crosses fills against both the book and the reserves of the pair, whichever gives the better
+//!   price at each step, and rests on the book for the rest with its funds reserved.
 //! * **Non-fungible asset:** An asset for which each unit has unique characteristics.
 //!
 //! ### Goals
@@ -53,20 +74,65 @@
 //! ### Dispatchable Functions
 //!
 //! * `create_pair` - Issues the total supply of a new fungible asset to the account of the caller of the function.
-//! * `swap` - Transfers an `amount` of units of fungible asset `id` from the balance of the function caller's account (`origin`) and receive the fungible asset to swap to
-//!  to a `target` account.
+//! * `swap` - Swaps exactly `amount` of fungible asset `from` from the caller's account for as much
+//!   of fungible asset `to` as the pair of the two gives at the constant product (x*y=k) price,
+//!   after a swap fee that stays in the reserves.
+//! * `swap_exact_tokens_for_tokens` - Swaps exactly `amount_in` of the first asset of `path` through
+//!   each consecutive pair of `path` for as much of its last asset as that gives.
+//! * `swap_tokens_for_exact_tokens` - Swaps as little of the first asset of `path` as it takes to
+//!   get exactly `amount_out` of its last asset through each consecutive pair of `path`.
+//! * `set_fee_to` - Sets the account that receives the protocol fee, or turns the fee off.
+//! * `create_pool` - Creates a constant product pair, a StableSwap pool or a weighted pool of the
+//!   given assets.
+//! * `add_stable_liquidity` - Deposits assets into a StableSwap pool for its liquidity tokens.
+//! * `remove_stable_liquidity` - Burns liquidity tokens of a StableSwap pool for its assets.
+//! * `swap_stable` - Swaps exactly `amount_in` of one asset of a StableSwap pool for another.
+//! * `ramp_amplification` - Ramps the amplification of a StableSwap pool towards a new value.
+//! * `stop_ramp_amplification` - Stops the amplification ramp of a StableSwap pool.
+//! * `add_weighted_liquidity` - Deposits every asset of a weighted pool for its liquidity tokens.
+//! * `remove_weighted_liquidity` - Burns liquidity tokens of a weighted pool for every asset.
+//! * `add_single_weighted_liquidity` - Deposits one asset of a weighted pool for its liquidity
+//!   tokens.
+//! * `remove_single_weighted_liquidity` - Burns liquidity tokens of a weighted pool for one asset.
+//! * `swap_weighted` - Swaps exactly `amount_in` of one asset of a weighted pool for another.
+//! * `place_limit_order` - Buys or sells an asset at a limit price, filling what it can against
+//!   the order book and the pair, and resting on the book for the rest.
+//! * `cancel_order` - Cancels a resting limit order and releases its reserved funds.
 //! * `burn` - Burns the lptoken and withdraws two fungible assets
 //! * `mint` - Deposits two fungible assets and receive lp token
 //! that called the function.
 //!
+//! Every call that trades or adds or removes liquidity takes a `deadline` after which it fails, and
+//! limits on the amounts it may give or take, so a transaction included late or after other trades
+//! cannot get an arbitrarily bad fill.
+//!
 //! Please refer to the [`Call`](./enum.Call.html) enum and its associated variants for documentation on each function.
 //!
 //! ### Public Functions
 //! <!-- Original author of descriptions: @gavofyork -->
 //!
 //! * `reserves` - Get the reserves of two fungible assets in a given pair
-//! * `pair` - Get the two fungible asset ids for a pair with a given liquidity asset id.
-//! * `lpt` - Get the liquidity asset id from the two fungible asset ids
+//! * `pair` - Get the `PairInfo` of a pair with a given liquidity asset id.
+//! * `lpt` - Get the liquidity asset id from the two fungible asset ids, in either order
+//! * `get_amount_out` - Get the output of a swap from the input and the reserves of the pair
+//! * `get_amount_in` - Get the input of a swap from the output and the reserves of the pair
+//! * `get_amounts_out` - Get the amounts of every asset along a swap path from its input
+//! * `get_amounts_in` - Get the amounts of every asset along a swap path from its output
+//! * `quote` - Value an amount at the ratio of two reserves
+//! * `fee_liquidity` - Get the liquidity tokens the protocol fee of a pair is owed
+//! * `current_cumulative_prices` - Get the cumulative prices of a pair as of now
+//! * `consult` - Get the time-weighted average prices of a pair over a time window
+//! * `stable_pool` - Get the `StablePool` of a StableSwap pool with a given liquidity asset id.
+//! * `amplification` - Get the current amplification of a StableSwap pool
+//! * `get_stable_amount_out` - Get the output of a swap through a StableSwap pool
+//! * `pool` - Get the `Pool` of a weighted pool with a given liquidity asset id.
+//! * `get_weighted_amount_out` - Get the output of a swap through a weighted pool
+//! * `order` - Get a resting limit `Order` by its id.
+//! * `order_book` - Get the bids and asks of a pair aggregated by price, best first
+//! * `reserve_price` - Value an amount of one asset in the other asset of its pair at the pair's
+//!   reserve price
+//! * `average_price` - Value an amount of one asset in the other asset of its pair at the pair's
+//!   time-weighted average price
 //!
 //! Please refer to the [`Module`](./struct.Module.html) struct for details on publicly available functions.
 //!
@@ -123,6 +189,8 @@
 //!
 //! * The total count of assets should be less than
 //!   `Trait::AssetId::max_value()`.
+//! * The assets of a StableSwap pool have the same number of decimals, as its invariant treats
+//!   one unit of each as worth the same.
 //!
 //! ## Related Modules
 //!
@@ -132,24 +200,38 @@
 // Ensure we're `no_std` when compiling for Wasm.
 #![cfg_attr(not(feature = "std"), no_std)]
 
-use asset;
-use pallet_balances;
+use asset::{self, MultiCurrency, MultiReservableCurrency};
+use codec::{Decode, Encode};
 mod math;
+mod stable_swap;
+mod weighted_math;
+pub mod uq64x64;
+pub use uq64x64::UQ64x64;
 use crate::sp_api_hidden_includes_decl_storage::hidden_include::sp_runtime::traits::*;
 use crate::sp_api_hidden_includes_decl_storage::hidden_include::sp_runtime::FixedPointNumber;
-use crate::sp_api_hidden_includes_decl_storage::hidden_include::traits::StoredMap;
 
 /// Edit this file to define custom logic or remove it if it is not needed.
 /// Learn more about FRAME and the core library of Substrate FRAME pallets:
 /// https://substrate.dev/docs/en/knowledgebase/runtime/frame
 use frame_support::{
     decl_error, decl_event, decl_module, decl_storage, dispatch, ensure,
-    traits::{ExistenceRequirement, Get, WithdrawReason},
+    storage::{
+        migration::{take_storage_value, StorageIterator},
+        IterableStorageMap, StoragePrefixedMap,
+    },
+    traits::{
+        BalanceStatus, EnsureOrigin, ExistenceRequirement, Get, ReservableCurrency, WithdrawReason,
+    },
+    transactional,
+    weights::Weight,
 };
-use frame_system::ensure_signed;
+use frame_system::{ensure_root, ensure_signed};
 use pallet_timestamp as timestamp;
+use sp_core::U256;
+use sp_runtime::helpers_128bit::multiply_by_rational;
 use sp_runtime::traits::Zero;
-use sp_runtime::FixedU128;
+use sp_runtime::{FixedU128, PerThing, Perbill, Permill, RuntimeDebug};
+use sp_std::{collections::btree_map::BTreeMap, prelude::*, vec};
 
 #[cfg(test)]
 mod mock;
@@ -157,25 +239,291 @@ mod mock;
 #[cfg(test)]
 mod tests;
 /// Configure the pallet by specifying the parameters and types on which it depends.
-pub trait Trait:
-    frame_system::Trait + asset::Trait + timestamp::Trait + pallet_balances::Trait
-{
+///
+/// Both the native currency and issued assets are handled through `asset::MultiCurrency`, so a
+/// pair may include the native currency under `asset::Trait::NativeAssetId`.
+pub trait Trait: frame_system::Trait + asset::Trait + timestamp::Trait {
     /// Because this pallet emits events, it depends on the runtime's definition of an event.
     type Event: From<Event<Self>>
         + Into<<Self as frame_system::Trait>::Event>
         + Into<<Self as asset::Trait>::Event>;
+
+    /// The fee taken from the input of every swap. It stays in the reserves, so it accrues to
+    /// the liquidity providers.
+    type SwapFee: Get<Permill>;
+
+    /// The most assets a swap path may go through.
+    type MaxPathLength: Get<u32>;
+
+    /// The least time between two observations of the prices of a pair.
+    type ObservationPeriod: Get<Self::Moment>;
+
+    /// The most observations kept for each pair. `consult` can average over windows of up to
+    /// this many `ObservationPeriod`s.
+    type MaxObservations: Get<u32>;
+
+    /// The origin that may set the receiver of the protocol fee.
+    type FeeToOrigin: EnsureOrigin<Self::Origin>;
+
+    /// The most assets a StableSwap pool may hold.
+    type MaxStableAssets: Get<u32>;
+
+    /// The largest amplification of a StableSwap pool.
+    type MaxAmplification: Get<u32>;
+
+    /// The shortest ramp of the amplification of a StableSwap pool, and the least time from the
+    /// start of one ramp to the start of the next.
+    type MinRampTime: Get<Self::Moment>;
+
+    /// The most assets a weighted pool may hold.
+    type MaxWeightedAssets: Get<u32>;
+
+    /// The most resting limit orders on each side of the order book of a pair.
+    type MaxOrders: Get<u32>;
+
+    /// The deposit in the native currency reserved for each resting limit order, so that filling
+    /// the order book is not free.
+    type OrderDeposit: Get<<Self as asset::Trait>::Balance>;
+}
+
+/// The liquidity tokens minted for the deposit that creates a weighted pool.
+const INITIAL_WEIGHTED_SUPPLY: u128 = 100_000_000_000_000_000_000;
+
+/// The part of `INITIAL_WEIGHTED_SUPPLY` locked with the default account, so the supply of a
+/// weighted pool never returns to zero.
+const MIN_WEIGHTED_SUPPLY: u128 = 1_000_000;
+
+/// The smallest weight of an asset in a weighted pool.
+const MIN_WEIGHT: Perbill = Perbill::from_percent(2);
+
+/// The most storage keys a withdrawal or deposit of an asset reads: its balance, reserved
+/// balance, supply, minimum balance, locks, vesting schedules and freezes.
+const ASSET_MOVE_READS: Weight = 8;
+
+/// The most storage keys a withdrawal or deposit of an asset writes: its balance, supply and
+/// holder indices, and the freeze lifted when an account is reaped.
+const ASSET_MOVE_WRITES: Weight = 5;
+
+/// The storage keys read to accumulate the price of a pair before its reserves move.
+const ORACLE_UPDATE_READS: Weight = 5;
+
+/// The storage keys written to accumulate the price of a pair before its reserves move.
+const ORACLE_UPDATE_WRITES: Weight = 3;
+
+/// A conservative weight of one round of Newton's method on the StableSwap invariant, for each
+/// asset of the pool.
+const STABLE_ROUND_WEIGHT: Weight = 1_000_000;
+
+/// The worst-case weight of computing the StableSwap invariant of a pool of `n` assets `times`
+/// times, each of which may take every round of Newton's method.
+fn stable_math_weight(n: Weight, times: Weight) -> Weight {
+    STABLE_ROUND_WEIGHT
+        .saturating_mul(stable_swap::MAX_ITERATIONS as Weight)
+        .saturating_mul(n)
+        .saturating_mul(times)
+}
+
+/// A conservative weight of one term of the binomial series, or one multiplication, of a power in
+/// a weighted pool.
+const POW_STEP_WEIGHT: Weight = 1_000_000;
+
+/// The worst-case weight of one power in a weighted pool: every term of its series, and a
+/// multiplication for each whole unit of an exponent of at most `1 / MIN_WEIGHT`.
+fn weighted_math_weight() -> Weight {
+    let multiplications = (Perbill::ACCURACY / MIN_WEIGHT.deconstruct()) as Weight;
+    POW_STEP_WEIGHT
+        .saturating_mul(weighted_math::MAX_POW_ITERATIONS as Weight)
+        .saturating_add(POW_STEP_WEIGHT.saturating_mul(multiplications))
+}
+
+/// How a pool prices trades between its assets.
+#[derive(Encode, Decode, Clone, Eq, PartialEq, RuntimeDebug)]
+pub enum PoolKind {
+    /// A pair of assets trading at the constant product of their reserves.
+    ConstantProduct,
+    /// Two or more assets trading near par on the StableSwap invariant.
+    StableSwap {
+        /// How flat the price curve is around the balanced point.
+        amplification: u32,
+    },
+    /// Two or more assets trading at the weighted product of their balances.
+    Weighted {
+        /// The weight of each asset, in the order the assets are given. They must add up to one.
+        weights: Vec<Perbill>,
+    },
+}
+
+/// A pool of two or more assets on the weighted-product invariant, generalizing a pair to more
+/// assets with weights other than one half each.
+#[derive(Encode, Decode, Clone, Default, Eq, PartialEq, RuntimeDebug)]
+pub struct Pool<AssetId, Balance> {
+    /// The assets of the pool, in ascending order of their ids.
+    pub assets: Vec<AssetId>,
+    /// The amount of each asset the pool holds, in the order of `assets`.
+    pub balances: Vec<Balance>,
+    /// The normalized weight of each asset, in the order of `assets`.
+    pub weights: Vec<Perbill>,
+}
+
+/// The identifier of a limit order.
+pub type OrderId = u64;
+
+/// The side of a limit order in the order book of a pair.
+#[derive(Encode, Decode, Clone, Copy, Eq, PartialEq, RuntimeDebug)]
+pub enum OrderSide {
+    /// Buy the first asset of the pair with the second.
+    Buy,
+    /// Sell the first asset of the pair for the second.
+    Sell,
+}
+
+impl OrderSide {
+    /// The side that orders of this side match against.
+    fn opposite(self) -> Self {
+        match self {
+            OrderSide::Buy => OrderSide::Sell,
+            OrderSide::Sell => OrderSide::Buy,
+        }
+    }
+}
+
+/// A limit order resting in the order book of a pair.
+#[derive(Encode, Decode, Clone, Eq, PartialEq, RuntimeDebug)]
+pub struct Order<AccountId, AssetId, Balance> {
+    /// The account that placed the order.
+    pub owner: AccountId,
+    /// The asset of the pair with the smaller id, which the order buys or sells.
+    pub base: AssetId,
+    /// The asset of the pair with the larger id, which the order pays or takes.
+    pub quote: AssetId,
+    /// Whether the order buys or sells `base`.
+    pub side: OrderSide,
+    /// The price in `quote` per unit of `base`.
+    pub price: UQ64x64,
+    /// The amount of `base` left to buy or sell.
+    pub amount: Balance,
+    /// The amount of `quote` for a buy, or of `base` for a sell, still reserved for the order.
+    pub locked: Balance,
+    /// The native currency reserved from `owner` while the order rests.
+    pub deposit: Balance,
+}
+
+/// The resting orders of a pair aggregated by price, best first.
+#[derive(Encode, Decode, Clone, Default, Eq, PartialEq, RuntimeDebug)]
+pub struct OrderBookSnapshot<Balance> {
+    /// The amount of the first asset bid at each price, highest first.
+    pub bids: Vec<(UQ64x64, Balance)>,
+    /// The amount of the first asset asked at each price, lowest first.
+    pub asks: Vec<(UQ64x64, Balance)>,
+}
+
+/// How a limit order fills against the order book and the pair of its assets.
+struct Matching<AssetId, Balance> {
+    /// The liquidity token of the pair, the amounts of the first and second asset traded with
+    /// it, and its reserves after.
+    pair: Option<(AssetId, Balance, Balance, (Balance, Balance))>,
+    /// The resting orders traded with, and the amounts of the first and second asset traded.
+    fills: Vec<(OrderId, Balance, Balance)>,
+    /// The amount of the first asset left to rest on the book.
+    remaining: Balance,
+}
+
+/// A pool of two or more assets on the StableSwap invariant.
+#[derive(Encode, Decode, Clone, Default, Eq, PartialEq, RuntimeDebug)]
+pub struct StablePool<AssetId, Balance, Moment> {
+    /// The assets of the pool, in ascending order of their ids.
+    pub assets: Vec<AssetId>,
+    /// The amount of each asset the pool holds, in the order of `assets`.
+    pub balances: Vec<Balance>,
+    /// The amplification at the start of the current ramp.
+    pub initial_amplification: u32,
+    /// The amplification at the end of the current ramp, and after it.
+    pub future_amplification: u32,
+    /// The start of the current ramp.
+    pub initial_amplification_time: Moment,
+    /// The end of the current ramp.
+    pub future_amplification_time: Moment,
+}
+
+/// A pair of assets pooled against each other.
+#[derive(Encode, Decode, Clone, Default, Eq, PartialEq, RuntimeDebug)]
+pub struct PairInfo<AssetId, Balance> {
+    /// The asset of the pair with the smaller id.
+    pub token0: AssetId,
+    /// The asset of the pair with the larger id.
+    pub token1: AssetId,
+    /// The amount of `token0` the pair holds.
+    pub reserve0: Balance,
+    /// The amount of `token1` the pair holds.
+    pub reserve1: Balance,
+    /// The product of the reserves as of the last liquidity event while the protocol fee was on,
+    /// or zero.
+    pub k_last: U256,
+}
+
+/// The layout of the storage of the module.
+#[derive(Encode, Decode, Clone, Copy, Eq, PartialEq, RuntimeDebug)]
+enum Releases {
+    /// Tokens and reserves of a pair in separate maps, in the order the pair was created with.
+    V1_0_0,
+    /// `PairInfo` keyed by liquidity token, and pair keys in ascending order.
+    V2_0_0,
+    /// The time prices were last accumulated kept for each pair, and observations of them.
+    V3_0_0,
+    /// Cumulative prices in `UQ64x64` rather than `FixedU128`.
+    V4_0_0,
+}
+
+/// The cumulative prices of a pair at a point in time.
+#[derive(Encode, Decode, Clone, Default, Eq, PartialEq, RuntimeDebug)]
+pub struct Observation<Moment> {
+    /// The time of the observation.
+    pub timestamp: Moment,
+    /// The price of the first asset of the pair in the second, accumulated over time.
+    pub price0_cumulative: UQ64x64,
+    /// The price of the second asset of the pair in the first, accumulated over time.
+    pub price1_cumulative: UQ64x64,
+}
+
+impl Default for Releases {
+    fn default() -> Self {
+        Releases::V1_0_0
+    }
 }
 
 // The pallet's runtime storage items.
 // https://substrate.dev/docs/en/knowledgebase/runtime/storage
 decl_storage! {
     trait Store for Module<T: Trait> as SwapModule {
-        pub LastBlockTimestamp get(fn last_block_timestamp): T::Moment;
+        // The time the prices of each pair were last accumulated. key is lptoken identifier
+        pub LastBlockTimestamp get(fn last_block_timestamp): map hasher(blake2_128_concat) T::AssetId => T::Moment;
         // Accumulated price data for each pair. key is lptoken identifier
-        pub LastAccumulativePrice get(fn last_cumulative_price): map hasher(blake2_128_concat) T::AssetId => (FixedU128, FixedU128);
-        pub Reserves get(fn reserves): map hasher(blake2_128_concat) T::AssetId => (<T as asset::Trait>::Balance, <T as asset::Trait>::Balance);
-        pub Pairs get(fn pair): map hasher(blake2_128_concat) T::AssetId => (T::AssetId, T::AssetId);
-        pub LPTokens get(fn lpt): map hasher(blake2_128_concat) (T::AssetId, T::AssetId) => Option<T::AssetId>;
+        pub LastAccumulativePrice get(fn last_cumulative_price): map hasher(blake2_128_concat) T::AssetId => (UQ64x64, UQ64x64);
+        // The ring buffer of price observations of each pair
+        pub Observations get(fn observations): double_map hasher(blake2_128_concat) T::AssetId, hasher(twox_64_concat) u32 => Option<Observation<T::Moment>>;
+        // The slot of the newest observation of each pair
+        pub ObservationIndex get(fn observation_index): map hasher(blake2_128_concat) T::AssetId => Option<u32>;
+        // The pair of each liquidity token
+        pub Pairs get(fn pair): map hasher(blake2_128_concat) T::AssetId => Option<PairInfo<T::AssetId, <T as asset::Trait>::Balance>>;
+        // The liquidity token of each pair, keyed by its assets in ascending order
+        pub LPTokens: map hasher(blake2_128_concat) (T::AssetId, T::AssetId) => Option<T::AssetId>;
+        // The account that receives the protocol fee, if it is on
+        pub FeeTo get(fn fee_to): Option<T::AccountId>;
+        // The StableSwap pool of each liquidity token
+        pub StablePools get(fn stable_pool): map hasher(blake2_128_concat) T::AssetId => Option<StablePool<T::AssetId, <T as asset::Trait>::Balance, T::Moment>>;
+        // The liquidity token of each StableSwap pool, keyed by its assets in ascending order
+        pub StableLPTokens get(fn stable_lpt): map hasher(blake2_128_concat) Vec<T::AssetId> => Option<T::AssetId>;
+        // The weighted pool of each liquidity token
+        pub Pools get(fn pool): map hasher(blake2_128_concat) T::AssetId => Option<Pool<T::AssetId, <T as asset::Trait>::Balance>>;
+        // The liquidity token of each weighted pool, keyed by its assets in ascending order
+        pub PoolLPTokens get(fn pool_lpt): map hasher(blake2_128_concat) Vec<T::AssetId> => Option<T::AssetId>;
+        // The resting limit orders
+        pub Orders get(fn order): map hasher(twox_64_concat) OrderId => Option<Order<T::AccountId, T::AssetId, <T as asset::Trait>::Balance>>;
+        // The id of the next limit order
+        pub NextOrderId get(fn next_order_id): OrderId;
+        // The prices and ids of the resting orders of each side of the book of each pair, keyed by its assets in ascending order, best first
+        pub OrderBook get(fn order_book_side): double_map hasher(blake2_128_concat) (T::AssetId, T::AssetId), hasher(twox_64_concat) OrderSide => Vec<(UQ64x64, OrderId)>;
+        StorageVersion build(|_: &GenesisConfig| Releases::V4_0_0): Releases;
     }
 }
 
@@ -189,15 +537,44 @@ decl_event!(
         Token1 = <T as asset::Trait>::AssetId,
         LPToken = <T as asset::Trait>::AssetId,
         Balance = <T as asset::Trait>::Balance,
+        Moment = <T as timestamp::Trait>::Moment,
     {
         /// Event documentation should end with an array that provides descriptive names for event
         /// parameters. [something, who]
         SomethingStored(u32, AccountId),
         CreatePair(Token0, Token1, LPToken),
-        Swap(Token0, Balance, Token1, Balance),
+        /// An account swapped an amount of one asset for an amount of another.
+        /// \[who, asset_in, amount_in, asset_out, amount_out\]
+        Swap(AccountId, Token0, Balance, Token1, Balance),
         MintedLiquidity(Token0, Token1, LPToken),
         BurnedLiquidity(LPToken, Token0, Token1),
-        Sync(FixedU128, FixedU128),
+        Sync(UQ64x64, UQ64x64),
+        /// The receiver of the protocol fee was set, or the fee turned off. \[fee_to\]
+        FeeToSet(Option<AccountId>),
+        /// A StableSwap pool was created. \[who, lpt\]
+        StablePoolCreated(AccountId, LPToken),
+        /// Liquidity tokens of a StableSwap pool were minted for a deposit. \[who, lpt, minted\]
+        StableLiquidityAdded(AccountId, LPToken, Balance),
+        /// Liquidity tokens of a StableSwap pool were burned for a withdrawal. \[who, lpt, burned\]
+        StableLiquidityRemoved(AccountId, LPToken, Balance),
+        /// The amplification of a StableSwap pool started ramping.
+        /// \[lpt, amplification, future_amplification, future_time\]
+        AmplificationRamped(LPToken, u32, u32, Moment),
+        /// The amplification of a StableSwap pool stopped ramping. \[lpt, amplification\]
+        AmplificationRampStopped(LPToken, u32),
+        /// A weighted pool was created. \[who, lpt\]
+        WeightedPoolCreated(AccountId, LPToken),
+        /// Liquidity tokens of a weighted pool were minted for a deposit. \[who, lpt, minted\]
+        WeightedLiquidityAdded(AccountId, LPToken, Balance),
+        /// Liquidity tokens of a weighted pool were burned for a withdrawal. \[who, lpt, burned\]
+        WeightedLiquidityRemoved(AccountId, LPToken, Balance),
+        /// A limit order rested on the book for what it did not fill. \[order_id, who, amount\]
+        OrderPlaced(OrderId, AccountId, Balance),
+        /// A resting limit order was filled, in full or in part.
+        /// \[order_id, taker, base_amount, quote_amount\]
+        OrderFilled(OrderId, AccountId, Balance, Balance),
+        /// A resting limit order was cancelled. \[order_id, who\]
+        OrderCancelled(OrderId, AccountId),
     }
 );
 
@@ -216,6 +593,46 @@ decl_error! {
         InsufficientLiquidityBurned,
         InsufficientOutputAmount,
         K,
+        /// There is no pair of the two assets
+        UnknownPair,
+        /// The pair does not hold enough of the output asset
+        InsufficientLiquidity,
+        /// The deadline of the call has passed
+        Expired,
+        /// More of the input asset than the given maximum would be swapped
+        ExcessiveInputAmount,
+        /// A swap path has fewer than two or more than `MaxPathLength` assets
+        InvalidPath,
+        /// Less of the first asset of the call than the given minimum would be deposited or withdrawn
+        InsufficientAmount0,
+        /// Less of the second asset of the call than the given minimum would be deposited or withdrawn
+        InsufficientAmount1,
+        /// There is no StableSwap or weighted pool of the liquidity token
+        UnknownPool,
+        /// A pool has too few or too many assets, or not one amount for each of them
+        InvalidPoolAssets,
+        /// The amplification is zero, above `MaxAmplification` or more than tenfold the current one
+        InvalidAmplification,
+        /// The amplification ramp is shorter than `MinRampTime`, or the last one started too recently
+        RampTooFast,
+        /// The invariant of a pool could not be computed
+        InvariantFailed,
+        /// A weight is below the minimum, or the weights do not add up to one
+        InvalidWeights,
+        /// A trade puts in more than half the balance of a weighted pool, or takes out more than a third
+        TradeTooLarge,
+        /// There is no resting limit order with the id
+        UnknownOrder,
+        /// The limit order belongs to another account
+        NotOrderOwner,
+        /// The assets of an order book must be given in ascending order
+        UnsortedAssets,
+        /// A limit order has a zero price or amount
+        InvalidOrder,
+        /// The side of the order book the order would rest on is full
+        OrderBookFull,
+        /// A resting order no longer holds the reserve it would fill from
+        InsufficientReserve,
     }
 }
 
@@ -230,154 +647,1956 @@ decl_module! {
         // Events must be initialized if they are used by the pallet.
         fn deposit_event() = default;
 
+        // Move the storage of older releases into the current layout.
+        fn on_runtime_upgrade() -> Weight {
+            let mut weight = T::DbWeight::get().reads(1);
+            let version = StorageVersion::get();
+            if version == Releases::V1_0_0 {
+                weight = weight.saturating_add(Self::migrate_to_pair_info());
+            }
+            if version == Releases::V1_0_0 || version == Releases::V2_0_0 {
+                weight = weight.saturating_add(Self::migrate_to_pair_oracles());
+            }
+            if version != Releases::V4_0_0 {
+                weight = weight.saturating_add(Self::migrate_to_uq64x64());
+                StorageVersion::put(Releases::V4_0_0);
+                weight = weight.saturating_add(T::DbWeight::get().writes(1));
+            }
+            weight
+        }
 
         // Mint liquidity by adding a liquidity in a pair
-        #[weight = 10_000 + T::DbWeight::get().reads_writes(1,1)]
-        pub fn mint_liquidity(origin, token0: T::AssetId, amount0: <T as asset::Trait>::Balance, token1: T::AssetId, amount1: <T as asset::Trait>::Balance) -> dispatch::DispatchResult {
+        //
+        // `amount0` and `amount1` are the most the caller deposits. For an existing pair, only
+        // what matches the ratio of the reserves is taken, and it must be at least `amount0_min`
+        // and `amount1_min`. A new pair locks the first unit of its liquidity with the default
+        // account, so its supply never returns to zero. Fails once `deadline` has passed.
+        //
+        // Weighs the worse of both paths: up to two withdrawals, issuing the liquidity token and
+        // two deposits of it, the oracle update and the pair's storage.
+        #[weight = 10_000 + T::DbWeight::get().reads_writes(
+            5 + 5 * ASSET_MOVE_READS + ORACLE_UPDATE_READS,
+            6 + 5 * ASSET_MOVE_WRITES + ORACLE_UPDATE_WRITES
+        )]
+        #[transactional]
+        pub fn mint_liquidity(
+            origin,
+            token0: T::AssetId,
+            amount0: <T as asset::Trait>::Balance,
+            token1: T::AssetId,
+            amount1: <T as asset::Trait>::Balance,
+            amount0_min: <T as asset::Trait>::Balance,
+            amount1_min: <T as asset::Trait>::Balance,
+            deadline: T::Moment
+        ) -> dispatch::DispatchResult {
             let minimum_liquidity = <T as asset::Trait>::Balance::from(1);
             let sender = ensure_signed(origin)?;
-
-            // Burn assets from user to deposit to reserves
-            asset::Module::<T>::burn_from_system(&token0, &sender, &amount0)?;
-            asset::Module::<T>::burn_from_system(&token1, &sender, &amount1)?;
-            match LPTokens::<T>::get((&token0, &token1)) {
+            Self::ensure_deadline(deadline)?;
+            ensure!(token0 != token1, Error::<T>::IdenticalIdentifier);
+            match Self::pair_reserves(token0, token1) {
                 // create pair if lpt does not exist
                 None => {
-                    // Deposit assets to the reserve
-                    <Reserves<T>>::insert(&token0, (amount0, amount1));
-                    let mut lptoken_amount: <T as asset::Trait>::Balance = math::sqrt::<T>(amount0 * amount1);
-                    lptoken_amount = lptoken_amount.checked_sub(&minimum_liquidity).expect("Integer overflow");
+                    // The first `minimum_liquidity` is locked forever, so the supply never returns to zero
+                    let product = amount0.checked_mul(&amount1).ok_or(Error::<T>::StorageOverflow)?;
+                    let lptoken_amount = math::sqrt::<T>(product)
+                        .checked_sub(&minimum_liquidity)
+                        .filter(|amount| !amount.is_zero())
+                        .ok_or(Error::<T>::InsufficientLiquidityMinted)?;
+                    asset::Module::<T>::ensure_can_withdraw(token0, &sender, amount0)?;
+                    asset::Module::<T>::ensure_can_withdraw(token1, &sender, amount1)?;
+                    // Withdraw assets from user to deposit to reserves
+                    asset::Module::<T>::withdraw(token0, &sender, amount0)?;
+                    asset::Module::<T>::withdraw(token1, &sender, amount1)?;
                     // Issue LPtoken
                     asset::Module::<T>::issue_from_system(<T as asset::Trait>::Balance::from(0))?;
                     let mut lptoken_id: T::AssetId = asset::NextAssetId::<T>::get();
                     lptoken_id -= One::one();
-                    // Mint LPtoken to the sender
-                    asset::Module::<T>::mint_from_system(&lptoken_id, &sender, &lptoken_amount)?;
-                    // Insert pair info
-                    <Pairs<T>>::insert(lptoken_id, (token0, token1));
-                    Self::deposit_event(RawEvent::CreatePair(token0, token1, lptoken_id));
+                    // Lock the minimum liquidity with the system account, then mint LPtoken to the sender
+                    asset::Module::<T>::deposit(lptoken_id, &T::AccountId::default(), minimum_liquidity)?;
+                    asset::Module::<T>::deposit(lptoken_id, &sender, lptoken_amount)?;
+                    // Insert pair info, with the deposits as the reserves
+                    let mut info = if token0 < token1 {
+                        PairInfo { token0, token1, reserve0: amount0, reserve1: amount1, k_last: U256::zero() }
+                    } else {
+                        PairInfo { token0: token1, token1: token0, reserve0: amount1, reserve1: amount0, k_last: U256::zero() }
+                    };
+                    if Self::fee_to().is_some() {
+                        info.k_last = Self::k(info.reserve0, info.reserve1);
+                    }
+                    <LPTokens<T>>::insert((info.token0, info.token1), lptoken_id);
+                    Self::deposit_event(RawEvent::CreatePair(info.token0, info.token1, lptoken_id));
+                    <Pairs<T>>::insert(lptoken_id, info);
+                    Self::start_oracle(lptoken_id);
                     Ok(())
                 },
-                // when lpt exists and total supply is superset of 0
-                Some(lpt) if asset::Module::<T>::total_supply(lpt) > <T as asset::Trait>::Balance::from(0) => {
-                    let total_supply = asset::Module::<T>::total_supply(lpt);
-                    let reserves = <Reserves<T>>::get(lpt);
-                    let left = amount0.checked_mul(&total_supply).expect("Multiplicaiton overflow").checked_div(&reserves.0).expect("Divide by zero error");
-                    let right = amount1.checked_mul(&total_supply).expect("Multiplicaiton overflow").checked_div(&reserves.1).expect("Divide by zero error");
+                Some((lpt, reserve0, reserve1, in_order)) => {
+                    let (amount0, amount1) = Self::optimal_amounts(
+                        (amount0, amount1),
+                        (amount0_min, amount1_min),
+                        (reserve0, reserve1),
+                    )?;
+                    let info = Self::pair(lpt).ok_or(Error::<T>::UnknownPair)?;
+                    let fee_to = Self::fee_to();
+                    let fee = Self::fee_liquidity(&fee_to, &info, asset::Module::<T>::total_supply(lpt));
+                    let total_supply = asset::Module::<T>::total_supply(lpt)
+                        .checked_add(&fee)
+                        .ok_or(Error::<T>::StorageOverflow)?;
+                    ensure!(
+                        !total_supply.is_zero() && !reserve0.is_zero() && !reserve1.is_zero(),
+                        Error::<T>::InsufficientLiquidityMinted
+                    );
+                    let left = amount0.checked_mul(&total_supply).ok_or(Error::<T>::StorageOverflow)? / reserve0;
+                    let right = amount1.checked_mul(&total_supply).ok_or(Error::<T>::StorageOverflow)? / reserve1;
                     let lptoken_amount = math::min::<T>(left, right);
+                    ensure!(!lptoken_amount.is_zero(), Error::<T>::InsufficientLiquidityMinted);
+                    asset::Module::<T>::ensure_can_withdraw(token0, &sender, amount0)?;
+                    asset::Module::<T>::ensure_can_withdraw(token1, &sender, amount1)?;
+                    // Withdraw assets from user to deposit to reserves
+                    asset::Module::<T>::withdraw(token0, &sender, amount0)?;
+                    asset::Module::<T>::withdraw(token1, &sender, amount1)?;
+                    // Accumulate the price that held until now before moving it
+                    Self::_update(&lpt)?;
                     // Deposit assets to the reserve
-                    <Reserves<T>>::mutate(lpt, |reserves| {
-                        reserves.0 += amount0;
-                        reserves.1 += amount1;
-                    });
-                    // Mint LPtoken to the sender
-                    asset::Module::<T>::mint_from_system(&lpt, &sender, &lptoken_amount)?;
+                    let (amount0, amount1) = if in_order { (amount0, amount1) } else { (amount1, amount0) };
+                    let reserves = (info.reserve0 + amount0, info.reserve1 + amount1);
+                    Self::set_liquidity_reserves(lpt, info, reserves, fee_to.is_some());
+                    // Mint the protocol fee, then LPtoken to the sender
+                    Self::mint_fee(lpt, &fee_to, fee)?;
+                    asset::Module::<T>::deposit(lpt, &sender, lptoken_amount)?;
+                    let (token0, token1) = Self::sorted(token0, token1);
                     Self::deposit_event(RawEvent::CreatePair(token0, token1, lpt));
-                    Self::_update(&lpt)?;
                     Ok(())
                 },
-                Some(lpt) if asset::Module::<T>::total_supply(lpt) < <T as asset::Trait>::Balance::from(0) => {
-                    Err(Error::<T>::InsufficientLiquidityMinted)?
-                },
-                Some(_) => Err(Error::<T>::NoneValue)?,
             }
         }
 
-        #[weight = 10_000 + T::DbWeight::get().reads_writes(1,1)]
-        pub fn burn_liquidity(origin, lpt: T::AssetId, amount: <T as asset::Trait>::Balance) -> dispatch::DispatchResult{
+        // Burn `amount` of liquidity token `lpt` for the pro-rata share of the reserves, which
+        // must be at least `amount0_min` and `amount1_min`. Fails once `deadline` has passed.
+        #[weight = 10_000 + T::DbWeight::get().reads_writes(
+            4 + 4 * ASSET_MOVE_READS + ORACLE_UPDATE_READS,
+            1 + 4 * ASSET_MOVE_WRITES + ORACLE_UPDATE_WRITES
+        )]
+        #[transactional]
+        pub fn burn_liquidity(
+            origin,
+            lpt: T::AssetId,
+            amount: <T as asset::Trait>::Balance,
+            amount0_min: <T as asset::Trait>::Balance,
+            amount1_min: <T as asset::Trait>::Balance,
+            deadline: T::Moment
+        ) -> dispatch::DispatchResult {
             let sender = ensure_signed(origin)?;
-            let reserves = <Reserves<T>>::get(lpt);
-            let tokens = <Pairs<T>>::get(lpt);
-            let total_supply = asset::Module::<T>::total_supply(lpt);
+            Self::ensure_deadline(deadline)?;
+            let info = Self::pair(lpt).ok_or(Error::<T>::UnknownPair)?;
+            let fee_to = Self::fee_to();
+            let fee = Self::fee_liquidity(&fee_to, &info, asset::Module::<T>::total_supply(lpt));
+            let total_supply = asset::Module::<T>::total_supply(lpt)
+                .checked_add(&fee)
+                .ok_or(Error::<T>::StorageOverflow)?;
 
             // Calculate rewards for providing liquidity with pro-rata distribution
-            let reward0 = amount.checked_mul(&reserves.0).expect("Multiplicaiton overflow").checked_div(&total_supply).expect("Divide by zero error");
-            let reward1 = amount.checked_mul(&reserves.1).expect("Multiplicaiton overflow").checked_div(&total_supply).expect("Divide by zero error");
+            let reward0 = amount
+                .checked_mul(&info.reserve0)
+                .ok_or(Error::<T>::StorageOverflow)?
+                .checked_div(&total_supply)
+                .ok_or(Error::<T>::InsufficientLiquidityBurned)?;
+            let reward1 = amount
+                .checked_mul(&info.reserve1)
+                .ok_or(Error::<T>::StorageOverflow)?
+                .checked_div(&total_supply)
+                .ok_or(Error::<T>::InsufficientLiquidityBurned)?;
 
             // Ensure rewards exist
             ensure!(reward0 > Zero::zero() && reward1 > Zero::zero(), Error::<T>::InsufficientLiquidityBurned);
+            ensure!(reward0 >= amount0_min, Error::<T>::InsufficientAmount0);
+            ensure!(reward1 >= amount1_min, Error::<T>::InsufficientAmount1);
 
             // Distribute reward to the sender
-            asset::Module::<T>::burn_from_system(&lpt, &sender, &amount)?;
-            asset::Module::<T>::mint_from_system(&tokens.0, &sender, &reward0)?;
-            asset::Module::<T>::mint_from_system(&tokens.1, &sender, &reward1)?;
+            asset::Module::<T>::withdraw(lpt, &sender, amount)?;
+            Self::mint_fee(lpt, &fee_to, fee)?;
+            asset::Module::<T>::deposit(info.token0, &sender, reward0)?;
+            asset::Module::<T>::deposit(info.token1, &sender, reward1)?;
 
+            // Accumulate the price that held until now before moving it
+            Self::_update(&lpt)?;
             // Update reserve when the balance is set
-            <Reserves<T>>::mutate(lpt, |reserves| {
-                reserves.0 -= reward0;
-                reserves.1 -= reward1;
-            });
+            let (token0, token1) = (info.token0, info.token1);
+            let reserves = (info.reserve0 - reward0, info.reserve1 - reward1);
+            Self::set_liquidity_reserves(lpt, info, reserves, fee_to.is_some());
 
             // Deposit event that the liquidity is burned successfully
-            Self::deposit_event(RawEvent::BurnedLiquidity(lpt, tokens.0, tokens.1));
-            // Update price
-            Self::_update(&lpt)?;
+            Self::deposit_event(RawEvent::BurnedLiquidity(lpt, token0, token1));
+            Ok(())
+        }
+
+        /// Swap exactly `amount` of asset `from` for as much of asset `to` as their pair gives at
+        /// the constant product price, after the swap fee.
+        ///
+        /// Fails if that is less than `amount_out_min`, or once `deadline` has passed.
+        #[weight = 10_000 + T::DbWeight::get().reads_writes(2,3)]
+        #[transactional]
+        pub fn swap(
+            origin,
+            from: T::AssetId,
+            amount: <T as asset::Trait>::Balance,
+            to: T::AssetId,
+            amount_out_min: <T as asset::Trait>::Balance,
+            deadline: T::Moment
+        ) -> dispatch::DispatchResult {
+            let sender = ensure_signed(origin)?;
+            Self::ensure_deadline(deadline)?;
+            let path = vec![from, to];
+            let amounts = Self::get_amounts_out(amount, &path)?;
+            ensure!(amounts[1] >= amount_out_min, Error::<T>::InsufficientOutputAmount);
+
+            Self::do_swap(&sender, &amounts, &path)
+        }
+
+        /// Swap exactly `amount_in` of the first asset of `path` for as much of its last asset as
+        /// swapping through each consecutive pair of `path` gives.
+        ///
+        /// Fails if that is less than `amount_out_min`, or once `deadline` has passed.
+        #[weight = 10_000 + T::DbWeight::get().reads_writes(2 * path.len() as Weight, 3 * path.len() as Weight)]
+        #[transactional]
+        pub fn swap_exact_tokens_for_tokens(
+            origin,
+            amount_in: <T as asset::Trait>::Balance,
+            amount_out_min: <T as asset::Trait>::Balance,
+            path: Vec<T::AssetId>,
+            deadline: T::Moment
+        ) -> dispatch::DispatchResult {
+            let sender = ensure_signed(origin)?;
+            Self::ensure_deadline(deadline)?;
+            let amounts = Self::get_amounts_out(amount_in, &path)?;
+            ensure!(
+                amounts[amounts.len() - 1] >= amount_out_min,
+                Error::<T>::InsufficientOutputAmount
+            );
+
+            Self::do_swap(&sender, &amounts, &path)
+        }
+
+        /// Swap as little of the first asset of `path` as swapping through each consecutive pair
+        /// of `path` takes for exactly `amount_out` of its last asset.
+        ///
+        /// Fails if that is more than `amount_in_max`, or once `deadline` has passed.
+        #[weight = 10_000 + T::DbWeight::get().reads_writes(2 * path.len() as Weight, 3 * path.len() as Weight)]
+        #[transactional]
+        pub fn swap_tokens_for_exact_tokens(
+            origin,
+            amount_out: <T as asset::Trait>::Balance,
+            amount_in_max: <T as asset::Trait>::Balance,
+            path: Vec<T::AssetId>,
+            deadline: T::Moment
+        ) -> dispatch::DispatchResult {
+            let sender = ensure_signed(origin)?;
+            Self::ensure_deadline(deadline)?;
+            let amounts = Self::get_amounts_in(amount_out, &path)?;
+            ensure!(amounts[0] <= amount_in_max, Error::<T>::ExcessiveInputAmount);
+
+            Self::do_swap(&sender, &amounts, &path)
+        }
+
+        /// Set the account that receives the protocol fee, or turn the fee off with `None`.
+        ///
+        /// While the fee is on, every mint and burn of liquidity first mints liquidity tokens to
+        /// `fee_to` worth 1/6th of the growth of sqrt(k) of the pair since its last mint or burn.
+        ///
+        /// The dispatch origin for this call must be `FeeToOrigin`.
+        #[weight = 10_000 + T::DbWeight::get().writes(1)]
+        pub fn set_fee_to(origin, fee_to: Option<T::AccountId>) -> dispatch::DispatchResult {
+            T::FeeToOrigin::ensure_origin(origin)?;
+            <FeeTo<T>>::set(fee_to.clone());
+            Self::deposit_event(RawEvent::FeeToSet(fee_to));
             Ok(())
         }
 
-        #[weight = 10_000 + T::DbWeight::get().reads_writes(1,1)]
-        pub fn swap(origin, from: T::AssetId, amount: <T as asset::Trait>::Balance, to: T::AssetId) -> dispatch::DispatchResult {
+        /// Create a pool of `kind` from `amounts` of `assets` of the caller.
+        ///
+        /// A `ConstantProduct` pool is a new pair of two assets, created as by `mint_liquidity`.
+        /// A `StableSwap` pool holds two to `MaxStableAssets` assets, and mints its invariant in
+        /// liquidity tokens of a new asset to the caller. A `Weighted` pool holds two to
+        /// `MaxWeightedAssets` assets, and mints a fixed initial supply of liquidity tokens of a
+        /// new asset to the caller, less a small part that stays locked. Fails once `deadline`
+        /// has passed.
+        #[weight = 10_000
+            + stable_math_weight(assets.len() as Weight, 1)
+            + T::DbWeight::get().reads_writes(2 + assets.len() as Weight, 4 + assets.len() as Weight)]
+        pub fn create_pool(
+            origin,
+            kind: PoolKind,
+            assets: Vec<T::AssetId>,
+            amounts: Vec<<T as asset::Trait>::Balance>,
+            deadline: T::Moment
+        ) -> dispatch::DispatchResult {
+            match kind {
+                PoolKind::ConstantProduct => {
+                    ensure!(assets.len() == 2 && amounts.len() == 2, Error::<T>::InvalidPoolAssets);
+                    ensure!(Self::lpt((assets[0], assets[1])).is_none(), Error::<T>::PairExists);
+                    Self::mint_liquidity(
+                        origin, assets[0], amounts[0], assets[1], amounts[1], amounts[0], amounts[1], deadline,
+                    )
+                }
+                PoolKind::StableSwap { amplification } => {
+                    let sender = ensure_signed(origin)?;
+                    Self::ensure_deadline(deadline)?;
+                    Self::create_stable_pool(&sender, assets, amounts, amplification)
+                }
+                PoolKind::Weighted { weights } => {
+                    let sender = ensure_signed(origin)?;
+                    Self::ensure_deadline(deadline)?;
+                    Self::create_weighted_pool(&sender, assets, amounts, weights)
+                }
+            }
+        }
+
+        /// Deposit `amounts` of the assets of StableSwap pool `lpt`, in the order of its assets,
+        /// for its liquidity tokens.
+        ///
+        /// The part of the deposit out of proportion with the pool pays the swap fee, as if it was
+        /// swapped in. Fails if fewer than `min_liquidity` tokens would be minted, or once
+        /// `deadline` has passed.
+        #[weight = 10_000
+            + stable_math_weight(amounts.len() as Weight, 3)
+            + T::DbWeight::get().reads_writes(2 + amounts.len() as Weight, 3 + amounts.len() as Weight)]
+        pub fn add_stable_liquidity(
+            origin,
+            lpt: T::AssetId,
+            amounts: Vec<<T as asset::Trait>::Balance>,
+            min_liquidity: <T as asset::Trait>::Balance,
+            deadline: T::Moment
+        ) -> dispatch::DispatchResult {
             let sender = ensure_signed(origin)?;
+            Self::ensure_deadline(deadline)?;
+            let mut pool = Self::stable_pool(lpt).ok_or(Error::<T>::UnknownPool)?;
+            ensure!(amounts.len() == pool.assets.len(), Error::<T>::InvalidPoolAssets);
+            let amplification = Self::amplification(&pool);
+            let total_supply = asset::Module::<T>::total_supply(lpt);
+            let balances = pool
+                .balances
+                .iter()
+                .zip(&amounts)
+                .map(|(balance, amount)| balance.checked_add(amount))
+                .collect::<Option<Vec<_>>>()
+                .ok_or(Error::<T>::StorageOverflow)?;
+            let d0 = Self::stable_invariant(&pool.balances, amplification)?;
+            let d1 = Self::stable_invariant(&balances, amplification)?;
+            ensure!(d1 > d0, Error::<T>::InsufficientLiquidityMinted);
+            let liquidity = if total_supply.is_zero() || d0.is_zero() {
+                d1
+            } else {
+                let n = pool.assets.len() as u32;
+                let fee = Permill::from_parts(T::SwapFee::get().deconstruct() * n / (4 * (n - 1)));
+                let adjusted = pool
+                    .balances
+                    .iter()
+                    .zip(&balances)
+                    .map(|(&old, &new)| {
+                        let ideal = Self::quote(d1, d0, old).ok_or(Error::<T>::InvariantFailed)?;
+                        let difference = if ideal > new { ideal - new } else { new - ideal };
+                        Ok(new.saturating_sub(fee.mul_ceil(difference)))
+                    })
+                    .collect::<Result<Vec<_>, Error<T>>>()?;
+                let d2 = Self::stable_invariant(&adjusted, amplification)?;
+                Self::quote(total_supply, d0, d2.saturating_sub(d0)).unwrap_or_else(Zero::zero)
+            };
+            ensure!(
+                !liquidity.is_zero() && liquidity >= min_liquidity,
+                Error::<T>::InsufficientLiquidityMinted
+            );
+            for (&id, &amount) in pool.assets.iter().zip(&amounts) {
+                asset::Module::<T>::ensure_can_withdraw(id, &sender, amount)?;
+            }
+
+            for (&id, &amount) in pool.assets.iter().zip(&amounts) {
+                asset::Module::<T>::withdraw(id, &sender, amount)?;
+            }
+            asset::Module::<T>::deposit(lpt, &sender, liquidity)?;
+            pool.balances = balances;
+            <StablePools<T>>::insert(lpt, pool);
+            Self::deposit_event(RawEvent::StableLiquidityAdded(sender, lpt, liquidity));
+            Ok(())
+        }
+
+        /// Burn `amount` of liquidity token `lpt` of a StableSwap pool for the pro-rata share of
+        /// each of its assets, which must be at least the matching entry of `min_amounts`.
+        ///
+        /// Fails once `deadline` has passed.
+        #[weight = 10_000 + T::DbWeight::get().reads_writes(2 + min_amounts.len() as Weight, 2 + min_amounts.len() as Weight)]
+        #[transactional]
+        pub fn remove_stable_liquidity(
+            origin,
+            lpt: T::AssetId,
+            amount: <T as asset::Trait>::Balance,
+            min_amounts: Vec<<T as asset::Trait>::Balance>,
+            deadline: T::Moment
+        ) -> dispatch::DispatchResult {
+            let sender = ensure_signed(origin)?;
+            Self::ensure_deadline(deadline)?;
+            let mut pool = Self::stable_pool(lpt).ok_or(Error::<T>::UnknownPool)?;
+            ensure!(min_amounts.len() == pool.assets.len(), Error::<T>::InvalidPoolAssets);
+            let total_supply = asset::Module::<T>::total_supply(lpt);
+            ensure!(
+                !amount.is_zero() && amount <= total_supply,
+                Error::<T>::InsufficientLiquidityBurned
+            );
+            let rewards = Self::pro_rata(amount, total_supply, &pool.balances);
+            for (reward, min) in rewards.iter().zip(&min_amounts) {
+                ensure!(reward >= min, Error::<T>::InsufficientOutputAmount);
+            }
+
+            asset::Module::<T>::withdraw(lpt, &sender, amount)?;
+            for ((&id, balance), &reward) in pool.assets.iter().zip(pool.balances.iter_mut()).zip(&rewards) {
+                asset::Module::<T>::deposit(id, &sender, reward)?;
+                *balance -= reward;
+            }
+            <StablePools<T>>::insert(lpt, pool);
+            Self::deposit_event(RawEvent::StableLiquidityRemoved(sender, lpt, amount));
+            Ok(())
+        }
+
+        /// Swap exactly `amount_in` of asset `from` for as much of asset `to` as StableSwap pool
+        /// `lpt` gives, after the swap fee.
+        ///
+        /// Fails if that is less than `amount_out_min`, or once `deadline` has passed.
+        #[weight = 10_000
+            + stable_math_weight(T::MaxStableAssets::get() as Weight, 2)
+            + T::DbWeight::get().reads_writes(3, 4)]
+        pub fn swap_stable(
+            origin,
+            lpt: T::AssetId,
+            from: T::AssetId,
+            amount_in: <T as asset::Trait>::Balance,
+            to: T::AssetId,
+            amount_out_min: <T as asset::Trait>::Balance,
+            deadline: T::Moment
+        ) -> dispatch::DispatchResult {
+            let sender = ensure_signed(origin)?;
+            Self::ensure_deadline(deadline)?;
+            let mut pool = Self::stable_pool(lpt).ok_or(Error::<T>::UnknownPool)?;
+            let (i, j) = Self::asset_indices(&pool.assets, from, to)?;
+            let amount_out = Self::stable_amount_out(&pool, i, j, amount_in)?;
+            ensure!(
+                !amount_out.is_zero() && amount_out >= amount_out_min,
+                Error::<T>::InsufficientOutputAmount
+            );
+            asset::Module::<T>::ensure_can_withdraw(from, &sender, amount_in)?;
+
+            asset::Module::<T>::withdraw(from, &sender, amount_in)?;
+            asset::Module::<T>::deposit(to, &sender, amount_out)?;
+            pool.balances[i] += amount_in;
+            pool.balances[j] -= amount_out;
+            <StablePools<T>>::insert(lpt, pool);
+            Self::deposit_event(RawEvent::Swap(sender, from, amount_in, to, amount_out));
+            Ok(())
+        }
+
+        /// Ramp the amplification of StableSwap pool `lpt` linearly from its current value now
+        /// to `future_amplification` at `future_time`.
+        ///
+        /// The ramp must last at least `MinRampTime`, start at least `MinRampTime` after the last
+        /// one started, and at most multiply or divide the amplification by ten.
+        ///
+        /// The dispatch origin for this call must be _Root_.
+        #[weight = 10_000 + T::DbWeight::get().reads_writes(1, 1)]
+        pub fn ramp_amplification(
+            origin,
+            lpt: T::AssetId,
+            future_amplification: u32,
+            future_time: T::Moment
+        ) -> dispatch::DispatchResult {
+            ensure_root(origin)?;
+            let mut pool = Self::stable_pool(lpt).ok_or(Error::<T>::UnknownPool)?;
+            let now = <timestamp::Module<T>>::get();
+            let min_ramp_time = T::MinRampTime::get();
+            ensure!(
+                now >= pool.initial_amplification_time.saturating_add(min_ramp_time),
+                Error::<T>::RampTooFast
+            );
+            ensure!(future_time >= now.saturating_add(min_ramp_time), Error::<T>::RampTooFast);
+            let amplification = Self::amplification(&pool);
+            let (current, future) = (amplification as u64, future_amplification as u64);
+            ensure!(
+                future_amplification > 0
+                    && future_amplification <= T::MaxAmplification::get()
+                    && future <= current * 10
+                    && future * 10 >= current,
+                Error::<T>::InvalidAmplification
+            );
+
+            pool.initial_amplification = amplification;
+            pool.future_amplification = future_amplification;
+            pool.initial_amplification_time = now;
+            pool.future_amplification_time = future_time;
+            <StablePools<T>>::insert(lpt, pool);
+            Self::deposit_event(RawEvent::AmplificationRamped(lpt, amplification, future_amplification, future_time));
+            Ok(())
+        }
+
+        /// Stop the amplification ramp of StableSwap pool `lpt` at its current value.
+        ///
+        /// The dispatch origin for this call must be _Root_.
+        #[weight = 10_000 + T::DbWeight::get().reads_writes(1, 1)]
+        pub fn stop_ramp_amplification(origin, lpt: T::AssetId) -> dispatch::DispatchResult {
+            ensure_root(origin)?;
+            let mut pool = Self::stable_pool(lpt).ok_or(Error::<T>::UnknownPool)?;
+            let now = <timestamp::Module<T>>::get();
+            let amplification = Self::amplification(&pool);
+
+            pool.initial_amplification = amplification;
+            pool.future_amplification = amplification;
+            pool.initial_amplification_time = now;
+            pool.future_amplification_time = now;
+            <StablePools<T>>::insert(lpt, pool);
+            Self::deposit_event(RawEvent::AmplificationRampStopped(lpt, amplification));
+            Ok(())
+        }
+
+        /// Mint `liquidity` tokens of weighted pool `lpt` for the pro-rata share of each of its
+        /// assets, which must be at most the matching entry of `max_amounts`.
+        ///
+        /// Fails once `deadline` has passed.
+        #[weight = 10_000 + T::DbWeight::get().reads_writes(2 + max_amounts.len() as Weight, 2 + max_amounts.len() as Weight)]
+        pub fn add_weighted_liquidity(
+            origin,
+            lpt: T::AssetId,
+            liquidity: <T as asset::Trait>::Balance,
+            max_amounts: Vec<<T as asset::Trait>::Balance>,
+            deadline: T::Moment
+        ) -> dispatch::DispatchResult {
+            let sender = ensure_signed(origin)?;
+            Self::ensure_deadline(deadline)?;
+            let mut pool = Self::pool(lpt).ok_or(Error::<T>::UnknownPool)?;
+            ensure!(max_amounts.len() == pool.assets.len(), Error::<T>::InvalidPoolAssets);
+            let total_supply = asset::Module::<T>::total_supply(lpt);
+            ensure!(
+                !liquidity.is_zero() && !total_supply.is_zero(),
+                Error::<T>::InsufficientLiquidityMinted
+            );
+            // Round the deposits up, so minting never dilutes the pool.
+            let amounts = pool
+                .balances
+                .iter()
+                .map(|&balance| {
+                    let amount = U256::from(liquidity.saturated_into::<u128>())
+                        * U256::from(balance.saturated_into::<u128>());
+                    let supply = U256::from(total_supply.saturated_into::<u128>());
+                    let amount = (amount + supply - U256::one()) / supply;
+                    ensure!(amount <= U256::from(u128::max_value()), Error::<T>::StorageOverflow);
+                    Ok(amount.low_u128().saturated_into())
+                })
+                .collect::<Result<Vec<<T as asset::Trait>::Balance>, Error<T>>>()?;
+            for ((&id, &amount), max) in pool.assets.iter().zip(&amounts).zip(&max_amounts) {
+                ensure!(amount <= *max, Error::<T>::ExcessiveInputAmount);
+                asset::Module::<T>::ensure_can_withdraw(id, &sender, amount)?;
+            }
+
+            for ((&id, balance), &amount) in pool.assets.iter().zip(pool.balances.iter_mut()).zip(&amounts) {
+                asset::Module::<T>::withdraw(id, &sender, amount)?;
+                *balance += amount;
+            }
+            asset::Module::<T>::deposit(lpt, &sender, liquidity)?;
+            <Pools<T>>::insert(lpt, pool);
+            Self::deposit_event(RawEvent::WeightedLiquidityAdded(sender, lpt, liquidity));
+            Ok(())
+        }
+
+        /// Burn `amount` of liquidity token `lpt` of a weighted pool for the pro-rata share of
+        /// each of its assets, which must be at least the matching entry of `min_amounts`.
+        ///
+        /// Fails once `deadline` has passed.
+        #[weight = 10_000 + T::DbWeight::get().reads_writes(2 + min_amounts.len() as Weight, 2 + min_amounts.len() as Weight)]
+        #[transactional]
+        pub fn remove_weighted_liquidity(
+            origin,
+            lpt: T::AssetId,
+            amount: <T as asset::Trait>::Balance,
+            min_amounts: Vec<<T as asset::Trait>::Balance>,
+            deadline: T::Moment
+        ) -> dispatch::DispatchResult {
+            let sender = ensure_signed(origin)?;
+            Self::ensure_deadline(deadline)?;
+            let mut pool = Self::pool(lpt).ok_or(Error::<T>::UnknownPool)?;
+            ensure!(min_amounts.len() == pool.assets.len(), Error::<T>::InvalidPoolAssets);
+            let total_supply = asset::Module::<T>::total_supply(lpt);
+            ensure!(
+                !amount.is_zero() && amount <= total_supply,
+                Error::<T>::InsufficientLiquidityBurned
+            );
+            let rewards = Self::pro_rata(amount, total_supply, &pool.balances);
+            for (reward, min) in rewards.iter().zip(&min_amounts) {
+                ensure!(reward >= min, Error::<T>::InsufficientOutputAmount);
+            }
+
+            asset::Module::<T>::withdraw(lpt, &sender, amount)?;
+            for ((&id, balance), &reward) in pool.assets.iter().zip(pool.balances.iter_mut()).zip(&rewards) {
+                asset::Module::<T>::deposit(id, &sender, reward)?;
+                *balance -= reward;
+            }
+            <Pools<T>>::insert(lpt, pool);
+            Self::deposit_event(RawEvent::WeightedLiquidityRemoved(sender, lpt, amount));
+            Ok(())
+        }
+
+        /// Deposit exactly `amount_in` of a single asset of weighted pool `lpt` for its liquidity
+        /// tokens.
+        ///
+        /// The part of the deposit beyond the weight of the asset pays the swap fee, as if it was
+        /// swapped for the other assets. Fails if fewer than `min_liquidity` tokens would be
+        /// minted, if `amount_in` is more than half the balance of the asset, or once `deadline`
+        /// has passed.
+        #[weight = 10_000 + weighted_math_weight() + T::DbWeight::get().reads_writes(3, 3)]
+        pub fn add_single_weighted_liquidity(
+            origin,
+            lpt: T::AssetId,
+            asset: T::AssetId,
+            amount_in: <T as asset::Trait>::Balance,
+            min_liquidity: <T as asset::Trait>::Balance,
+            deadline: T::Moment
+        ) -> dispatch::DispatchResult {
+            let sender = ensure_signed(origin)?;
+            Self::ensure_deadline(deadline)?;
+            let mut pool = Self::pool(lpt).ok_or(Error::<T>::UnknownPool)?;
+            let i = pool.assets.iter().position(|&id| id == asset).ok_or(Error::<T>::UnknownPair)?;
+            ensure!(
+                amount_in <= pool.balances[i] / <T as asset::Trait>::Balance::from(2u32),
+                Error::<T>::TradeTooLarge
+            );
+            let liquidity: <T as asset::Trait>::Balance = weighted_math::pool_out_given_single_in(
+                pool.balances[i].saturated_into(),
+                pool.weights[i],
+                asset::Module::<T>::total_supply(lpt).saturated_into(),
+                amount_in.saturated_into(),
+                T::SwapFee::get(),
+            )
+            .ok_or(Error::<T>::InvariantFailed)?
+            .saturated_into();
+            ensure!(
+                !liquidity.is_zero() && liquidity >= min_liquidity,
+                Error::<T>::InsufficientLiquidityMinted
+            );
+            asset::Module::<T>::ensure_can_withdraw(asset, &sender, amount_in)?;
+
+            asset::Module::<T>::withdraw(asset, &sender, amount_in)?;
+            asset::Module::<T>::deposit(lpt, &sender, liquidity)?;
+            pool.balances[i] += amount_in;
+            <Pools<T>>::insert(lpt, pool);
+            Self::deposit_event(RawEvent::WeightedLiquidityAdded(sender, lpt, liquidity));
+            Ok(())
+        }
+
+        /// Burn exactly `amount` of liquidity token `lpt` of a weighted pool for a single asset
+        /// of it.
+        ///
+        /// The part of the withdrawal beyond the weight of the asset pays the swap fee, as if the
+        /// other assets were swapped for it. Fails if that is less than `min_amount_out` or more
+        /// than a third of the balance of the asset, or once `deadline` has passed.
+        #[weight = 10_000 + weighted_math_weight() + T::DbWeight::get().reads_writes(3, 3)]
+        pub fn remove_single_weighted_liquidity(
+            origin,
+            lpt: T::AssetId,
+            amount: <T as asset::Trait>::Balance,
+            asset: T::AssetId,
+            min_amount_out: <T as asset::Trait>::Balance,
+            deadline: T::Moment
+        ) -> dispatch::DispatchResult {
+            let sender = ensure_signed(origin)?;
+            Self::ensure_deadline(deadline)?;
+            let mut pool = Self::pool(lpt).ok_or(Error::<T>::UnknownPool)?;
+            let i = pool.assets.iter().position(|&id| id == asset).ok_or(Error::<T>::UnknownPair)?;
+            let total_supply = asset::Module::<T>::total_supply(lpt);
+            ensure!(
+                !amount.is_zero() && amount < total_supply,
+                Error::<T>::InsufficientLiquidityBurned
+            );
+            let amount_out: <T as asset::Trait>::Balance = weighted_math::single_out_given_pool_in(
+                pool.balances[i].saturated_into(),
+                pool.weights[i],
+                total_supply.saturated_into(),
+                amount.saturated_into(),
+                T::SwapFee::get(),
+            )
+            .ok_or(Error::<T>::InvariantFailed)?
+            .saturated_into();
+            ensure!(
+                amount_out <= pool.balances[i] / <T as asset::Trait>::Balance::from(3u32),
+                Error::<T>::TradeTooLarge
+            );
+            ensure!(
+                !amount_out.is_zero() && amount_out >= min_amount_out,
+                Error::<T>::InsufficientOutputAmount
+            );
+
+            asset::Module::<T>::withdraw(lpt, &sender, amount)?;
+            asset::Module::<T>::deposit(asset, &sender, amount_out)?;
+            pool.balances[i] -= amount_out;
+            <Pools<T>>::insert(lpt, pool);
+            Self::deposit_event(RawEvent::WeightedLiquidityRemoved(sender, lpt, amount));
+            Ok(())
+        }
+
+        /// Swap exactly `amount_in` of asset `from` for as much of asset `to` as weighted pool
+        /// `lpt` gives at the weighted product price, after the swap fee.
+        ///
+        /// Fails if that is less than `amount_out_min`, if the trade is too large for the pool, or
+        /// once `deadline` has passed.
+        #[weight = 10_000 + weighted_math_weight() + T::DbWeight::get().reads_writes(3, 4)]
+        pub fn swap_weighted(
+            origin,
+            lpt: T::AssetId,
+            from: T::AssetId,
+            amount_in: <T as asset::Trait>::Balance,
+            to: T::AssetId,
+            amount_out_min: <T as asset::Trait>::Balance,
+            deadline: T::Moment
+        ) -> dispatch::DispatchResult {
+            let sender = ensure_signed(origin)?;
+            Self::ensure_deadline(deadline)?;
+            let mut pool = Self::pool(lpt).ok_or(Error::<T>::UnknownPool)?;
+            let (i, j) = Self::asset_indices(&pool.assets, from, to)?;
+            let amount_out = Self::weighted_amount_out(&pool, i, j, amount_in)?;
+            ensure!(
+                !amount_out.is_zero() && amount_out >= amount_out_min,
+                Error::<T>::InsufficientOutputAmount
+            );
+            asset::Module::<T>::ensure_can_withdraw(from, &sender, amount_in)?;
+
+            asset::Module::<T>::withdraw(from, &sender, amount_in)?;
+            asset::Module::<T>::deposit(to, &sender, amount_out)?;
+            pool.balances[i] += amount_in;
+            pool.balances[j] -= amount_out;
+            <Pools<T>>::insert(lpt, pool);
+            Self::deposit_event(RawEvent::Swap(sender, from, amount_in, to, amount_out));
+            Ok(())
+        }
+
+        /// Buy or sell `amount` of asset `base` for asset `quote` at `price` in `quote` per unit
+        /// of `base` or better.
+        ///
+        /// The order first fills against the pair of the two assets and the resting orders of the
+        /// other side of their book, whichever gives the better price at each step, resting
+        /// orders at their own price and in the order they were placed. Whatever is left rests on
+        /// the book, with the `quote` it may pay for a buy, or the `base` it may sell, reserved
+        /// until it fills or is cancelled, along with `OrderDeposit` of the native currency.
+        ///
+        /// The pair and every resting order fill together or not at all: if any leg fails, the
+        /// whole call is undone. `base` must have the smaller id of the two. Fails once `deadline`
+        /// has passed.
+        #[weight = 10_000 + T::DbWeight::get().reads_writes(4 + 2 * T::MaxOrders::get() as Weight, 4 + 2 * T::MaxOrders::get() as Weight)]
+        #[transactional]
+        pub fn place_limit_order(
+            origin,
+            base: T::AssetId,
+            quote: T::AssetId,
+            side: OrderSide,
+            price: UQ64x64,
+            amount: <T as asset::Trait>::Balance,
+            deadline: T::Moment
+        ) -> dispatch::DispatchResult {
+            let sender = ensure_signed(origin)?;
+            Self::ensure_deadline(deadline)?;
+            ensure!(base != quote, Error::<T>::IdenticalIdentifier);
+            ensure!(base < quote, Error::<T>::UnsortedAssets);
+            ensure!(!price.is_zero() && !amount.is_zero(), Error::<T>::InvalidOrder);
+            let matching = Self::match_order(base, quote, side, price, amount)?;
+            let mut book = Self::order_book_side((base, quote), side);
+            ensure!(
+                matching.remaining.is_zero() || book.len() < T::MaxOrders::get() as usize,
+                Error::<T>::OrderBookFull
+            );
+            let lock: <T as asset::Trait>::Balance = match side {
+                OrderSide::Buy => price
+                    .checked_mul_ceil(matching.remaining.saturated_into())
+                    .ok_or(Error::<T>::StorageOverflow)?
+                    .saturated_into(),
+                OrderSide::Sell => matching.remaining,
+            };
+            let (pay, receive) = match side {
+                OrderSide::Buy => (quote, base),
+                OrderSide::Sell => (base, quote),
+            };
+            let mut total = lock;
+            if let Some((_, amount0, amount1, _)) = matching.pair {
+                total = total.saturating_add(if side == OrderSide::Buy { amount1 } else { amount0 });
+            }
+            for &(_, amount0, amount1) in matching.fills.iter() {
+                total = total.saturating_add(if side == OrderSide::Buy { amount1 } else { amount0 });
+            }
+            asset::Module::<T>::ensure_can_withdraw(pay, &sender, total)?;
+
+            if let Some((lpt, amount0, amount1, (reserve0, reserve1))) = matching.pair {
+                let (amount_in, amount_out) = match side {
+                    OrderSide::Buy => (amount1, amount0),
+                    OrderSide::Sell => (amount0, amount1),
+                };
+                // Accumulate the price that held until now before moving it
+                Self::_update(&lpt)?;
+                asset::Module::<T>::withdraw(pay, &sender, amount_in)?;
+                asset::Module::<T>::deposit(receive, &sender, amount_out)?;
+                <Pairs<T>>::mutate(lpt, |info| {
+                    if let Some(info) = info {
+                        info.reserve0 = reserve0;
+                        info.reserve1 = reserve1;
+                    }
+                });
+                Self::deposit_event(RawEvent::Swap(sender.clone(), pay, amount_in, receive, amount_out));
+            }
+            let mut filled = 0;
+            for (id, amount0, amount1) in matching.fills {
+                let mut order = Self::order(id).ok_or(Error::<T>::UnknownOrder)?;
+                match side {
+                    // The maker sells `base` it reserved for `quote` from the taker.
+                    OrderSide::Buy => {
+                        let unpaid = asset::Module::<T>::repatriate_reserved(base, &order.owner, &sender, amount0, BalanceStatus::Free)?;
+                        ensure!(unpaid.is_zero(), Error::<T>::InsufficientReserve);
+                        <asset::Module<T> as MultiCurrency<_>>::transfer(quote, &sender, &order.owner, amount1)?;
+                        order.locked = order.locked.saturating_sub(amount0);
+                    }
+                    // The maker buys `base` from the taker with `quote` it reserved.
+                    OrderSide::Sell => {
+                        let unpaid = asset::Module::<T>::repatriate_reserved(quote, &order.owner, &sender, amount1, BalanceStatus::Free)?;
+                        ensure!(unpaid.is_zero(), Error::<T>::InsufficientReserve);
+                        <asset::Module<T> as MultiCurrency<_>>::transfer(base, &sender, &order.owner, amount0)?;
+                        order.locked = order.locked.saturating_sub(amount1);
+                    }
+                }
+                order.amount -= amount0;
+                Self::deposit_event(RawEvent::OrderFilled(id, sender.clone(), amount0, amount1));
+                if order.amount.is_zero() {
+                    // Release what rounding left reserved.
+                    let locked_asset = if order.side == OrderSide::Buy { quote } else { base };
+                    asset::Module::<T>::unreserve(locked_asset, &order.owner, order.locked);
+                    <T as asset::Trait>::Currency::unreserve(&order.owner, order.deposit);
+                    <Orders<T>>::remove(id);
+                    filled += 1;
+                } else {
+                    <Orders<T>>::insert(id, order);
+                }
+            }
+            if filled > 0 {
+                <OrderBook<T>>::mutate((base, quote), side.opposite(), |entries| {
+                    entries.drain(..filled);
+                });
+            }
+
+            if !matching.remaining.is_zero() {
+                asset::Module::<T>::reserve(pay, &sender, lock)?;
+                let deposit = T::OrderDeposit::get();
+                <T as asset::Trait>::Currency::reserve(&sender, deposit)?;
+                let id = Self::next_order_id();
+                NextOrderId::put(id.wrapping_add(1));
+                let position = book
+                    .iter()
+                    .position(|&(other, _)| match side {
+                        OrderSide::Buy => other < price,
+                        OrderSide::Sell => other > price,
+                    })
+                    .unwrap_or_else(|| book.len());
+                book.insert(position, (price, id));
+                <OrderBook<T>>::insert((base, quote), side, book);
+                <Orders<T>>::insert(
+                    id,
+                    Order {
+                        owner: sender.clone(),
+                        base,
+                        quote,
+                        side,
+                        price,
+                        amount: matching.remaining,
+                        locked: lock,
+                        deposit,
+                    },
+                );
+                Self::deposit_event(RawEvent::OrderPlaced(id, sender, matching.remaining));
+            }
+            Ok(())
+        }
+
+        /// Cancel resting limit order `id` of the caller, releasing what it has reserved and its
+        /// deposit.
+        #[weight = 10_000 + T::DbWeight::get().reads_writes(3, 3)]
+        pub fn cancel_order(origin, id: OrderId) -> dispatch::DispatchResult {
+            let sender = ensure_signed(origin)?;
+            let order = Self::order(id).ok_or(Error::<T>::UnknownOrder)?;
+            ensure!(order.owner == sender, Error::<T>::NotOrderOwner);
+
+            let locked_asset = match order.side {
+                OrderSide::Buy => order.quote,
+                OrderSide::Sell => order.base,
+            };
+            asset::Module::<T>::unreserve(locked_asset, &sender, order.locked);
+            <T as asset::Trait>::Currency::unreserve(&sender, order.deposit);
+            <OrderBook<T>>::mutate((order.base, order.quote), order.side, |entries| {
+                entries.retain(|&(_, other)| other != id);
+            });
+            <Orders<T>>::remove(id);
+            Self::deposit_event(RawEvent::OrderCancelled(id, sender));
             Ok(())
         }
     }
 }
 // The main implementation block for the module.
 impl<T: Trait> Module<T> {
-    // TODO: add fee option for pair creators
-    // if fee is on, mint liquidity equivalent to 1/6th of the growth in sqrt(k)
-    pub fn _mint_fee(
-        reserve0: <T as asset::Trait>::Balance,
-        reserve1: <T as asset::Trait>::Balance,
-    ) -> bool {
-        let rootK: <T as asset::Trait>::Balance = math::sqrt::<T>(
-            reserve0
-                .checked_mul(&reserve1)
-                .expect("Multiplicaiton overflow"),
+    /// The liquidity token of the pair of `token0` and `token1`, given in either order.
+    pub fn lpt((token0, token1): (T::AssetId, T::AssetId)) -> Option<T::AssetId> {
+        <LPTokens<T>>::get(Self::sorted(token0, token1))
+    }
+
+    /// The reserves of the pair of liquidity token `lpt`, in the order of its assets.
+    ///
+    /// Both are zero if there is no such pair.
+    pub fn reserves(
+        lpt: T::AssetId,
+    ) -> (<T as asset::Trait>::Balance, <T as asset::Trait>::Balance) {
+        Self::pair(lpt)
+            .map(|info| (info.reserve0, info.reserve1))
+            .unwrap_or_default()
+    }
+
+    /// `a` and `b` in ascending order.
+    fn sorted(a: T::AssetId, b: T::AssetId) -> (T::AssetId, T::AssetId) {
+        if a < b {
+            (a, b)
+        } else {
+            (b, a)
+        }
+    }
+
+    /// The amount of `to` that `amount` of `from` is worth at the reserve price of their pair.
+    ///
+    /// Returns `None` if the pair does not exist or one of its reserves is empty.
+    pub fn reserve_price(
+        from: T::AssetId,
+        amount: <T as asset::Trait>::Balance,
+        to: T::AssetId,
+    ) -> Option<<T as asset::Trait>::Balance> {
+        let (_, reserve_in, reserve_out, _) = Self::pair_reserves(from, to)?;
+        Self::quote(amount, reserve_in, reserve_out)
+    }
+
+    /// The amount of `to` that `amount` of `from` is worth at the time-weighted average price of
+    /// their pair over at least `window`, as given by `consult`.
+    ///
+    /// Returns `None` if the pair does not exist or has no observation that old.
+    pub fn average_price(
+        from: T::AssetId,
+        amount: <T as asset::Trait>::Balance,
+        to: T::AssetId,
+        window: T::Moment,
+    ) -> Option<<T as asset::Trait>::Balance> {
+        let lpt = Self::lpt((from, to))?;
+        let (price0, price1) = Self::consult(lpt, window)?;
+        let price = if from < to { price0 } else { price1 };
+        price
+            .checked_mul_floor(amount.saturated_into())
+            .map(|amount| amount.saturated_into())
+    }
+
+    /// The amount of the other asset that `amount` is worth at the ratio of `reserve_in` to
+    /// `reserve_out`.
+    ///
+    /// Returns `None` if either reserve is empty.
+    pub fn quote(
+        amount: <T as asset::Trait>::Balance,
+        reserve_in: <T as asset::Trait>::Balance,
+        reserve_out: <T as asset::Trait>::Balance,
+    ) -> Option<<T as asset::Trait>::Balance> {
+        if reserve_in.is_zero() || reserve_out.is_zero() {
+            return None;
+        }
+        multiply_by_rational(
+            amount.saturated_into(),
+            reserve_out.saturated_into(),
+            reserve_in.saturated_into(),
+        )
+        .ok()
+        .map(|amount: u128| amount.saturated_into())
+    }
+
+    /// The largest deposit of at most `desired` that matches the ratio of `reserves`, if it is
+    /// at least `min`.
+    fn optimal_amounts(
+        desired: (<T as asset::Trait>::Balance, <T as asset::Trait>::Balance),
+        min: (<T as asset::Trait>::Balance, <T as asset::Trait>::Balance),
+        reserves: (<T as asset::Trait>::Balance, <T as asset::Trait>::Balance),
+    ) -> Result<(<T as asset::Trait>::Balance, <T as asset::Trait>::Balance), Error<T>> {
+        let amount1_optimal = match Self::quote(desired.0, reserves.0, reserves.1) {
+            Some(amount) => amount,
+            // An empty pair takes any ratio.
+            None => return Ok(desired),
+        };
+        if amount1_optimal <= desired.1 {
+            ensure!(amount1_optimal >= min.1, Error::<T>::InsufficientAmount1);
+            Ok((desired.0, amount1_optimal))
+        } else {
+            let amount0_optimal = Self::quote(desired.1, reserves.1, reserves.0)
+                .ok_or(Error::<T>::InsufficientLiquidity)?;
+            ensure!(amount0_optimal >= min.0, Error::<T>::InsufficientAmount0);
+            Ok((amount0_optimal, desired.1))
+        }
+    }
+
+    /// Ensure that `deadline` has not passed.
+    fn ensure_deadline(deadline: T::Moment) -> dispatch::DispatchResult {
+        ensure!(<timestamp::Module<T>>::get() <= deadline, Error::<T>::Expired);
+        Ok(())
+    }
+
+    /// The amount of the output asset that `amount_in` buys from reserves of `reserve_in` and
+    /// `reserve_out` at the constant product price, after the swap fee.
+    ///
+    /// Returns `None` if either reserve is empty.
+    pub fn get_amount_out(
+        amount_in: <T as asset::Trait>::Balance,
+        reserve_in: <T as asset::Trait>::Balance,
+        reserve_out: <T as asset::Trait>::Balance,
+    ) -> Option<<T as asset::Trait>::Balance> {
+        if reserve_in.is_zero() || reserve_out.is_zero() {
+            return None;
+        }
+        let amount_in_with_fee = amount_in - T::SwapFee::get().mul_ceil(amount_in);
+        multiply_by_rational(
+            amount_in_with_fee.saturated_into(),
+            reserve_out.saturated_into(),
+            reserve_in.checked_add(&amount_in_with_fee)?.saturated_into(),
+        )
+        .ok()
+        .map(|amount: u128| amount.saturated_into())
+    }
+
+    /// The amount of the input asset it takes to buy `amount_out` from reserves of `reserve_in`
+    /// and `reserve_out` at the constant product price, after the swap fee.
+    ///
+    /// Returns `None` if either reserve is empty or does not cover `amount_out`.
+    pub fn get_amount_in(
+        amount_out: <T as asset::Trait>::Balance,
+        reserve_in: <T as asset::Trait>::Balance,
+        reserve_out: <T as asset::Trait>::Balance,
+    ) -> Option<<T as asset::Trait>::Balance> {
+        if reserve_in.is_zero() || amount_out >= reserve_out {
+            return None;
+        }
+        let fee = T::SwapFee::get();
+        if fee == Permill::one() {
+            return None;
+        }
+        let accuracy = U256::from(Permill::one().deconstruct());
+        // Round both divisions up, so that `get_amount_out` of the result is at least
+        // `amount_out`.
+        let numerator = U256::from(reserve_in.saturated_into::<u128>())
+            * U256::from(amount_out.saturated_into::<u128>());
+        let denominator = U256::from((reserve_out - amount_out).saturated_into::<u128>());
+        let amount_in_with_fee = (numerator + denominator - U256::one()) / denominator;
+        let net = accuracy - U256::from(fee.deconstruct());
+        let amount_in = (amount_in_with_fee * accuracy + net - U256::one()) / net;
+        if amount_in > U256::from(u128::max_value()) {
+            return None;
+        }
+        let amount_in = amount_in.low_u128();
+        // The balance type may be narrower than `u128`.
+        if amount_in > <T as asset::Trait>::Balance::max_value().saturated_into::<u128>() {
+            return None;
+        }
+        Some(amount_in.saturated_into())
+    }
+
+    /// The amounts of each asset of `path` when swapping exactly `amount_in` of its first asset
+    /// through each consecutive pair, starting with `amount_in`.
+    pub fn get_amounts_out(
+        amount_in: <T as asset::Trait>::Balance,
+        path: &[T::AssetId],
+    ) -> Result<Vec<<T as asset::Trait>::Balance>, dispatch::DispatchError> {
+        Self::ensure_path(path)?;
+        let mut amounts = Vec::with_capacity(path.len());
+        let mut amount = amount_in;
+        amounts.push(amount);
+        for hop in path.windows(2) {
+            let (_, reserve_in, reserve_out, _) =
+                Self::pair_reserves(hop[0], hop[1]).ok_or(Error::<T>::UnknownPair)?;
+            amount = Self::get_amount_out(amount, reserve_in, reserve_out)
+                .ok_or(Error::<T>::InsufficientLiquidity)?;
+            ensure!(!amount.is_zero(), Error::<T>::InsufficientOutputAmount);
+            amounts.push(amount);
+        }
+        Ok(amounts)
+    }
+
+    /// The amounts of each asset of `path` when swapping its first asset through each
+    /// consecutive pair for exactly `amount_out` of its last asset, ending with `amount_out`.
+    pub fn get_amounts_in(
+        amount_out: <T as asset::Trait>::Balance,
+        path: &[T::AssetId],
+    ) -> Result<Vec<<T as asset::Trait>::Balance>, dispatch::DispatchError> {
+        Self::ensure_path(path)?;
+        ensure!(!amount_out.is_zero(), Error::<T>::InsufficientOutputAmount);
+        let mut amounts = Vec::with_capacity(path.len());
+        let mut amount = amount_out;
+        amounts.push(amount);
+        for hop in path.windows(2).rev() {
+            let (_, reserve_in, reserve_out, _) =
+                Self::pair_reserves(hop[0], hop[1]).ok_or(Error::<T>::UnknownPair)?;
+            amount = Self::get_amount_in(amount, reserve_in, reserve_out)
+                .ok_or(Error::<T>::InsufficientLiquidity)?;
+            amounts.push(amount);
+        }
+        amounts.reverse();
+        Ok(amounts)
+    }
+
+    /// Ensure that `path` has between two and `MaxPathLength` assets, none of them twice.
+    fn ensure_path(path: &[T::AssetId]) -> dispatch::DispatchResult {
+        ensure!(
+            path.len() >= 2 && path.len() <= T::MaxPathLength::get() as usize,
+            Error::<T>::InvalidPath
         );
-        //let rootKLast: <T as asset::Trait>::Balance = math::sqrt()
-        return true;
+        for (i, id) in path.iter().enumerate() {
+            ensure!(!path[i + 1..].contains(id), Error::<T>::IdenticalIdentifier);
+        }
+        Ok(())
     }
 
-    fn _burn_token(
-        id: &T::AssetId,
-        target: &T::AccountId,
-        amount: &<T as asset::Trait>::Balance,
+    /// Take `amounts[0]` of the first asset of `path` from `who`, swap it through each
+    /// consecutive pair of `path` and pay the last of `amounts` out to `who`.
+    ///
+    /// `amounts` must come from `get_amounts_out` or `get_amounts_in` for the same `path`. The
+    /// caller must be transactional, as the payout can fail after the input is taken.
+    fn do_swap(
+        who: &T::AccountId,
+        amounts: &[<T as asset::Trait>::Balance],
+        path: &[T::AssetId],
     ) -> dispatch::DispatchResult {
-        asset::Module::<T>::burn_from_system(id, target, amount)?;
-        if *id == Zero::zero() {
-            let account = <T as pallet_balances::Trait>::AccountStore::get(target);
-            pallet_balances::Module::<T>::mutate_account(target, |account| {})
+        let amount_in = amounts[0];
+        let amount_out = amounts[amounts.len() - 1];
+        let hops = path
+            .windows(2)
+            .zip(amounts.windows(2))
+            .map(|(hop, hop_amounts)| {
+                let (lpt, reserve_in, reserve_out, in_is_token0) =
+                    Self::pair_reserves(hop[0], hop[1]).ok_or(Error::<T>::UnknownPair)?;
+                let (new_reserve_in, new_reserve_out) =
+                    Self::swapped_reserves(reserve_in, reserve_out, hop_amounts[0], hop_amounts[1])?;
+                let reserves = if in_is_token0 {
+                    (new_reserve_in, new_reserve_out)
+                } else {
+                    (new_reserve_out, new_reserve_in)
+                };
+                Ok((lpt, reserves))
+            })
+            .collect::<Result<Vec<_>, Error<T>>>()?;
+        asset::Module::<T>::ensure_can_withdraw(path[0], who, amount_in)?;
+
+        asset::Module::<T>::withdraw(path[0], who, amount_in)?;
+        asset::Module::<T>::deposit(path[path.len() - 1], who, amount_out)?;
+        for (i, (lpt, (reserve0, reserve1))) in hops.into_iter().enumerate() {
+            // Accumulate the price that held until now before moving it
+            Self::_update(&lpt)?;
+            <Pairs<T>>::mutate(lpt, |info| {
+                if let Some(info) = info {
+                    info.reserve0 = reserve0;
+                    info.reserve1 = reserve1;
+                }
+            });
+            Self::deposit_event(RawEvent::Swap(
+                who.clone(),
+                path[i],
+                amounts[i],
+                path[i + 1],
+                amounts[i + 1],
+            ));
         }
         Ok(())
     }
 
-    fn _mint_token(
-        id: &T::AssetId,
-        target: &T::AccountId,
-        amount: &<T as asset::Trait>::Balance,
+    /// The reserves of a pair after `amount_in` goes into `reserve_in` and `amount_out` comes out
+    /// of `reserve_out`, if the product of the reserves net of the swap fee does not shrink.
+    fn swapped_reserves(
+        reserve_in: <T as asset::Trait>::Balance,
+        reserve_out: <T as asset::Trait>::Balance,
+        amount_in: <T as asset::Trait>::Balance,
+        amount_out: <T as asset::Trait>::Balance,
+    ) -> Result<(<T as asset::Trait>::Balance, <T as asset::Trait>::Balance), Error<T>> {
+        ensure!(amount_out < reserve_out, Error::<T>::InsufficientLiquidity);
+        let new_reserve_in = reserve_in
+            .checked_add(&amount_in)
+            .ok_or(Error::<T>::StorageOverflow)?;
+        let new_reserve_out = reserve_out - amount_out;
+        let adjusted_in = new_reserve_in - T::SwapFee::get().mul_ceil(amount_in);
+        ensure!(
+            U256::from(adjusted_in.saturated_into::<u128>())
+                * U256::from(new_reserve_out.saturated_into::<u128>())
+                >= U256::from(reserve_in.saturated_into::<u128>())
+                    * U256::from(reserve_out.saturated_into::<u128>()),
+            Error::<T>::K
+        );
+        Ok((new_reserve_in, new_reserve_out))
+    }
+
+    /// The liquidity token of the pair of `from` and `to` and its reserves of `from` and `to`, in
+    /// that order, along with whether `from` is the first asset of the pair.
+    fn pair_reserves(
+        from: T::AssetId,
+        to: T::AssetId,
+    ) -> Option<(
+        T::AssetId,
+        <T as asset::Trait>::Balance,
+        <T as asset::Trait>::Balance,
+        bool,
+    )> {
+        let lpt = Self::lpt((from, to))?;
+        let info = Self::pair(lpt)?;
+        if from == info.token0 {
+            Some((lpt, info.reserve0, info.reserve1, true))
+        } else {
+            Some((lpt, info.reserve1, info.reserve0, false))
+        }
+    }
+
+    /// Create a StableSwap pool of `assets` with `amplification` from `amounts` of `who`, and
+    /// mint its invariant in liquidity tokens to `who`.
+    fn create_stable_pool(
+        who: &T::AccountId,
+        assets: Vec<T::AssetId>,
+        amounts: Vec<<T as asset::Trait>::Balance>,
+        amplification: u32,
     ) -> dispatch::DispatchResult {
-        asset::Module::<T>::mint_from_system(id, target, amount)?;
-        if *id == Zero::zero() {}
+        ensure!(
+            assets.len() >= 2
+                && assets.len() <= T::MaxStableAssets::get() as usize
+                && amounts.len() == assets.len(),
+            Error::<T>::InvalidPoolAssets
+        );
+        ensure!(
+            amplification > 0 && amplification <= T::MaxAmplification::get(),
+            Error::<T>::InvalidAmplification
+        );
+        let mut holdings = assets.into_iter().zip(amounts).collect::<Vec<_>>();
+        holdings.sort_by_key(|&(id, _)| id);
+        ensure!(
+            holdings.windows(2).all(|pair| pair[0].0 != pair[1].0),
+            Error::<T>::IdenticalIdentifier
+        );
+        ensure!(
+            holdings.iter().all(|(_, amount)| !amount.is_zero()),
+            Error::<T>::InsufficientLiquidityMinted
+        );
+        let (assets, balances): (Vec<_>, Vec<_>) = holdings.into_iter().unzip();
+        ensure!(Self::stable_lpt(&assets).is_none(), Error::<T>::PairExists);
+        let liquidity = Self::stable_invariant(&balances, amplification)?;
+        for (&id, &amount) in assets.iter().zip(&balances) {
+            asset::Module::<T>::ensure_can_withdraw(id, who, amount)?;
+        }
+
+        for (&id, &amount) in assets.iter().zip(&balances) {
+            asset::Module::<T>::withdraw(id, who, amount)?;
+        }
+        asset::Module::<T>::issue_from_system(Zero::zero())?;
+        let lpt = asset::NextAssetId::<T>::get() - One::one();
+        asset::Module::<T>::deposit(lpt, who, liquidity)?;
+        let now = <timestamp::Module<T>>::get();
+        <StableLPTokens<T>>::insert(&assets, lpt);
+        <StablePools<T>>::insert(
+            lpt,
+            StablePool {
+                assets,
+                balances,
+                initial_amplification: amplification,
+                future_amplification: amplification,
+                initial_amplification_time: now,
+                future_amplification_time: now,
+            },
+        );
+        Self::deposit_event(RawEvent::StablePoolCreated(who.clone(), lpt));
         Ok(())
     }
 
+    /// The amplification of `pool` now, part way along its ramp if one is under way.
+    pub fn amplification(
+        pool: &StablePool<T::AssetId, <T as asset::Trait>::Balance, T::Moment>,
+    ) -> u32 {
+        let now = <timestamp::Module<T>>::get();
+        if now >= pool.future_amplification_time
+            || pool.future_amplification_time <= pool.initial_amplification_time
+        {
+            return pool.future_amplification;
+        }
+        let elapsed: u128 = now
+            .saturating_sub(pool.initial_amplification_time)
+            .saturated_into();
+        let duration: u128 = (pool.future_amplification_time - pool.initial_amplification_time)
+            .saturated_into();
+        let (initial, future) = (
+            pool.initial_amplification as u128,
+            pool.future_amplification as u128,
+        );
+        let amplification = if future > initial {
+            initial + (future - initial) * elapsed / duration
+        } else {
+            initial - (initial - future) * elapsed / duration
+        };
+        amplification as u32
+    }
+
+    /// The StableSwap invariant of `balances` with `amplification`.
+    fn stable_invariant(
+        balances: &[<T as asset::Trait>::Balance],
+        amplification: u32,
+    ) -> Result<<T as asset::Trait>::Balance, Error<T>> {
+        let balances = balances
+            .iter()
+            .map(|balance| balance.saturated_into::<u128>())
+            .collect::<Vec<_>>();
+        stable_swap::compute_d(&balances, amplification.into())
+            .map(|d| d.saturated_into())
+            .ok_or(Error::<T>::InvariantFailed)
+    }
+
+    /// The positions of `from` and `to` among `assets`.
+    fn asset_indices(
+        assets: &[T::AssetId],
+        from: T::AssetId,
+        to: T::AssetId,
+    ) -> Result<(usize, usize), Error<T>> {
+        ensure!(from != to, Error::<T>::IdenticalIdentifier);
+        let position = |asset| assets.iter().position(|&id| id == asset);
+        match (position(from), position(to)) {
+            (Some(i), Some(j)) => Ok((i, j)),
+            _ => Err(Error::<T>::UnknownPair),
+        }
+    }
+
+    /// The amount of the asset at `j` that `amount_in` of the asset at `i` buys from `pool`,
+    /// after the swap fee.
+    fn stable_amount_out(
+        pool: &StablePool<T::AssetId, <T as asset::Trait>::Balance, T::Moment>,
+        i: usize,
+        j: usize,
+        amount_in: <T as asset::Trait>::Balance,
+    ) -> Result<<T as asset::Trait>::Balance, Error<T>> {
+        let balances = pool
+            .balances
+            .iter()
+            .map(|balance| balance.saturated_into::<u128>())
+            .collect::<Vec<_>>();
+        let x = balances[i]
+            .checked_add(amount_in.saturated_into())
+            .ok_or(Error::<T>::StorageOverflow)?;
+        let y = stable_swap::compute_y(&balances, i, j, x, Self::amplification(pool).into())
+            .ok_or(Error::<T>::InvariantFailed)?;
+        // Round against the trader, so the invariant never shrinks.
+        let dy = balances[j]
+            .checked_sub(y)
+            .and_then(|dy| dy.checked_sub(1))
+            .ok_or(Error::<T>::InsufficientLiquidity)?;
+        let dy: <T as asset::Trait>::Balance = dy.saturated_into();
+        Ok(dy - T::SwapFee::get().mul_ceil(dy))
+    }
+
+    /// The amount of `to` that `amount_in` of `from` buys from StableSwap pool `lpt`, after the
+    /// swap fee.
+    pub fn get_stable_amount_out(
+        lpt: T::AssetId,
+        from: T::AssetId,
+        amount_in: <T as asset::Trait>::Balance,
+        to: T::AssetId,
+    ) -> Result<<T as asset::Trait>::Balance, dispatch::DispatchError> {
+        let pool = Self::stable_pool(lpt).ok_or(Error::<T>::UnknownPool)?;
+        let (i, j) = Self::asset_indices(&pool.assets, from, to)?;
+        Ok(Self::stable_amount_out(&pool, i, j, amount_in)?)
+    }
+
+    /// Create a weighted pool of `assets` with `weights` from `amounts` of `who`, and mint the
+    /// initial supply of its liquidity tokens to `who`, but for `MIN_WEIGHTED_SUPPLY`.
+    fn create_weighted_pool(
+        who: &T::AccountId,
+        assets: Vec<T::AssetId>,
+        amounts: Vec<<T as asset::Trait>::Balance>,
+        weights: Vec<Perbill>,
+    ) -> dispatch::DispatchResult {
+        ensure!(
+            assets.len() >= 2
+                && assets.len() <= T::MaxWeightedAssets::get() as usize
+                && amounts.len() == assets.len()
+                && weights.len() == assets.len(),
+            Error::<T>::InvalidPoolAssets
+        );
+        let total_weight = weights
+            .iter()
+            .map(|weight| weight.deconstruct() as u64)
+            .sum::<u64>();
+        ensure!(
+            weights.iter().all(|&weight| weight >= MIN_WEIGHT)
+                && total_weight == Perbill::ACCURACY as u64,
+            Error::<T>::InvalidWeights
+        );
+        let mut holdings = assets
+            .into_iter()
+            .zip(amounts)
+            .zip(weights)
+            .map(|((id, amount), weight)| (id, amount, weight))
+            .collect::<Vec<_>>();
+        holdings.sort_by_key(|&(id, _, _)| id);
+        ensure!(
+            holdings.windows(2).all(|pair| pair[0].0 != pair[1].0),
+            Error::<T>::IdenticalIdentifier
+        );
+        ensure!(
+            holdings.iter().all(|(_, amount, _)| !amount.is_zero()),
+            Error::<T>::InsufficientLiquidityMinted
+        );
+        let assets = holdings.iter().map(|&(id, _, _)| id).collect::<Vec<_>>();
+        let balances = holdings.iter().map(|&(_, amount, _)| amount).collect::<Vec<_>>();
+        let weights = holdings.iter().map(|&(_, _, weight)| weight).collect::<Vec<_>>();
+        ensure!(Self::pool_lpt(&assets).is_none(), Error::<T>::PairExists);
+        for (&id, &amount) in assets.iter().zip(&balances) {
+            asset::Module::<T>::ensure_can_withdraw(id, who, amount)?;
+        }
+
+        for (&id, &amount) in assets.iter().zip(&balances) {
+            asset::Module::<T>::withdraw(id, who, amount)?;
+        }
+        asset::Module::<T>::issue_from_system(Zero::zero())?;
+        let lpt = asset::NextAssetId::<T>::get() - One::one();
+        asset::Module::<T>::deposit(lpt, &T::AccountId::default(), MIN_WEIGHTED_SUPPLY.saturated_into())?;
+        asset::Module::<T>::deposit(
+            lpt,
+            who,
+            (INITIAL_WEIGHTED_SUPPLY - MIN_WEIGHTED_SUPPLY).saturated_into(),
+        )?;
+        <PoolLPTokens<T>>::insert(&assets, lpt);
+        <Pools<T>>::insert(
+            lpt,
+            Pool {
+                assets,
+                balances,
+                weights,
+            },
+        );
+        Self::deposit_event(RawEvent::WeightedPoolCreated(who.clone(), lpt));
+        Ok(())
+    }
+
+    /// The share of each of `balances` that `amount` out of `total_supply` liquidity tokens is
+    /// worth, rounded down.
+    fn pro_rata(
+        amount: <T as asset::Trait>::Balance,
+        total_supply: <T as asset::Trait>::Balance,
+        balances: &[<T as asset::Trait>::Balance],
+    ) -> Vec<<T as asset::Trait>::Balance> {
+        balances
+            .iter()
+            .map(|&balance| Self::quote(amount, total_supply, balance).unwrap_or_else(Zero::zero))
+            .collect()
+    }
+
+    /// The amount of the asset at `j` that `amount_in` of the asset at `i` buys from weighted
+    /// `pool`, after the swap fee.
+    fn weighted_amount_out(
+        pool: &Pool<T::AssetId, <T as asset::Trait>::Balance>,
+        i: usize,
+        j: usize,
+        amount_in: <T as asset::Trait>::Balance,
+    ) -> Result<<T as asset::Trait>::Balance, Error<T>> {
+        ensure!(
+            amount_in <= pool.balances[i] / <T as asset::Trait>::Balance::from(2u32),
+            Error::<T>::TradeTooLarge
+        );
+        let amount_out: <T as asset::Trait>::Balance = weighted_math::out_given_in(
+            pool.balances[i].saturated_into(),
+            pool.weights[i],
+            pool.balances[j].saturated_into(),
+            pool.weights[j],
+            amount_in.saturated_into(),
+            T::SwapFee::get(),
+        )
+        .ok_or(Error::<T>::InvariantFailed)?
+        .saturated_into();
+        ensure!(
+            amount_out <= pool.balances[j] / <T as asset::Trait>::Balance::from(3u32),
+            Error::<T>::TradeTooLarge
+        );
+        Ok(amount_out)
+    }
+
+    /// The amount of `to` that `amount_in` of `from` buys from weighted pool `lpt`, after the
+    /// swap fee.
+    pub fn get_weighted_amount_out(
+        lpt: T::AssetId,
+        from: T::AssetId,
+        amount_in: <T as asset::Trait>::Balance,
+        to: T::AssetId,
+    ) -> Result<<T as asset::Trait>::Balance, dispatch::DispatchError> {
+        let pool = Self::pool(lpt).ok_or(Error::<T>::UnknownPool)?;
+        let (i, j) = Self::asset_indices(&pool.assets, from, to)?;
+        Ok(Self::weighted_amount_out(&pool, i, j, amount_in)?)
+    }
+
+    /// How a limit order to buy or sell `amount` of `base` for `quote` at `limit` or better fills
+    /// against their pair and the other side of their order book, without trading.
+    ///
+    /// At each price level of the book, the pair is traded first up to the point its marginal
+    /// price reaches that level, then the resting order at the level.
+    fn match_order(
+        base: T::AssetId,
+        quote: T::AssetId,
+        side: OrderSide,
+        limit: UQ64x64,
+        amount: <T as asset::Trait>::Balance,
+    ) -> Result<Matching<T::AssetId, <T as asset::Trait>::Balance>, Error<T>> {
+        let mut pair: Option<(
+            T::AssetId,
+            <T as asset::Trait>::Balance,
+            <T as asset::Trait>::Balance,
+            (<T as asset::Trait>::Balance, <T as asset::Trait>::Balance),
+        )> = Self::lpt((base, quote))
+            .and_then(|lpt| Self::pair(lpt).map(|info| (lpt, info)))
+            .filter(|(_, info)| !info.reserve0.is_zero() && !info.reserve1.is_zero())
+            .map(|(lpt, info)| (lpt, Zero::zero(), Zero::zero(), (info.reserve0, info.reserve1)));
+        let mut fills = Vec::new();
+        let mut remaining = amount;
+        let book = Self::order_book_side((base, quote), side.opposite());
+        let mut entries = book.iter();
+        while !remaining.is_zero() {
+            let level = entries.next().filter(|&&(price, _)| match side {
+                OrderSide::Buy => price <= limit,
+                OrderSide::Sell => price >= limit,
+            });
+            let target = level.map(|&(price, _)| price).unwrap_or(limit);
+            if let Some((_, amount0, amount1, reserves)) = pair.as_mut() {
+                if let Some((base_amount, quote_amount)) =
+                    Self::pair_fill(side, *reserves, target, limit, remaining)
+                {
+                    *amount0 += base_amount;
+                    *amount1 += quote_amount;
+                    *reserves = match side {
+                        OrderSide::Buy => (reserves.0 - base_amount, reserves.1 + quote_amount),
+                        OrderSide::Sell => (reserves.0 + base_amount, reserves.1 - quote_amount),
+                    };
+                    remaining -= base_amount;
+                }
+            }
+            let (price, id) = match level {
+                Some(&entry) if !remaining.is_zero() => entry,
+                _ => break,
+            };
+            let order = Self::order(id).ok_or(Error::<T>::UnknownOrder)?;
+            let base_amount = math::min::<T>(remaining, order.amount);
+            // Round in favour of the resting order.
+            let quote_amount: <T as asset::Trait>::Balance = match side {
+                OrderSide::Buy => price.checked_mul_ceil(base_amount.saturated_into()),
+                OrderSide::Sell => price.checked_mul_floor(base_amount.saturated_into()),
+            }
+            .ok_or(Error::<T>::StorageOverflow)?
+            .saturated_into();
+            fills.push((id, base_amount, quote_amount));
+            remaining -= base_amount;
+        }
+        let pair = pair.filter(|(_, amount0, _, _)| !amount0.is_zero());
+        Ok(Matching {
+            pair,
+            fills,
+            remaining,
+        })
+    }
+
+    /// The amounts of the first and second asset of a pair with `reserves` that a limit order on
+    /// `side` trades with it before its marginal price reaches `target`, for at most `remaining`
+    /// of the first asset.
+    ///
+    /// Returns `None` if the pair is already at `target` or worse, or if its average price would
+    /// be worse than `limit`.
+    fn pair_fill(
+        side: OrderSide,
+        (reserve0, reserve1): (<T as asset::Trait>::Balance, <T as asset::Trait>::Balance),
+        target: UQ64x64,
+        limit: UQ64x64,
+        remaining: <T as asset::Trait>::Balance,
+    ) -> Option<(<T as asset::Trait>::Balance, <T as asset::Trait>::Balance)> {
+        let accuracy = U256::from(Permill::ACCURACY);
+        let net = accuracy - U256::from(T::SwapFee::get().deconstruct());
+        let k = Self::k(reserve0, reserve1);
+        let scaled_k = k.checked_mul(U256::one() << 64)?;
+        let target = U256::from(target.to_bits());
+        // The reserve of the first asset at which the marginal price, net of the swap fee, is
+        // `target`: buying pays `reserve1 / (reserve0 * (1 - fee))`, selling gets
+        // `reserve1 * (1 - fee) / reserve0`.
+        let (numerator, denominator) = match side {
+            OrderSide::Buy => (scaled_k.checked_mul(accuracy)?, target.checked_mul(net)?),
+            OrderSide::Sell => (scaled_k.checked_mul(net)?, target.checked_mul(accuracy)?),
+        };
+        let target_reserve = math::sqrt_u256(numerator / denominator);
+        let reserve0_u256 = U256::from(reserve0.saturated_into::<u128>());
+        match side {
+            OrderSide::Buy => {
+                if target_reserve >= reserve0_u256 {
+                    return None;
+                }
+                let amount0 = math::min::<T>(
+                    (reserve0_u256 - target_reserve).low_u128().saturated_into(),
+                    remaining,
+                );
+                let amount1 = Self::get_amount_in(amount0, reserve1, reserve0)?;
+                if amount1.saturated_into::<u128>() > limit.checked_mul_ceil(amount0.saturated_into())? {
+                    return None;
+                }
+                Some((amount0, amount1))
+            }
+            OrderSide::Sell => {
+                if target_reserve <= reserve0_u256 {
+                    return None;
+                }
+                let amount0 = math::min::<T>(
+                    (target_reserve - reserve0_u256)
+                        .min(U256::from(u128::max_value()))
+                        .low_u128()
+                        .saturated_into(),
+                    remaining,
+                );
+                let amount1 = Self::get_amount_out(amount0, reserve0, reserve1)?;
+                if amount1.is_zero()
+                    || amount1.saturated_into::<u128>() < limit.checked_mul_floor(amount0.saturated_into())?
+                {
+                    return None;
+                }
+                Some((amount0, amount1))
+            }
+        }
+    }
+
+    /// The resting orders of the pair of `token0` and `token1`, given in either order, as the
+    /// amounts of its first asset bid and asked at each price, for at most `depth` prices on
+    /// each side.
+    pub fn order_book(
+        token0: T::AssetId,
+        token1: T::AssetId,
+        depth: u32,
+    ) -> OrderBookSnapshot<<T as asset::Trait>::Balance> {
+        let pair = Self::sorted(token0, token1);
+        let levels = |side| {
+            let mut levels: Vec<(UQ64x64, <T as asset::Trait>::Balance)> = Vec::new();
+            for (price, id) in Self::order_book_side(pair, side) {
+                let amount = Self::order(id).map(|order| order.amount).unwrap_or_default();
+                let same_price = levels.last().map_or(false, |&(level, _)| level == price);
+                if same_price {
+                    if let Some((_, total)) = levels.last_mut() {
+                        *total += amount;
+                    }
+                } else if levels.len() < depth as usize {
+                    levels.push((price, amount));
+                } else {
+                    break;
+                }
+            }
+            levels
+        };
+        OrderBookSnapshot {
+            bids: levels(OrderSide::Buy),
+            asks: levels(OrderSide::Sell),
+        }
+    }
+
+    /// Move the pairs from the `Releases::V1_0_0` layout into `PairInfo`, with their assets in
+    /// ascending order.
+    ///
+    /// `Pairs` held the assets of each pair in the order it was created with, and `Reserves` the
+    /// reserves in the same order under the liquidity token or, for pairs created before that was
+    /// fixed, under the first asset. If both orders of two assets made a pair, only the older one
+    /// stays in `LPTokens`; the other keeps its reserves, so it can still be burned.
+    fn migrate_to_pair_info() -> Weight {
+        let module = b"SwapModule";
+        let mut pairs: Vec<(T::AssetId, (T::AssetId, T::AssetId))> =
+            StorageIterator::<(T::AssetId, T::AssetId)>::new(module, b"Pairs")
+                .drain()
+                .filter_map(|(key, tokens)| {
+                    // The key is the `blake2_128_concat` hash of the liquidity token.
+                    let lpt = T::AssetId::decode(&mut key.get(16..)?).ok()?;
+                    Some((lpt, tokens))
+                })
+                .collect();
+        let mut reserves: BTreeMap<T::AssetId, _> =
+            StorageIterator::<(<T as asset::Trait>::Balance, <T as asset::Trait>::Balance)>::new(
+                module,
+                b"Reserves",
+            )
+            .drain()
+            .filter_map(|(key, reserves)| {
+                let id = T::AssetId::decode(&mut key.get(16..)?).ok()?;
+                Some((id, reserves))
+            })
+            .collect();
+        <LPTokens<T>>::remove_all();
+        let reads = 2 * pairs.len() as Weight + reserves.len() as Weight;
+
+        let mut writes = 0;
+        // Liquidity tokens are issued in order, so older pairs come first.
+        pairs.sort_by_key(|(lpt, _)| *lpt);
+        for &(lpt, (token0, token1)) in pairs.iter() {
+            let (reserve0, reserve1) = match reserves.remove(&lpt) {
+                Some(reserves) => reserves,
+                None if !pairs.iter().any(|(id, _)| *id == token0) => {
+                    reserves.remove(&token0).unwrap_or_default()
+                }
+                None => Default::default(),
+            };
+            let info = if token0 < token1 {
+                PairInfo { token0, token1, reserve0, reserve1, k_last: U256::zero() }
+            } else {
+                // The cumulative prices follow the assets they price.
+                if <LastAccumulativePrice<T>>::contains_key(lpt) {
+                    let (price0, price1) = <LastAccumulativePrice<T>>::get(lpt);
+                    <LastAccumulativePrice<T>>::insert(lpt, (price1, price0));
+                    writes += 1;
+                }
+                PairInfo {
+                    token0: token1,
+                    token1: token0,
+                    reserve0: reserve1,
+                    reserve1: reserve0,
+                    k_last: U256::zero(),
+                }
+            };
+            if !<LPTokens<T>>::contains_key((info.token0, info.token1)) {
+                <LPTokens<T>>::insert((info.token0, info.token1), lpt);
+                writes += 1;
+            }
+            <Pairs<T>>::insert(lpt, info);
+            writes += 1;
+        }
+
+        T::DbWeight::get().reads_writes(reads, writes + reads)
+    }
+
+    /// Move from the `Releases::V2_0_0` layout, with one `LastBlockTimestamp` for all pairs, to
+    /// one for each pair.
+    ///
+    /// The shared timestamp is the time any pair last traded, so the prices of each pair start
+    /// accumulating afresh from now, with a first observation.
+    fn migrate_to_pair_oracles() -> Weight {
+        let _ = take_storage_value::<T::Moment>(b"SwapModule", b"LastBlockTimestamp", &[]);
+        let pairs = <Pairs<T>>::iter().map(|(lpt, _)| lpt).collect::<Vec<_>>();
+        for lpt in pairs.iter() {
+            Self::start_oracle(*lpt);
+        }
+        let count = pairs.len() as Weight;
+        T::DbWeight::get().reads_writes(1 + 2 * count, 1 + 3 * count)
+    }
+
+    /// Move the cumulative prices from the `FixedU128` of the `Releases::V3_0_0` layout to
+    /// `UQ64x64`.
+    ///
+    /// Both hold the price times a scale in a `u128`, `10^18` before and `2^64` after, so the
+    /// stored values are rescaled in place.
+    fn migrate_to_uq64x64() -> Weight {
+        let rescale = |price: UQ64x64| {
+            let bits = (U256::from(price.to_bits()) << 64) / U256::from(FixedU128::DIV);
+            UQ64x64::from_bits(bits.low_u128())
+        };
+        let mut reads = 0;
+        let mut writes = 0;
+        for (lpt, _) in <Pairs<T>>::iter() {
+            let (price0_cumulative, price1_cumulative) = Self::last_cumulative_price(lpt);
+            <LastAccumulativePrice<T>>::insert(
+                lpt,
+                (rescale(price0_cumulative), rescale(price1_cumulative)),
+            );
+            reads += 2;
+            writes += 1;
+            for index in 0..T::MaxObservations::get() {
+                reads += 1;
+                if let Some(observation) = Self::observations(lpt, index) {
+                    <Observations<T>>::insert(
+                        lpt,
+                        index,
+                        Observation {
+                            price0_cumulative: rescale(observation.price0_cumulative),
+                            price1_cumulative: rescale(observation.price1_cumulative),
+                            ..observation
+                        },
+                    );
+                    writes += 1;
+                }
+            }
+        }
+        T::DbWeight::get().reads_writes(reads, writes)
+    }
+
+    /// The product of `reserve0` and `reserve1`.
+    fn k(
+        reserve0: <T as asset::Trait>::Balance,
+        reserve1: <T as asset::Trait>::Balance,
+    ) -> U256 {
+        U256::from(reserve0.saturated_into::<u128>()) * U256::from(reserve1.saturated_into::<u128>())
+    }
+
+    /// The liquidity tokens owed to `fee_to` for pair `info` with `total_supply` liquidity
+    /// tokens: 1/6th of the growth of sqrt(k) since `k_last`, as a share of the pair.
+    ///
+    /// Zero while the fee is off, and until a mint or burn sets `k_last` with the fee on.
+    pub fn fee_liquidity(
+        fee_to: &Option<T::AccountId>,
+        info: &PairInfo<T::AssetId, <T as asset::Trait>::Balance>,
+        total_supply: <T as asset::Trait>::Balance,
+    ) -> <T as asset::Trait>::Balance {
+        if fee_to.is_none() || info.k_last.is_zero() {
+            return Zero::zero();
+        }
+        let root_k = math::sqrt_u256(Self::k(info.reserve0, info.reserve1));
+        let root_k_last = math::sqrt_u256(info.k_last);
+        if root_k <= root_k_last {
+            return Zero::zero();
+        }
+        let numerator = U256::from(total_supply.saturated_into::<u128>()) * (root_k - root_k_last);
+        let denominator = root_k * U256::from(5) + root_k_last;
+        // The fee is a fraction of the total supply, so it fits.
+        (numerator / denominator).low_u128().saturated_into()
+    }
+
+    /// Mint `fee` of liquidity token `lpt` to `fee_to`, if the protocol fee is on.
+    fn mint_fee(
+        lpt: T::AssetId,
+        fee_to: &Option<T::AccountId>,
+        fee: <T as asset::Trait>::Balance,
+    ) -> dispatch::DispatchResult {
+        match fee_to {
+            Some(fee_to) if !fee.is_zero() => asset::Module::<T>::deposit(lpt, fee_to, fee),
+            _ => Ok(()),
+        }
+    }
+
+    /// Store `reserves` as the reserves of pair `info` after a mint or burn of liquidity, and
+    /// their product as `k_last` if the protocol fee is on.
+    fn set_liquidity_reserves(
+        lpt: T::AssetId,
+        info: PairInfo<T::AssetId, <T as asset::Trait>::Balance>,
+        (reserve0, reserve1): (<T as asset::Trait>::Balance, <T as asset::Trait>::Balance),
+        fee_on: bool,
+    ) {
+        let k_last = if fee_on {
+            Self::k(reserve0, reserve1)
+        } else {
+            U256::zero()
+        };
+        <Pairs<T>>::insert(
+            lpt,
+            PairInfo {
+                reserve0,
+                reserve1,
+                k_last,
+                ..info
+            },
+        );
+    }
+
+    /// The cumulative prices of pair `lpt` as of now: the last accumulated ones, plus the current
+    /// prices for the time since they were accumulated.
+    pub fn current_cumulative_prices(lpt: T::AssetId) -> (UQ64x64, UQ64x64) {
+        let (price0_cumulative, price1_cumulative) = Self::last_cumulative_price(lpt);
+        let time_elapsed =
+            <timestamp::Module<T>>::get().saturating_sub(Self::last_block_timestamp(lpt));
+        let (reserve0, reserve1) = Self::reserves(lpt);
+        if time_elapsed.is_zero() || reserve0.is_zero() || reserve1.is_zero() {
+            return (price0_cumulative, price1_cumulative);
+        }
+        let (reserve0, reserve1) = (reserve0.saturated_into(), reserve1.saturated_into());
+        let elapsed = time_elapsed.saturated_into::<u128>();
+        // Neither reserve is zero, so the divisions succeed.
+        let price0 = UQ64x64::saturating_from_rational(reserve1, reserve0).unwrap_or_default();
+        let price1 = UQ64x64::saturating_from_rational(reserve0, reserve1).unwrap_or_default();
+        // The accumulators wrap around, which leaves their differences right.
+        (
+            price0_cumulative.wrapping_add(price0.wrapping_mul_int(elapsed)),
+            price1_cumulative.wrapping_add(price1.wrapping_mul_int(elapsed)),
+        )
+    }
+
+    /// The time-weighted average prices of the assets of pair `lpt`, the first in the second and
+    /// the second in the first, from the newest observation at least `window` old until now.
+    ///
+    /// The average may cover somewhat more than `window`, by up to `ObservationPeriod` plus the
+    /// time between trades. Returns `None` if no observation is that old, which is the case for
+    /// windows longer than `MaxObservations` periods.
+    pub fn consult(lpt: T::AssetId, window: T::Moment) -> Option<(UQ64x64, UQ64x64)> {
+        let now = <timestamp::Module<T>>::get();
+        let newest = Self::observation_index(lpt)?;
+        let count = T::MaxObservations::get();
+        let observation = (0..count)
+            .map(|age| Self::observations(lpt, (newest + count - age) % count))
+            .take_while(Option::is_some)
+            .flatten()
+            .find(|observation| now.saturating_sub(observation.timestamp) >= window)?;
+        let elapsed = now.saturating_sub(observation.timestamp);
+        if elapsed.is_zero() {
+            return None;
+        }
+        let elapsed = elapsed.saturated_into::<u128>();
+        let (price0_cumulative, price1_cumulative) = Self::current_cumulative_prices(lpt);
+        Some((
+            price0_cumulative
+                .wrapping_sub(observation.price0_cumulative)
+                .uqdiv(elapsed)?,
+            price1_cumulative
+                .wrapping_sub(observation.price1_cumulative)
+                .uqdiv(elapsed)?,
+        ))
+    }
+
+    /// Record the cumulative prices of pair `lpt` at `timestamp` in its ring buffer of
+    /// observations, unless the newest one is less than `ObservationPeriod` old.
+    fn observe(
+        lpt: T::AssetId,
+        timestamp: T::Moment,
+        (price0_cumulative, price1_cumulative): (UQ64x64, UQ64x64),
+    ) {
+        let count = T::MaxObservations::get();
+        if count == 0 {
+            return;
+        }
+        let index = match Self::observation_index(lpt) {
+            Some(newest) => {
+                let recent = Self::observations(lpt, newest).map_or(false, |observation| {
+                    timestamp.saturating_sub(observation.timestamp) < T::ObservationPeriod::get()
+                });
+                if recent {
+                    return;
+                }
+                (newest + 1) % count
+            }
+            None => 0,
+        };
+        <Observations<T>>::insert(
+            lpt,
+            index,
+            Observation {
+                timestamp,
+                price0_cumulative,
+                price1_cumulative,
+            },
+        );
+        <ObservationIndex<T>>::insert(lpt, index);
+    }
+
+    /// Start the price oracle of a new pair `lpt` at the current time.
+    fn start_oracle(lpt: T::AssetId) {
+        let now = <timestamp::Module<T>>::get();
+        <LastBlockTimestamp<T>>::insert(lpt, now);
+        Self::observe(lpt, now, Self::last_cumulative_price(lpt));
+    }
+
+    /// Accumulate the prices of pair `pair` until now, before its reserves change.
     fn _update(pair: &T::AssetId) -> dispatch::DispatchResult {
-        let block_timestamp = <timestamp::Module<T>>::get() % T::Moment::from(2u32.pow(32));
-        let time_elapsed = block_timestamp - Self::last_block_timestamp();
-        let reserves = <Reserves<T>>::get(pair);
-        if time_elapsed > Zero::zero() && reserves.0 != Zero::zero() && reserves.1 != Zero::zero() {
-            let reserve0 = FixedU128::saturating_from_integer(reserves.0.saturated_into());
-            let reserve1 = FixedU128::saturating_from_integer(reserves.1.saturated_into());
-            let price0_cumulative_last = reserve1.checked_div(&reserve0).unwrap()
-                * FixedU128::saturating_from_integer(time_elapsed.saturated_into());
-            let price1_cumulative_last = reserve0.checked_div(&reserve1).unwrap()
-                * FixedU128::saturating_from_integer(time_elapsed.saturated_into());
+        let block_timestamp = <timestamp::Module<T>>::get();
+        if block_timestamp > Self::last_block_timestamp(pair) {
+            let (price0_cumulative_last, price1_cumulative_last) =
+                Self::current_cumulative_prices(*pair);
             <LastAccumulativePrice<T>>::insert(
                 &pair,
                 (&price0_cumulative_last, &price1_cumulative_last),
             );
-            <LastBlockTimestamp<T>>::put(block_timestamp);
+            <LastBlockTimestamp<T>>::insert(&pair, block_timestamp);
+            Self::observe(
+                *pair,
+                block_timestamp,
+                (price0_cumulative_last, price1_cumulative_last),
+            );
             Self::deposit_event(RawEvent::Sync(
                 price0_cumulative_last,
                 price1_cumulative_last,
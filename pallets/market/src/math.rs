@@ -1,4 +1,6 @@
 use crate::Trait;
+use sp_core::U256;
+
 pub fn sqrt<T: Trait>(y: <T as asset::Trait>::Balance) -> <T as asset::Trait>::Balance {
     if y > <T as asset::Trait>::Balance::from(3) {
         let mut z = y;
@@ -17,6 +19,22 @@ pub fn sqrt<T: Trait>(y: <T as asset::Trait>::Balance) -> <T as asset::Trait>::B
     }
 }
 
+pub fn sqrt_u256(y: U256) -> U256 {
+    if y > U256::from(3) {
+        let mut z = y;
+        let mut x = y / U256::from(2) + U256::one();
+        while x < z {
+            z = x;
+            x = (y / x + x) / U256::from(2);
+        }
+        z
+    } else if !y.is_zero() {
+        U256::one()
+    } else {
+        y
+    }
+}
+
 pub fn min<T: Trait>(
     x: <T as asset::Trait>::Balance,
     y: <T as asset::Trait>::Balance,
@@ -31,13 +49,23 @@ pub fn min<T: Trait>(
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::mock::Test;
+
     #[test]
     fn sqrt_works() {
-        assert_eq!(2, sqrt(4));
+        assert_eq!(2, sqrt::<Test>(4));
+    }
+
+    #[test]
+    fn sqrt_u256_works() {
+        assert_eq!(U256::from(2), sqrt_u256(U256::from(4)));
+        assert_eq!(U256::from(10_018), sqrt_u256(U256::from(6688u64 * 15008)));
+        let max = U256::from(u128::max_value());
+        assert_eq!(max, sqrt_u256(max * max));
     }
 
     #[test]
     fn min_works() {
-        assert_eq!(1, min(1, 3));
+        assert_eq!(1, min::<Test>(1, 3));
     }
 }
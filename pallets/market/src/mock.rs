@@ -5,8 +5,8 @@ use frame_system as system;
 use sp_core::H256;
 use sp_runtime::{
     testing::Header,
-    traits::{BlakeTwo256, IdentityLookup},
-    Perbill,
+    traits::{BlakeTwo256, ConvertInto, IdentityLookup},
+    ModuleId, Perbill, Permill,
 };
 impl_outer_origin! {
     pub enum Origin for Test {}
@@ -22,6 +22,26 @@ parameter_types! {
     pub const MaximumBlockWeight: Weight = 1024;
     pub const MaximumBlockLength: u32 = 2 * 1024;
     pub const AvailableBlockRatio: Perbill = Perbill::from_percent(75);
+    pub const MetadataDepositBase: u128 = 10;
+    pub const MetadataDepositPerByte: u128 = 1;
+    pub const StringLimit: u32 = 16;
+    pub const NativeAssetId: u128 = 0;
+    pub const AssetModuleId: ModuleId = ModuleId(*b"py/asset");
+    pub const MaxBatchSize: u32 = 16;
+    pub const MaxAttributes: u32 = 16;
+    pub const MaxVestingSchedules: u32 = 8;
+    pub const MinVestedTransferFactor: u32 = 10;
+    pub const MinimumPeriod: u64 = 1;
+    pub const SwapFee: Permill = Permill::from_perthousand(3);
+    pub const MaxPathLength: u32 = 4;
+    pub const ObservationPeriod: u64 = 10;
+    pub const MaxObservations: u32 = 4;
+    pub const MaxStableAssets: u32 = 4;
+    pub const MaxAmplification: u32 = 1_000_000;
+    pub const MinRampTime: u64 = 100;
+    pub const MaxWeightedAssets: u32 = 8;
+    pub const MaxOrders: u32 = 4;
+    pub const OrderDeposit: u128 = 10;
 }
 
 impl system::Trait for Test {
@@ -46,7 +66,7 @@ impl system::Trait for Test {
     type AvailableBlockRatio = AvailableBlockRatio;
     type Version = ();
     type ModuleToIndex = ();
-    type AccountData = ();
+    type AccountData = pallet_balances::AccountData<u128>;
     type OnNewAccount = ();
     type OnKilledAccount = ();
     type SystemWeightInfo = ();
@@ -56,6 +76,20 @@ impl asset::Trait for Test {
     type Event = ();
     type Balance = u128;
     type AssetId = u128;
+    type Currency = pallet_balances::Module<Test>;
+    type NativeAssetId = NativeAssetId;
+    type ModuleId = AssetModuleId;
+    type MetadataDepositBase = MetadataDepositBase;
+    type MetadataDepositPerByte = MetadataDepositPerByte;
+    type StringLimit = StringLimit;
+    type OnDust = ();
+    type MaxBatchSize = MaxBatchSize;
+    type ItemId = u32;
+    type MaxAttributes = MaxAttributes;
+    type BlockNumberToBalance = ConvertInto;
+    type MaxVestingSchedules = MaxVestingSchedules;
+    type MinVestedTransferFactor = MinVestedTransferFactor;
+    type WeightInfo = ();
 }
 
 impl pallet_balances::Trait for Test {
@@ -69,16 +103,43 @@ impl pallet_balances::Trait for Test {
     type WeightInfo = ();
 }
 
+impl pallet_timestamp::Trait for Test {
+    type Moment = u64;
+    type OnTimestampSet = ();
+    type MinimumPeriod = MinimumPeriod;
+    type WeightInfo = ();
+}
+
 impl Trait for Test {
     type Event = ();
+    type SwapFee = SwapFee;
+    type MaxPathLength = MaxPathLength;
+    type ObservationPeriod = ObservationPeriod;
+    type MaxObservations = MaxObservations;
+    type FeeToOrigin = system::EnsureRoot<u64>;
+    type MaxStableAssets = MaxStableAssets;
+    type MaxAmplification = MaxAmplification;
+    type MinRampTime = MinRampTime;
+    type MaxWeightedAssets = MaxWeightedAssets;
+    type MaxOrders = MaxOrders;
+    type OrderDeposit = OrderDeposit;
 }
 
-pub type TemplateModule = Module<Test>;
+pub type System = system::Module<Test>;
+pub type Balances = pallet_balances::Module<Test>;
+pub type Timestamp = pallet_timestamp::Module<Test>;
+pub type Assets = asset::Module<Test>;
+pub type Market = Module<Test>;
 
 // Build genesis storage according to the mock runtime.
 pub fn new_test_ext() -> sp_io::TestExternalities {
-    system::GenesisConfig::default()
+    let mut t = system::GenesisConfig::default()
         .build_storage::<Test>()
-        .unwrap()
-        .into()
+        .unwrap();
+    pallet_balances::GenesisConfig::<Test> {
+        balances: vec![(1, 1_000_000), (2, 1_000_000)],
+    }
+    .assimilate_storage(&mut t)
+    .unwrap();
+    t.into()
 }
@@ -0,0 +1,152 @@
+//! The StableSwap invariant of Curve, for pools of two or more assets meant to trade near par.
+//!
+//! The balances `x_i` of a pool of `n` assets with amplification `A` hold the invariant `D` of
+//!
+//! `A n^n sum(x_i) + D = A D n^n + D^(n+1) / (n^n prod(x_i))`
+//!
+//! which is a constant sum for large `A` and a constant product for small `A`. Both `D` and the
+//! balance that keeps it after a trade are found by Newton's method.
+
+use sp_core::U256;
+
+/// The most rounds of Newton's method before giving up.
+pub const MAX_ITERATIONS: u32 = 255;
+
+/// Whether `a` and `b` differ by at most one.
+fn converged(a: U256, b: U256) -> bool {
+    if a > b {
+        a - b <= U256::one()
+    } else {
+        b - a <= U256::one()
+    }
+}
+
+/// A 256-bit intermediate result as a `u128`, if it fits.
+fn to_u128(x: U256) -> Option<u128> {
+    if x > U256::from(u128::max_value()) {
+        None
+    } else {
+        Some(x.low_u128())
+    }
+}
+
+/// The invariant `D` of a pool of `balances` with `amplification`.
+///
+/// Returns zero for an empty pool, and `None` if a balance is zero while others are not, or if
+/// the computation overflows or does not converge.
+pub fn compute_d(balances: &[u128], amplification: u128) -> Option<u128> {
+    let n = U256::from(balances.len());
+    let sum = balances
+        .iter()
+        .try_fold(U256::zero(), |sum, &x| sum.checked_add(U256::from(x)))?;
+    if sum.is_zero() {
+        return Some(0);
+    }
+    let ann = U256::from(amplification).checked_mul(n)?;
+    if ann.is_zero() {
+        return None;
+    }
+    let mut d = sum;
+    for _ in 0..MAX_ITERATIONS {
+        let mut d_p = d;
+        for &x in balances {
+            let denominator = U256::from(x).checked_mul(n)?;
+            d_p = d_p.checked_mul(d)?.checked_div(denominator)?;
+        }
+        let d_prev = d;
+        let numerator = ann
+            .checked_mul(sum)?
+            .checked_add(d_p.checked_mul(n)?)?
+            .checked_mul(d)?;
+        let denominator = (ann - U256::one())
+            .checked_mul(d)?
+            .checked_add((n + U256::one()).checked_mul(d_p)?)?;
+        d = numerator.checked_div(denominator)?;
+        if converged(d, d_prev) {
+            return to_u128(d);
+        }
+    }
+    None
+}
+
+/// The balance of asset `j` that keeps the invariant of a pool of `balances` with
+/// `amplification` when the balance of asset `i` becomes `x`.
+///
+/// Returns `None` if `i` and `j` are the same or out of range, or if the computation fails.
+pub fn compute_y(
+    balances: &[u128],
+    i: usize,
+    j: usize,
+    x: u128,
+    amplification: u128,
+) -> Option<u128> {
+    if i == j || i >= balances.len() || j >= balances.len() {
+        return None;
+    }
+    let n = U256::from(balances.len());
+    let d = U256::from(compute_d(balances, amplification)?);
+    let ann = U256::from(amplification).checked_mul(n)?;
+    let mut c = d;
+    let mut sum = U256::zero();
+    for (k, &balance) in balances.iter().enumerate() {
+        let x_k = if k == i {
+            x
+        } else if k != j {
+            balance
+        } else {
+            continue;
+        };
+        let x_k = U256::from(x_k);
+        sum = sum.checked_add(x_k)?;
+        c = c.checked_mul(d)?.checked_div(x_k.checked_mul(n)?)?;
+    }
+    c = c.checked_mul(d)?.checked_div(ann.checked_mul(n)?)?;
+    let b = sum.checked_add(d.checked_div(ann)?)?;
+    let mut y = d;
+    for _ in 0..MAX_ITERATIONS {
+        let y_prev = y;
+        let numerator = y.checked_mul(y)?.checked_add(c)?;
+        let denominator = y
+            .checked_mul(U256::from(2))?
+            .checked_add(b)?
+            .checked_sub(d)?;
+        y = numerator.checked_div(denominator)?;
+        if converged(y, y_prev) {
+            return to_u128(y);
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn compute_d_works() {
+        assert_eq!(compute_d(&[0, 0], 100), Some(0));
+        // A balanced pool is worth the sum of its balances.
+        assert_eq!(compute_d(&[10_000, 10_000], 100), Some(20_000));
+        assert_eq!(compute_d(&[10_000, 10_000, 10_000], 100), Some(30_000));
+        // An imbalanced pool is worth less, and more so with less amplification.
+        let d_high = compute_d(&[15_000, 5_000], 1_000).unwrap();
+        let d_low = compute_d(&[15_000, 5_000], 1).unwrap();
+        assert!(d_low < d_high && d_high < 20_000);
+        assert_eq!(compute_d(&[10_000, 0], 100), None);
+        assert_eq!(compute_d(&[10_000, 10_000], 0), None);
+    }
+
+    #[test]
+    fn compute_y_works() {
+        let balances = [10_000, 10_000];
+        // Near par, 1000 more of one asset takes almost 1000 of the other.
+        assert_eq!(compute_y(&balances, 0, 1, 11_000, 100), Some(9_000));
+        // The invariant holds after the trade.
+        let y = compute_y(&[10_000, 20_000, 30_000], 0, 2, 12_000, 10).unwrap();
+        let before = compute_d(&[10_000, 20_000, 30_000], 10).unwrap();
+        let after = compute_d(&[12_000, 20_000, y], 10).unwrap();
+        assert!(before <= after && after - before <= 2);
+        assert_eq!(compute_y(&balances, 0, 0, 11_000, 100), None);
+        assert_eq!(compute_y(&balances, 0, 2, 11_000, 100), None);
+    }
+}
@@ -1,23 +1,1367 @@
-use crate::{Error, mock::*};
-use frame_support::{assert_ok, assert_noop};
+use crate::{mock::*, Error, OrderSide, PairInfo, PoolKind, UQ64x64};
+use codec::Encode;
+use frame_support::{
+	assert_noop, assert_ok,
+	storage::migration::put_storage_value,
+	traits::{Get, OnRuntimeUpgrade},
+	Blake2_128Concat, StorageHasher,
+};
+use sp_core::U256;
+use sp_runtime::{traits::BadOrigin, FixedPointNumber, FixedU128, Perbill};
+
+const DEADLINE: u64 = u64::max_value();
+
+/// Issue assets 1 and 2 to account 1 and pool 10_000 of each. The liquidity token is asset 3.
+fn setup_pair() {
+	assert_ok!(Assets::issue(Origin::signed(1), 1_000_000));
+	assert_ok!(Assets::issue(Origin::signed(1), 1_000_000));
+	assert_ok!(Market::mint_liquidity(
+		Origin::signed(1),
+		1,
+		10_000,
+		2,
+		10_000,
+		0,
+		0,
+		DEADLINE
+	));
+	assert_eq!(Market::lpt((1, 2)), Some(3));
+	assert_eq!(Market::reserves(3), (10_000, 10_000));
+}
+
+#[test]
+fn swap_should_follow_constant_product() {
+	new_test_ext().execute_with(|| {
+		setup_pair();
+
+		// 0.3% of 1000 is taken as fee: 997 * 10_000 / (10_000 + 997) = 906.
+		assert_ok!(Market::swap(Origin::signed(1), 1, 1000, 2, 0, DEADLINE));
+		assert_eq!(Assets::balance(1, 1), 1_000_000 - 10_000 - 1000);
+		assert_eq!(Assets::balance(2, 1), 1_000_000 - 10_000 + 906);
+		assert_eq!(Market::reserves(3), (11_000, 9_094));
+
+		// The other way round uses the same pair.
+		assert_ok!(Market::swap(Origin::signed(1), 2, 1000, 1, 0, DEADLINE));
+		assert_eq!(Assets::balance(1, 1), 1_000_000 - 10_000 - 1000 + 1086);
+		assert_eq!(Market::reserves(3), (9_914, 10_094));
+	});
+}
+
+#[test]
+fn swap_fee_should_stay_in_reserves() {
+	new_test_ext().execute_with(|| {
+		setup_pair();
+		assert_eq!(Market::get_amount_out(500, 10_000, 10_000), Some(474));
+
+		assert_ok!(Market::swap(Origin::signed(1), 1, 500, 2, 0, DEADLINE));
+		let (reserve0, reserve1) = Market::reserves(3);
+		assert!(reserve0 * reserve1 > 10_000 * 10_000);
+
+		// Swapping the output straight back returns less than was put in.
+		assert_ok!(Market::swap(Origin::signed(1), 2, 474, 1, 0, DEADLINE));
+		assert!(Assets::balance(1, 1) < 1_000_000 - 10_000);
+	});
+}
+
+#[test]
+fn swap_should_work_with_native_currency() {
+	new_test_ext().execute_with(|| {
+		let native = NativeAssetId::get();
+		assert_ok!(Assets::issue(Origin::signed(1), 1_000_000));
+		assert_ok!(Market::mint_liquidity(
+			Origin::signed(1),
+			1,
+			10_000,
+			native,
+			10_000,
+			0,
+			0,
+			DEADLINE
+		));
+		assert_eq!(Balances::free_balance(1), 1_000_000 - 10_000);
+
+		assert_ok!(Market::swap(Origin::signed(2), native, 1000, 1, 0, DEADLINE));
+		assert_eq!(Balances::free_balance(2), 1_000_000 - 1000);
+		assert_eq!(Assets::balance(1, 2), 906);
+		// The native currency has the smaller id, so it comes first in the pair.
+		assert_eq!(Market::reserves(2), (11_000, 9_094));
+		// The pooled native currency is held by the asset module account, not burned.
+		assert_eq!(Balances::free_balance(Assets::account_id()), 11_000);
+		assert_eq!(Balances::total_issuance(), 2_000_000);
+
+		// A payout below the existential deposit fails rather than vanishing, and keeps the input.
+		assert_ok!(Assets::transfer(Origin::signed(1), 1, 3, 1000));
+		assert_noop!(
+			Market::swap(Origin::signed(3), 1, 10, native, 0, DEADLINE),
+			pallet_balances::Error::<Test, pallet_balances::DefaultInstance>::ExistentialDeposit
+		);
+	});
+}
+
+#[test]
+fn burn_liquidity_should_keep_the_liquidity_if_a_payout_fails() {
+	new_test_ext().execute_with(|| {
+		let native = NativeAssetId::get();
+		assert_ok!(Assets::issue(Origin::signed(1), 1_000_000));
+		assert_ok!(Market::mint_liquidity(Origin::signed(1), 1, 10_000, native, 10_000, 0, 0, DEADLINE));
+		assert_ok!(Assets::transfer(Origin::signed(1), 2, 2, 50));
+		assert_ok!(Assets::set_min_balance(Origin::signed(1), 1, 100));
+
+		// The native payout goes through, but 50 of asset 1 is below its minimum balance.
+		assert_noop!(
+			Market::burn_liquidity(Origin::signed(2), 2, 50, 0, 0, DEADLINE),
+			asset::Error::<Test>::BelowMinimum
+		);
+		assert_eq!(Assets::balance(2, 2), 50);
+		assert_eq!(Balances::free_balance(2), 1_000_000);
+	});
+}
+
+#[test]
+fn swap_should_fail_on_bad_input() {
+	new_test_ext().execute_with(|| {
+		setup_pair();
+
+		assert_noop!(
+			Market::swap(Origin::signed(1), 1, 1000, 1, 0, DEADLINE),
+			Error::<Test>::IdenticalIdentifier
+		);
+		assert_noop!(
+			Market::swap(Origin::signed(1), 1, 1000, NativeAssetId::get(), 0, DEADLINE),
+			Error::<Test>::UnknownPair
+		);
+		// The whole input goes to the fee.
+		assert_noop!(
+			Market::swap(Origin::signed(1), 1, 1, 2, 0, DEADLINE),
+			Error::<Test>::InsufficientOutputAmount
+		);
+		// Account 2 holds none of asset 1.
+		assert_noop!(
+			Market::swap(Origin::signed(2), 1, 1000, 2, 0, DEADLINE),
+			asset::Error::<Test>::BalanceLow
+		);
+	});
+}
+
+#[test]
+fn swap_should_accumulate_price() {
+	new_test_ext().execute_with(|| {
+		setup_pair();
+
+		Timestamp::set_timestamp(10);
+		assert_ok!(Market::swap(Origin::signed(1), 1, 1000, 2, 0, DEADLINE));
+		// The price of 1 held for 10 units of time before the swap moved it.
+		assert_eq!(
+			Market::last_cumulative_price(3),
+			(UQ64x64::saturating_from_integer(10), UQ64x64::saturating_from_integer(10))
+		);
+		assert_eq!(Market::last_block_timestamp(3), 10);
+
+		// Asset 1 is now cheaper in asset 2, so its cumulative price grows slower.
+		Timestamp::set_timestamp(20);
+		assert_ok!(Market::swap(Origin::signed(1), 1, 1000, 2, 0, DEADLINE));
+		let (price0_cumulative, price1_cumulative) = Market::last_cumulative_price(3);
+		assert!(price0_cumulative < UQ64x64::saturating_from_integer(20));
+		assert!(price1_cumulative > UQ64x64::saturating_from_integer(20));
+	});
+}
+
+#[test]
+fn swap_should_respect_minimum_output_and_deadline() {
+	new_test_ext().execute_with(|| {
+		setup_pair();
+
+		assert_noop!(
+			Market::swap(Origin::signed(1), 1, 1000, 2, 907, DEADLINE),
+			Error::<Test>::InsufficientOutputAmount
+		);
+		Timestamp::set_timestamp(10);
+		assert_noop!(
+			Market::swap(Origin::signed(1), 1, 1000, 2, 906, 9),
+			Error::<Test>::Expired
+		);
+		assert_ok!(Market::swap(Origin::signed(1), 1, 1000, 2, 906, 10));
+		assert_eq!(Market::reserves(3), (11_000, 9_094));
+	});
+}
+
+#[test]
+fn mint_liquidity_should_take_amounts_at_reserve_ratio() {
+	new_test_ext().execute_with(|| {
+		setup_pair();
+
+		// Only 1000 of the 2000 of asset 2 match 1000 of asset 1 at a 1:1 ratio.
+		assert_noop!(
+			Market::mint_liquidity(Origin::signed(1), 1, 1000, 2, 2000, 0, 1001, DEADLINE),
+			Error::<Test>::InsufficientAmount1
+		);
+		assert_noop!(
+			Market::mint_liquidity(Origin::signed(1), 1, 2000, 2, 1000, 1001, 0, DEADLINE),
+			Error::<Test>::InsufficientAmount0
+		);
+		assert_ok!(Market::mint_liquidity(
+			Origin::signed(1),
+			1,
+			1000,
+			2,
+			2000,
+			1000,
+			1000,
+			DEADLINE
+		));
+		assert_eq!(Market::reserves(3), (11_000, 11_000));
+		assert_eq!(Assets::balance(2, 1), 1_000_000 - 11_000);
+	});
+}
+
+#[test]
+fn burn_liquidity_should_respect_minimum_amounts_and_deadline() {
+	new_test_ext().execute_with(|| {
+		setup_pair();
+		// Burning 1000 of the 10_000 liquidity tokens gives 1000 * 10_000 / 10_000 = 1000 of each.
+		assert_noop!(
+			Market::burn_liquidity(Origin::signed(1), 3, 1000, 1001, 0, DEADLINE),
+			Error::<Test>::InsufficientAmount0
+		);
+		assert_noop!(
+			Market::burn_liquidity(Origin::signed(1), 3, 1000, 0, 1001, DEADLINE),
+			Error::<Test>::InsufficientAmount1
+		);
+		Timestamp::set_timestamp(10);
+		assert_noop!(
+			Market::burn_liquidity(Origin::signed(1), 3, 1000, 0, 0, 9),
+			Error::<Test>::Expired
+		);
+
+		assert_ok!(Market::burn_liquidity(Origin::signed(1), 3, 1000, 1000, 1000, DEADLINE));
+		assert_eq!(Market::reserves(3), (9_000, 9_000));
+		assert_eq!(Assets::balance(1, 1), 1_000_000 - 9_000);
+	});
+}
+
+#[test]
+fn mint_liquidity_should_lock_the_minimum_liquidity() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Assets::issue(Origin::signed(1), 1_000_000));
+		assert_ok!(Assets::issue(Origin::signed(1), 1_000_000));
+		// Deposits whose liquidity does not exceed the locked minimum take nothing.
+		assert_noop!(
+			Market::mint_liquidity(Origin::signed(1), 1, 0, 2, 10_000, 0, 0, DEADLINE),
+			Error::<Test>::InsufficientLiquidityMinted
+		);
+		assert_noop!(
+			Market::mint_liquidity(Origin::signed(1), 1, 1, 2, 1, 0, 0, DEADLINE),
+			Error::<Test>::InsufficientLiquidityMinted
+		);
+		assert_noop!(
+			Market::mint_liquidity(Origin::signed(1), 1, u128::max_value(), 2, 2, 0, 0, DEADLINE),
+			Error::<Test>::StorageOverflow
+		);
+
+		assert_ok!(Market::mint_liquidity(
+			Origin::signed(1),
+			1,
+			10_000,
+			2,
+			10_000,
+			0,
+			0,
+			DEADLINE
+		));
+		assert_eq!(Assets::balance(3, 0), 1);
+		assert_eq!(Assets::balance(3, 1), 9_999);
+		assert_noop!(
+			Market::burn_liquidity(Origin::signed(1), 3, u128::max_value(), 0, 0, DEADLINE),
+			Error::<Test>::StorageOverflow
+		);
+
+		// Burning everything the sender holds leaves the locked share, so the pair takes deposits again.
+		assert_ok!(Market::burn_liquidity(Origin::signed(1), 3, 9_999, 0, 0, DEADLINE));
+		assert_eq!(Assets::total_supply(3), 1);
+		assert_eq!(Market::reserves(3), (1, 1));
+		assert_ok!(Market::mint_liquidity(Origin::signed(1), 1, 1000, 2, 1000, 0, 0, DEADLINE));
+		assert_eq!(Assets::balance(3, 1), 1000);
+		assert_eq!(Market::reserves(3), (1001, 1001));
+	});
+}
+
+#[test]
+fn mint_liquidity_should_take_nothing_if_a_withdrawal_fails() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Assets::issue(Origin::signed(1), 1_000_000));
+		assert_ok!(Assets::issue(Origin::signed(1), 1_000_000));
+		assert_ok!(Assets::transfer(Origin::signed(1), 2, 2, 995_000));
+		// Account 1 holds enough of asset 1 but not of asset 2.
+		assert_noop!(
+			Market::mint_liquidity(Origin::signed(1), 1, 10_000, 2, 10_000, 0, 0, DEADLINE),
+			asset::Error::<Test>::BalanceLow
+		);
+		assert_eq!(Assets::balance(1, 1), 1_000_000);
+
+		assert_ok!(Market::mint_liquidity(Origin::signed(1), 1, 1000, 2, 1000, 0, 0, DEADLINE));
+		assert_noop!(
+			Market::mint_liquidity(Origin::signed(1), 1, 10_000, 2, 10_000, 0, 0, DEADLINE),
+			asset::Error::<Test>::BalanceLow
+		);
+		assert_eq!(Assets::balance(1, 1), 999_000);
+		assert_eq!(Market::reserves(3), (1000, 1000));
+	});
+}
+
+/// `setup_pair`, then issue asset 4 to account 1 and pool 10_000 of it against 10_000 of asset
+/// 2. The liquidity token of that pair is asset 5.
+fn setup_route() {
+	setup_pair();
+	assert_ok!(Assets::issue(Origin::signed(1), 1_000_000));
+	assert_ok!(Market::mint_liquidity(
+		Origin::signed(1),
+		2,
+		10_000,
+		4,
+		10_000,
+		0,
+		0,
+		DEADLINE
+	));
+	assert_eq!(Market::lpt((2, 4)), Some(5));
+}
+
+#[test]
+fn get_amounts_should_walk_the_path() {
+	new_test_ext().execute_with(|| {
+		setup_route();
+
+		assert_eq!(Market::get_amounts_out(1000, &[1, 2, 4]), Ok(vec![1000, 906, 828]));
+		assert_eq!(Market::get_amounts_in(828, &[1, 2, 4]), Ok(vec![1000, 906, 828]));
+		// Buying exactly what selling gives costs no more than was sold.
+		assert_eq!(Market::get_amounts_in(906, &[1, 2]), Ok(vec![1000, 906]));
+		assert_eq!(Market::get_amounts_in(905, &[1, 2]), Ok(vec![999, 905]));
+
+		assert_eq!(Market::get_amounts_out(1000, &[1]), Err(Error::<Test>::InvalidPath.into()));
+		assert_eq!(
+			Market::get_amounts_out(1000, &[1, 2, 4, 3, 5]),
+			Err(Error::<Test>::InvalidPath.into())
+		);
+		assert_eq!(
+			Market::get_amounts_out(1000, &[1, 2, 1]),
+			Err(Error::<Test>::IdenticalIdentifier.into())
+		);
+		assert_eq!(Market::get_amounts_out(1000, &[1, 4]), Err(Error::<Test>::UnknownPair.into()));
+		assert_eq!(
+			Market::get_amounts_in(10_000, &[1, 2]),
+			Err(Error::<Test>::InsufficientLiquidity.into())
+		);
+	});
+}
+
+#[test]
+fn swap_exact_tokens_for_tokens_should_route_through_pairs() {
+	new_test_ext().execute_with(|| {
+		setup_route();
+		let asset2_before = Assets::balance(2, 1);
+
+		assert_noop!(
+			Market::swap_exact_tokens_for_tokens(
+				Origin::signed(1),
+				1000,
+				829,
+				vec![1, 2, 4],
+				DEADLINE
+			),
+			Error::<Test>::InsufficientOutputAmount
+		);
+		assert_ok!(Market::swap_exact_tokens_for_tokens(
+			Origin::signed(1),
+			1000,
+			828,
+			vec![1, 2, 4],
+			DEADLINE
+		));
+		assert_eq!(Assets::balance(1, 1), 1_000_000 - 10_000 - 1000);
+		assert_eq!(Assets::balance(4, 1), 1_000_000 - 10_000 + 828);
+		// The intermediate asset only passes through the pairs.
+		assert_eq!(Assets::balance(2, 1), asset2_before);
+		assert_eq!(Market::reserves(3), (11_000, 9_094));
+		assert_eq!(Market::reserves(5), (10_906, 9_172));
+	});
+}
+
+#[test]
+fn swap_tokens_for_exact_tokens_should_respect_maximum_input() {
+	new_test_ext().execute_with(|| {
+		setup_route();
+
+		assert_noop!(
+			Market::swap_tokens_for_exact_tokens(
+				Origin::signed(1),
+				828,
+				999,
+				vec![1, 2, 4],
+				DEADLINE
+			),
+			Error::<Test>::ExcessiveInputAmount
+		);
+		Timestamp::set_timestamp(10);
+		assert_noop!(
+			Market::swap_tokens_for_exact_tokens(
+				Origin::signed(1),
+				828,
+				1000,
+				vec![1, 2, 4],
+				9
+			),
+			Error::<Test>::Expired
+		);
+		assert_ok!(Market::swap_tokens_for_exact_tokens(
+			Origin::signed(1),
+			828,
+			1000,
+			vec![1, 2, 4],
+			DEADLINE
+		));
+		assert_eq!(Assets::balance(1, 1), 1_000_000 - 10_000 - 1000);
+		assert_eq!(Assets::balance(4, 1), 1_000_000 - 10_000 + 828);
+		assert_eq!(Market::reserves(5), (10_906, 9_172));
+	});
+}
+
+#[test]
+fn mint_liquidity_should_key_pairs_by_sorted_assets() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Assets::issue(Origin::signed(1), 1_000_000));
+		assert_ok!(Assets::issue(Origin::signed(1), 1_000_000));
+		assert_noop!(
+			Market::mint_liquidity(Origin::signed(1), 1, 1000, 1, 1000, 0, 0, DEADLINE),
+			Error::<Test>::IdenticalIdentifier
+		);
+
+		// Created with the larger id first, the pair still keeps the smaller id first.
+		assert_ok!(Market::mint_liquidity(
+			Origin::signed(1),
+			2,
+			5_000,
+			1,
+			10_000,
+			0,
+			0,
+			DEADLINE
+		));
+		assert_eq!(
+			Market::pair(3),
+			Some(PairInfo {
+				token0: 1,
+				token1: 2,
+				reserve0: 10_000,
+				reserve1: 5_000,
+				k_last: U256::zero(),
+			})
+		);
+		assert_eq!(Market::lpt((1, 2)), Some(3));
+		assert_eq!(Market::lpt((2, 1)), Some(3));
+
+		// Either order adds to the same pair, with the amounts in the order they were given.
+		assert_ok!(Market::mint_liquidity(
+			Origin::signed(1),
+			1,
+			2_000,
+			2,
+			1_000,
+			2_000,
+			1_000,
+			DEADLINE
+		));
+		assert_eq!(Market::reserves(3), (12_000, 6_000));
+		assert_noop!(
+			Market::mint_liquidity(Origin::signed(1), 2, 1_000, 1, 1_000, 1_000, 1_000, DEADLINE),
+			Error::<Test>::InsufficientAmount0
+		);
+		assert_eq!(asset::NextAssetId::<Test>::get(), 4);
+	});
+}
+
+/// Write `value` under `key` of map `item` in the storage of the module.
+fn put_map_value<K: Encode, V: Encode>(item: &[u8], key: K, value: V) {
+	put_storage_value(
+		b"SwapModule",
+		item,
+		&key.using_encoded(Blake2_128Concat::hash),
+		value,
+	);
+}
+
+#[test]
+fn on_runtime_upgrade_should_migrate_pairs_into_pair_info() {
+	new_test_ext().execute_with(|| {
+		// Written in the `FixedU128` of the old layout, read back in `UQ64x64`.
+		let price = |n: u128| FixedU128::saturating_from_integer(n);
+		let uq = UQ64x64::saturating_from_integer;
+		// Created as (2, 1), with its reserves under the liquidity token.
+		put_map_value(b"Pairs", 3u128, (2u128, 1u128));
+		put_map_value(b"Reserves", 3u128, (500u128, 1000u128));
+		put_map_value(b"LPTokens", (2u128, 1u128), 3u128);
+		put_map_value(b"LastAccumulativePrice", 3u128, (price(1), price(2)));
+		// Created with its reserves under its first asset.
+		put_map_value(b"Pairs", 5u128, (1u128, 4u128));
+		put_map_value(b"Reserves", 1u128, (70u128, 80u128));
+		put_map_value(b"LPTokens", (1u128, 4u128), 5u128);
+		// Created later than the first pair, of the same assets in the other order.
+		put_map_value(b"Pairs", 6u128, (1u128, 2u128));
+		put_map_value(b"Reserves", 6u128, (10u128, 20u128));
+		put_map_value(b"LPTokens", (1u128, 2u128), 6u128);
+
+		Market::on_runtime_upgrade();
+
+		let pair = |token0, token1, reserve0, reserve1| PairInfo {
+			token0,
+			token1,
+			reserve0,
+			reserve1,
+			k_last: U256::zero(),
+		};
+		assert_eq!(Market::pair(3), Some(pair(1, 2, 1000, 500)));
+		assert_eq!(Market::last_cumulative_price(3), (uq(2), uq(1)));
+		assert_eq!(Market::pair(5), Some(pair(1, 4, 70, 80)));
+		assert_eq!(Market::pair(6), Some(pair(1, 2, 10, 20)));
+		assert_eq!(Market::lpt((2, 1)), Some(3));
+		assert_eq!(Market::lpt((4, 1)), Some(5));
+
+		// The migration only runs once.
+		Market::on_runtime_upgrade();
+		assert_eq!(Market::pair(3), Some(pair(1, 2, 1000, 500)));
+		assert_eq!(Market::last_cumulative_price(3), (uq(2), uq(1)));
+	});
+}
+
+#[test]
+fn set_fee_to_should_require_fee_to_origin() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(Market::set_fee_to(Origin::signed(1), Some(9)), BadOrigin);
+		assert_ok!(Market::set_fee_to(Origin::root(), Some(9)));
+		assert_eq!(Market::fee_to(), Some(9));
+		assert_ok!(Market::set_fee_to(Origin::root(), None));
+		assert_eq!(Market::fee_to(), None);
+	});
+}
+
+/// Swap 10_000 of asset 1 for asset 2 and 10_000 of asset 2 back through the pair of
+/// `setup_pair`, which grows its sqrt(k) from 10_000 to 10_018.
+fn trade_both_ways() {
+	assert_ok!(Market::swap(Origin::signed(1), 1, 10_000, 2, 0, DEADLINE));
+	assert_ok!(Market::swap(Origin::signed(1), 2, 10_000, 1, 0, DEADLINE));
+	assert_eq!(Market::reserves(3), (6_688, 15_008));
+}
+
+#[test]
+fn protocol_fee_should_take_a_sixth_of_sqrt_k_growth() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Market::set_fee_to(Origin::root(), Some(9)));
+		setup_pair();
+		assert_eq!(Market::pair(3).unwrap().k_last, U256::from(10_000u64 * 10_000));
+		trade_both_ways();
+
+		// 10_000 * (10_018 - 10_000) / (5 * 10_018 + 10_000) = 2, minted before the burn.
+		assert_ok!(Market::burn_liquidity(Origin::signed(1), 3, 1000, 0, 0, DEADLINE));
+		assert_eq!(Assets::balance(3, 9), 2);
+		assert_eq!(Assets::total_supply(3), 9_002);
+		assert_eq!(Market::reserves(3), (6_020, 13_508));
+		assert_eq!(Market::pair(3).unwrap().k_last, U256::from(6_020u64 * 13_508));
+
+		// Without swaps since, k has not grown.
+		assert_ok!(Market::burn_liquidity(Origin::signed(1), 3, 1000, 0, 0, DEADLINE));
+		assert_eq!(Assets::balance(3, 9), 2);
+	});
+}
+
+#[test]
+fn protocol_fee_should_be_off_without_fee_to() {
+	new_test_ext().execute_with(|| {
+		setup_pair();
+		assert_eq!(Market::pair(3).unwrap().k_last, U256::zero());
+		trade_both_ways();
+
+		assert_ok!(Market::burn_liquidity(Origin::signed(1), 3, 1000, 0, 0, DEADLINE));
+		assert_eq!(Assets::balance(3, 9), 0);
+		assert_eq!(Assets::total_supply(3), 9_000);
+		assert_eq!(Market::pair(3).unwrap().k_last, U256::zero());
+
+		// Once turned on, the fee accrues from the next mint or burn.
+		assert_ok!(Market::set_fee_to(Origin::root(), Some(9)));
+		assert_ok!(Market::burn_liquidity(Origin::signed(1), 3, 1000, 0, 0, DEADLINE));
+		assert_eq!(Assets::balance(3, 9), 0);
+		assert_eq!(Market::pair(3).unwrap().k_last, U256::from(5_352u64 * 12_008));
+
+		// Turned off again, it forgets `k_last`.
+		assert_ok!(Market::set_fee_to(Origin::root(), None));
+		assert_ok!(Market::mint_liquidity(
+			Origin::signed(1),
+			1,
+			1000,
+			2,
+			3000,
+			0,
+			0,
+			DEADLINE
+		));
+		assert_eq!(Market::pair(3).unwrap().k_last, U256::zero());
+	});
+}
+
+#[test]
+fn prices_should_accumulate_for_each_pair_separately() {
+	new_test_ext().execute_with(|| {
+		setup_route();
+
+		Timestamp::set_timestamp(10);
+		assert_ok!(Market::swap(Origin::signed(1), 1, 1000, 2, 0, DEADLINE));
+		assert_eq!(Market::last_block_timestamp(3), 10);
+		assert_eq!(Market::last_block_timestamp(5), 0);
+
+		// The swap through the first pair does not shorten the time the second one accumulates.
+		Timestamp::set_timestamp(20);
+		assert_ok!(Market::swap(Origin::signed(1), 2, 1000, 4, 0, DEADLINE));
+		assert_eq!(Market::last_block_timestamp(5), 20);
+		assert_eq!(
+			Market::last_cumulative_price(5),
+			(UQ64x64::saturating_from_integer(20), UQ64x64::saturating_from_integer(20))
+		);
+	});
+}
+
+#[test]
+fn consult_should_average_prices_over_the_window() {
+	new_test_ext().execute_with(|| {
+		setup_pair();
+		// The first observation is as old as the pair.
+		assert_eq!(Market::consult(3, 0), None);
+
+		Timestamp::set_timestamp(10);
+		assert_ok!(Market::swap(Origin::signed(1), 1, 1000, 2, 0, DEADLINE));
+		assert_eq!(Market::reserves(3), (11_000, 9_094));
+		let price0 = UQ64x64::checked_from_rational(9_094, 11_000).unwrap();
+		let price1 = UQ64x64::checked_from_rational(11_000, 9_094).unwrap();
+
+		Timestamp::set_timestamp(30);
+		// The prices since the swap held for the whole window.
+		assert_eq!(Market::consult(3, 20), Some((price0, price1)));
+		// Before the swap, both prices were 1.
+		let one = UQ64x64::one();
+		let (average0, average1) = Market::consult(3, 30).unwrap();
+		assert!(price0 < average0 && average0 < one);
+		assert!(one < average1 && average1 < price1);
+		assert_eq!(Market::consult(3, 31), None);
+	});
+}
+
+#[test]
+fn observations_should_wrap_around_the_ring_buffer() {
+	new_test_ext().execute_with(|| {
+		setup_pair();
+		assert_eq!(Market::observation_index(3), Some(0));
+
+		for &(time, from, to) in &[(10, 1, 2), (20, 2, 1), (30, 1, 2), (35, 2, 1)] {
+			Timestamp::set_timestamp(time);
+			assert_ok!(Market::swap(Origin::signed(1), from, 100, to, 0, DEADLINE));
+		}
+		// The swap at 35 came less than `ObservationPeriod` after the observation at 30.
+		assert_eq!(Market::observation_index(3), Some(3));
+		assert_eq!(Market::observations(3, 3).unwrap().timestamp, 30);
+
+		Timestamp::set_timestamp(40);
+		assert_ok!(Market::swap(Origin::signed(1), 1, 100, 2, 0, DEADLINE));
+		assert_eq!(Market::observation_index(3), Some(0));
+		assert_eq!(Market::observations(3, 0).unwrap().timestamp, 40);
+		// The observation from the creation of the pair is gone.
+		assert_eq!(Market::consult(3, 40), None);
+		assert!(Market::consult(3, 30).is_some());
+	});
+}
+
+/// Issue `count` assets to account 1 and pool 10_000 of each in a StableSwap pool with
+/// amplification 100. The liquidity token is asset `count + 1`.
+fn setup_stable_pool(count: u128) {
+	for _ in 0..count {
+		assert_ok!(Assets::issue(Origin::signed(1), 1_000_000));
+	}
+	assert_ok!(Market::create_pool(
+		Origin::signed(1),
+		PoolKind::StableSwap { amplification: 100 },
+		(1..=count).rev().collect(),
+		vec![10_000; count as usize],
+		DEADLINE
+	));
+}
+
+#[test]
+fn create_pool_should_create_a_constant_product_pair() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Assets::issue(Origin::signed(1), 1_000_000));
+		assert_ok!(Assets::issue(Origin::signed(1), 1_000_000));
+		assert_ok!(Market::create_pool(
+			Origin::signed(1),
+			PoolKind::ConstantProduct,
+			vec![2, 1],
+			vec![20_000, 10_000],
+			DEADLINE
+		));
+		assert_eq!(Market::lpt((1, 2)), Some(3));
+		assert_eq!(Market::reserves(3), (10_000, 20_000));
+
+		assert_noop!(
+			Market::create_pool(
+				Origin::signed(1),
+				PoolKind::ConstantProduct,
+				vec![1, 2],
+				vec![10_000, 10_000],
+				DEADLINE
+			),
+			Error::<Test>::PairExists
+		);
+		assert_noop!(
+			Market::create_pool(
+				Origin::signed(1),
+				PoolKind::ConstantProduct,
+				vec![1, 2, 3],
+				vec![10_000, 10_000, 10_000],
+				DEADLINE
+			),
+			Error::<Test>::InvalidPoolAssets
+		);
+	});
+}
 
 #[test]
-fn it_works_for_default_value() {
+fn create_pool_should_mint_the_stable_invariant() {
 	new_test_ext().execute_with(|| {
-		// Dispatch a signed extrinsic.
-		assert_ok!(TemplateModule::do_something(Origin::signed(1), 42));
-		// Read pallet storage and assert an expected result.
-		assert_eq!(TemplateModule::something(), Some(42));
+		setup_stable_pool(2);
+		let pool = Market::stable_pool(3).unwrap();
+		assert_eq!(pool.assets, vec![1, 2]);
+		assert_eq!(pool.balances, vec![10_000, 10_000]);
+		assert_eq!(Market::stable_lpt(vec![1, 2]), Some(3));
+		assert_eq!(Assets::balance(3, 1), 20_000);
+		assert_eq!(Assets::balance(1, 1), 1_000_000 - 10_000);
+
+		let create = |assets: Vec<u128>, amounts: Vec<u128>, amplification| {
+			Market::create_pool(
+				Origin::signed(1),
+				PoolKind::StableSwap { amplification },
+				assets,
+				amounts,
+				DEADLINE,
+			)
+		};
+		assert_noop!(create(vec![2, 1], vec![1, 1], 100), Error::<Test>::PairExists);
+		assert_noop!(create(vec![1, 1], vec![1, 1], 100), Error::<Test>::IdenticalIdentifier);
+		assert_noop!(create(vec![1], vec![1], 100), Error::<Test>::InvalidPoolAssets);
+		assert_noop!(
+			create(vec![1, 2, 3, 4, 5], vec![1; 5], 100),
+			Error::<Test>::InvalidPoolAssets
+		);
+		assert_noop!(create(vec![1, 3], vec![1], 100), Error::<Test>::InvalidPoolAssets);
+		assert_noop!(create(vec![1, 3], vec![1, 1], 0), Error::<Test>::InvalidAmplification);
+		assert_noop!(
+			create(vec![1, 3], vec![1, 1], 1_000_001),
+			Error::<Test>::InvalidAmplification
+		);
 	});
 }
 
 #[test]
-fn correct_error_for_none_value() {
+fn swap_stable_should_trade_near_par() {
 	new_test_ext().execute_with(|| {
-		// Ensure the expected error is thrown when no value is present.
+		setup_stable_pool(2);
+		// The invariant gives 999 of asset 2 for 1000 of asset 1, less a fee of 3.
+		assert_eq!(Market::get_stable_amount_out(3, 1, 1000, 2), Ok(996));
+		assert_noop!(
+			Market::swap_stable(Origin::signed(1), 3, 1, 1000, 2, 997, DEADLINE),
+			Error::<Test>::InsufficientOutputAmount
+		);
+
+		assert_ok!(Market::swap_stable(Origin::signed(1), 3, 1, 1000, 2, 996, DEADLINE));
+		assert_eq!(Assets::balance(1, 1), 1_000_000 - 10_000 - 1000);
+		assert_eq!(Assets::balance(2, 1), 1_000_000 - 10_000 + 996);
+		assert_eq!(Market::stable_pool(3).unwrap().balances, vec![11_000, 9_004]);
+
+		assert_noop!(
+			Market::swap_stable(Origin::signed(1), 3, 1, 1000, 1, 0, DEADLINE),
+			Error::<Test>::IdenticalIdentifier
+		);
+		assert_noop!(
+			Market::swap_stable(Origin::signed(1), 3, 1, 1000, 4, 0, DEADLINE),
+			Error::<Test>::UnknownPair
+		);
 		assert_noop!(
-			TemplateModule::cause_error(Origin::signed(1)),
-			Error::<Test>::NoneValue
+			Market::swap_stable(Origin::signed(1), 4, 1, 1000, 2, 0, DEADLINE),
+			Error::<Test>::UnknownPool
 		);
 	});
 }
+
+#[test]
+fn stable_liquidity_should_charge_imbalanced_deposits() {
+	new_test_ext().execute_with(|| {
+		setup_stable_pool(3);
+		assert_eq!(Assets::balance(4, 1), 30_000);
+
+		// A balanced deposit mints its share of the invariant.
+		assert_ok!(Market::add_stable_liquidity(
+			Origin::signed(1),
+			4,
+			vec![1000, 1000, 1000],
+			3000,
+			DEADLINE
+		));
+		assert_eq!(Assets::balance(4, 1), 33_000);
+
+		// A deposit of one asset pays the swap fee on the part out of proportion.
+		assert_noop!(
+			Market::add_stable_liquidity(Origin::signed(1), 4, vec![3000, 0, 0], 2991, DEADLINE),
+			Error::<Test>::InsufficientLiquidityMinted
+		);
+		assert_ok!(Market::add_stable_liquidity(
+			Origin::signed(1),
+			4,
+			vec![3000, 0, 0],
+			2990,
+			DEADLINE
+		));
+		assert_eq!(Assets::balance(4, 1), 35_990);
+		assert_eq!(Market::stable_pool(4).unwrap().balances, vec![14_000, 11_000, 11_000]);
+		assert_noop!(
+			Market::add_stable_liquidity(Origin::signed(1), 4, vec![1000, 1000], 0, DEADLINE),
+			Error::<Test>::InvalidPoolAssets
+		);
+
+		// Burning withdraws the pro-rata share of each asset.
+		assert_noop!(
+			Market::remove_stable_liquidity(
+				Origin::signed(1),
+				4,
+				3599,
+				vec![1401, 1100, 1100],
+				DEADLINE
+			),
+			Error::<Test>::InsufficientOutputAmount
+		);
+		assert_ok!(Market::remove_stable_liquidity(
+			Origin::signed(1),
+			4,
+			3599,
+			vec![1400, 1100, 1100],
+			DEADLINE
+		));
+		assert_eq!(Assets::balance(4, 1), 32_391);
+		assert_eq!(Market::stable_pool(4).unwrap().balances, vec![12_600, 9_900, 9_900]);
+		assert_eq!(Assets::balance(1, 1), 1_000_000 - 14_000 + 1400);
+	});
+}
+
+#[test]
+fn amplification_should_ramp_linearly() {
+	new_test_ext().execute_with(|| {
+		setup_stable_pool(2);
+		assert_noop!(
+			Market::ramp_amplification(Origin::signed(1), 3, 200, 300),
+			BadOrigin
+		);
+		// A ramp may only start `MinRampTime` after the last one.
+		assert_noop!(
+			Market::ramp_amplification(Origin::root(), 3, 200, 300),
+			Error::<Test>::RampTooFast
+		);
+
+		Timestamp::set_timestamp(100);
+		assert_noop!(
+			Market::ramp_amplification(Origin::root(), 3, 200, 150),
+			Error::<Test>::RampTooFast
+		);
+		assert_noop!(
+			Market::ramp_amplification(Origin::root(), 3, 1001, 300),
+			Error::<Test>::InvalidAmplification
+		);
+		assert_noop!(
+			Market::ramp_amplification(Origin::root(), 3, 9, 300),
+			Error::<Test>::InvalidAmplification
+		);
+		assert_ok!(Market::ramp_amplification(Origin::root(), 3, 200, 300));
+
+		Timestamp::set_timestamp(150);
+		let pool = Market::stable_pool(3).unwrap();
+		assert_eq!(Market::amplification(&pool), 125);
+		assert_noop!(
+			Market::ramp_amplification(Origin::root(), 3, 100, 400),
+			Error::<Test>::RampTooFast
+		);
+		assert_noop!(
+			Market::stop_ramp_amplification(Origin::signed(1), 3),
+			BadOrigin
+		);
+		assert_ok!(Market::stop_ramp_amplification(Origin::root(), 3));
+
+		Timestamp::set_timestamp(300);
+		let pool = Market::stable_pool(3).unwrap();
+		assert_eq!(Market::amplification(&pool), 125);
+		assert_eq!(pool.future_amplification, 125);
+	});
+}
+
+/// The liquidity tokens minted for the deposit that creates a weighted pool.
+const WEIGHTED_SUPPLY: u128 = 100_000_000_000_000_000_000;
+
+/// The part of `WEIGHTED_SUPPLY` locked with the default account.
+const LOCKED_WEIGHTED_SUPPLY: u128 = 1_000_000;
+
+/// Issue assets 1, 2 and 3 to account 1 and pool 10_000 of each in a weighted pool with weights
+/// of 50%, 25% and 25%. The liquidity token is asset 4.
+fn setup_weighted_pool() {
+	for _ in 0..3 {
+		assert_ok!(Assets::issue(Origin::signed(1), 1_000_000));
+	}
+	assert_ok!(Market::create_pool(
+		Origin::signed(1),
+		PoolKind::Weighted {
+			weights: vec![
+				Perbill::from_percent(25),
+				Perbill::from_percent(50),
+				Perbill::from_percent(25),
+			],
+		},
+		vec![3, 1, 2],
+		vec![10_000; 3],
+		DEADLINE
+	));
+	let pool = Market::pool(4).unwrap();
+	assert_eq!(pool.assets, vec![1, 2, 3]);
+	assert_eq!(
+		pool.weights,
+		vec![
+			Perbill::from_percent(50),
+			Perbill::from_percent(25),
+			Perbill::from_percent(25),
+		]
+	);
+	assert_eq!(Market::pool_lpt(vec![1, 2, 3]), Some(4));
+	assert_eq!(Assets::balance(4, 0), LOCKED_WEIGHTED_SUPPLY);
+	assert_eq!(Assets::balance(4, 1), WEIGHTED_SUPPLY - LOCKED_WEIGHTED_SUPPLY);
+}
+
+#[test]
+fn create_pool_should_check_weights() {
+	new_test_ext().execute_with(|| {
+		setup_weighted_pool();
+		let create = |assets: Vec<u128>, weights: Vec<u32>| {
+			let amounts = vec![1; assets.len()];
+			let weights = weights.into_iter().map(Perbill::from_percent).collect();
+			Market::create_pool(
+				Origin::signed(1),
+				PoolKind::Weighted { weights },
+				assets,
+				amounts,
+				DEADLINE,
+			)
+		};
+		assert_noop!(create(vec![1, 2, 3], vec![50, 25, 25]), Error::<Test>::PairExists);
+		assert_noop!(create(vec![1, 2], vec![50, 40]), Error::<Test>::InvalidWeights);
+		assert_noop!(create(vec![1, 2], vec![99, 1]), Error::<Test>::InvalidWeights);
+		assert_noop!(create(vec![1, 2], vec![100]), Error::<Test>::InvalidPoolAssets);
+		assert_noop!(create(vec![1, 1], vec![50, 50]), Error::<Test>::IdenticalIdentifier);
+		assert_noop!(create((1..=9).collect(), vec![11; 9]), Error::<Test>::InvalidPoolAssets);
+	});
+}
+
+#[test]
+fn swap_weighted_should_follow_weighted_product() {
+	new_test_ext().execute_with(|| {
+		setup_weighted_pool();
+		// 10_000 * (1 - (10_000 / 10_997)^(50 / 25)) = 1731
+		assert_eq!(Market::get_weighted_amount_out(4, 1, 1000, 2), Ok(1731));
+		// 10_000 * (1 - (10_000 / 10_997)^(25 / 50)) = 464
+		assert_eq!(Market::get_weighted_amount_out(4, 2, 1000, 1), Ok(464));
+
+		assert_ok!(Market::swap_weighted(Origin::signed(1), 4, 1, 1000, 2, 1731, DEADLINE));
+		assert_eq!(Assets::balance(1, 1), 1_000_000 - 10_000 - 1000);
+		assert_eq!(Assets::balance(2, 1), 1_000_000 - 10_000 + 1731);
+		assert_eq!(Market::pool(4).unwrap().balances, vec![11_000, 8_269, 10_000]);
+
+		assert_noop!(
+			Market::swap_weighted(Origin::signed(1), 4, 3, 1000, 1, 10_000, DEADLINE),
+			Error::<Test>::InsufficientOutputAmount
+		);
+		assert_noop!(
+			Market::swap_weighted(Origin::signed(1), 4, 3, 5001, 1, 0, DEADLINE),
+			Error::<Test>::TradeTooLarge
+		);
+		assert_noop!(
+			Market::swap_weighted(Origin::signed(1), 4, 3, 1000, 5, 0, DEADLINE),
+			Error::<Test>::UnknownPair
+		);
+	});
+}
+
+#[test]
+fn weighted_liquidity_should_be_added_and_removed_pro_rata() {
+	new_test_ext().execute_with(|| {
+		setup_weighted_pool();
+		let tenth = WEIGHTED_SUPPLY / 10;
+		assert_noop!(
+			Market::add_weighted_liquidity(Origin::signed(1), 4, tenth, vec![1000, 1000, 999], DEADLINE),
+			Error::<Test>::ExcessiveInputAmount
+		);
+		assert_ok!(Market::add_weighted_liquidity(
+			Origin::signed(1),
+			4,
+			tenth,
+			vec![1000; 3],
+			DEADLINE
+		));
+		assert_eq!(Market::pool(4).unwrap().balances, vec![11_000; 3]);
+		assert_eq!(Assets::balance(4, 1), WEIGHTED_SUPPLY - LOCKED_WEIGHTED_SUPPLY + tenth);
+
+		assert_noop!(
+			Market::remove_weighted_liquidity(Origin::signed(1), 4, tenth, vec![1001, 0, 0], DEADLINE),
+			Error::<Test>::InsufficientOutputAmount
+		);
+		assert_ok!(Market::remove_weighted_liquidity(
+			Origin::signed(1),
+			4,
+			tenth,
+			vec![1000; 3],
+			DEADLINE
+		));
+		assert_eq!(Market::pool(4).unwrap().balances, vec![10_000; 3]);
+		assert_eq!(Assets::balance(4, 1), WEIGHTED_SUPPLY - LOCKED_WEIGHTED_SUPPLY);
+		assert_eq!(Assets::balance(3, 1), 1_000_000 - 10_000);
+	});
+}
+
+#[test]
+fn weighted_pool_should_keep_its_locked_supply() {
+	new_test_ext().execute_with(|| {
+		setup_weighted_pool();
+		// Burning everything the creator holds leaves the locked share of every balance.
+		assert_ok!(Market::remove_weighted_liquidity(
+			Origin::signed(1),
+			4,
+			WEIGHTED_SUPPLY - LOCKED_WEIGHTED_SUPPLY,
+			vec![9_999; 3],
+			DEADLINE
+		));
+		assert_eq!(Assets::total_supply(4), LOCKED_WEIGHTED_SUPPLY);
+		assert_eq!(Market::pool(4).unwrap().balances, vec![1; 3]);
+
+		// So the pool takes deposits again.
+		assert_ok!(Market::add_weighted_liquidity(
+			Origin::signed(1),
+			4,
+			LOCKED_WEIGHTED_SUPPLY * 1000,
+			vec![1000; 3],
+			DEADLINE
+		));
+		assert_eq!(Market::pool(4).unwrap().balances, vec![1001; 3]);
+	});
+}
+
+#[test]
+fn weighted_liquidity_should_be_added_and_removed_in_a_single_asset() {
+	new_test_ext().execute_with(|| {
+		setup_weighted_pool();
+		// Half of the deposit pays the fee: the supply grows by (1 + 998 / 10_000)^(1/2).
+		assert_ok!(Market::add_single_weighted_liquidity(
+			Origin::signed(1),
+			4,
+			1,
+			1000,
+			0,
+			DEADLINE
+		));
+		assert_eq!(
+			Assets::balance(4, 1),
+			WEIGHTED_SUPPLY - LOCKED_WEIGHTED_SUPPLY + 4_871_349_757_774_900_300
+		);
+		assert_eq!(Market::pool(4).unwrap().balances, vec![11_000, 10_000, 10_000]);
+		assert_noop!(
+			Market::add_single_weighted_liquidity(Origin::signed(1), 4, 2, 5001, 0, DEADLINE),
+			Error::<Test>::TradeTooLarge
+		);
+		assert_noop!(
+			Market::add_single_weighted_liquidity(Origin::signed(1), 4, 5, 1000, 0, DEADLINE),
+			Error::<Test>::UnknownPair
+		);
+	});
+	new_test_ext().execute_with(|| {
+		setup_weighted_pool();
+		let tenth = WEIGHTED_SUPPLY / 10;
+		// Burning a tenth of the supply takes 1 - 0.9^2 of the balance, less a fee of 3.
+		assert_noop!(
+			Market::remove_single_weighted_liquidity(Origin::signed(1), 4, tenth, 1, 1898, DEADLINE),
+			Error::<Test>::InsufficientOutputAmount
+		);
+		assert_ok!(Market::remove_single_weighted_liquidity(
+			Origin::signed(1),
+			4,
+			tenth,
+			1,
+			1897,
+			DEADLINE
+		));
+		assert_eq!(Assets::balance(1, 1), 1_000_000 - 10_000 + 1897);
+		assert_eq!(Market::pool(4).unwrap().balances, vec![8_103, 10_000, 10_000]);
+		// A third of the balance is the most one exit may take.
+		assert_noop!(
+			Market::remove_single_weighted_liquidity(Origin::signed(1), 4, tenth * 2, 2, 0, DEADLINE),
+			Error::<Test>::TradeTooLarge
+		);
+	});
+}
+
+/// Issue assets 1 and 2 to account 1 and give 100_000 of each to account 2.
+fn setup_traders() {
+	assert_ok!(Assets::issue(Origin::signed(1), 1_000_000));
+	assert_ok!(Assets::issue(Origin::signed(1), 1_000_000));
+	assert_ok!(Assets::transfer(Origin::signed(1), 1, 2, 100_000));
+	assert_ok!(Assets::transfer(Origin::signed(1), 2, 2, 100_000));
+}
+
+fn price(n: u128) -> UQ64x64 {
+	UQ64x64::saturating_from_integer(n)
+}
+
+fn reserved(id: u128, who: u64) -> u128 {
+	asset::Reserved::<Test>::get((id, who))
+}
+
+#[test]
+fn limit_orders_should_fill_by_price_then_time() {
+	new_test_ext().execute_with(|| {
+		setup_traders();
+		let sell = |at, amount| {
+			Market::place_limit_order(Origin::signed(1), 1, 2, OrderSide::Sell, at, amount, DEADLINE)
+		};
+		assert_ok!(sell(price(3), 100));
+		assert_ok!(sell(price(2), 100));
+		assert_ok!(sell(price(2), 50));
+		assert_eq!(reserved(1, 1), 250);
+		assert_eq!(Balances::reserved_balance(1), 3 * OrderDeposit::get());
+		assert_eq!(Market::order_book(2, 1, 10).asks, vec![(price(2), 150), (price(3), 100)]);
+
+		// The older order at the best price fills first, then the next one in part.
+		assert_ok!(Market::place_limit_order(
+			Origin::signed(2),
+			1,
+			2,
+			OrderSide::Buy,
+			price(3),
+			120,
+			DEADLINE
+		));
+		assert_eq!(Assets::balance(1, 2), 100_120);
+		assert_eq!(Assets::balance(2, 2), 100_000 - 240);
+		assert_eq!(Assets::balance(2, 1), 900_240);
+		assert_eq!(Market::order(1), None);
+		let order = Market::order(2).unwrap();
+		assert_eq!((order.amount, order.locked), (30, 30));
+		assert_eq!(reserved(1, 1), 130);
+		// The deposit of the filled order goes back with it.
+		assert_eq!(Balances::reserved_balance(1), 2 * OrderDeposit::get());
+
+		// What does not fill rests on the book with its payment reserved.
+		assert_ok!(Market::place_limit_order(
+			Origin::signed(2),
+			1,
+			2,
+			OrderSide::Buy,
+			price(2),
+			200,
+			DEADLINE
+		));
+		assert_eq!(Market::order(2), None);
+		let order = Market::order(3).unwrap();
+		assert_eq!((order.owner, order.amount, order.locked), (2, 170, 340));
+		assert_eq!(Assets::balance(1, 2), 100_150);
+		assert_eq!(Assets::balance(2, 2), 100_000 - 300 - 340);
+		assert_eq!(reserved(2, 2), 340);
+		let book = Market::order_book(1, 2, 10);
+		assert_eq!(book.bids, vec![(price(2), 170)]);
+		assert_eq!(book.asks, vec![(price(3), 100)]);
+		assert_eq!(Market::order_book(1, 2, 0).asks, vec![]);
+	});
+}
+
+#[test]
+fn cancel_order_should_release_reserved_funds() {
+	new_test_ext().execute_with(|| {
+		setup_traders();
+		assert_ok!(Market::place_limit_order(
+			Origin::signed(2),
+			1,
+			2,
+			OrderSide::Buy,
+			price(2),
+			100,
+			DEADLINE
+		));
+		assert_eq!(Assets::balance(2, 2), 100_000 - 200);
+		assert_eq!(Balances::reserved_balance(2), OrderDeposit::get());
+
+		assert_noop!(Market::cancel_order(Origin::signed(1), 0), Error::<Test>::NotOrderOwner);
+		assert_ok!(Market::cancel_order(Origin::signed(2), 0));
+		assert_eq!(Assets::balance(2, 2), 100_000);
+		assert_eq!(reserved(2, 2), 0);
+		assert_eq!(Balances::reserved_balance(2), 0);
+		assert_eq!(Market::order_book(1, 2, 10).bids, vec![]);
+		assert_noop!(Market::cancel_order(Origin::signed(2), 0), Error::<Test>::UnknownOrder);
+	});
+}
+
+#[test]
+fn resting_orders_should_need_a_deposit() {
+	new_test_ext().execute_with(|| {
+		setup_traders();
+		assert_ok!(Assets::transfer(Origin::signed(1), 1, 3, 1000));
+		// Account 3 holds none of the native currency to reserve.
+		assert_noop!(
+			Market::place_limit_order(Origin::signed(3), 1, 2, OrderSide::Sell, price(2), 100, DEADLINE),
+			pallet_balances::Error::<Test, pallet_balances::DefaultInstance>::InsufficientBalance
+		);
+		assert_ok!(Balances::transfer(Origin::signed(1), 3, 1000));
+		assert_ok!(Market::place_limit_order(
+			Origin::signed(3),
+			1,
+			2,
+			OrderSide::Sell,
+			price(2),
+			100,
+			DEADLINE
+		));
+		assert_eq!(Balances::reserved_balance(3), OrderDeposit::get());
+	});
+}
+
+#[test]
+fn place_limit_order_should_check_its_arguments() {
+	new_test_ext().execute_with(|| {
+		setup_traders();
+		let place = |base, quote, side, at, amount| {
+			Market::place_limit_order(Origin::signed(1), base, quote, side, at, amount, DEADLINE)
+		};
+		assert_noop!(place(2, 1, OrderSide::Sell, price(1), 1), Error::<Test>::UnsortedAssets);
+		assert_noop!(place(1, 1, OrderSide::Sell, price(1), 1), Error::<Test>::IdenticalIdentifier);
+		assert_noop!(place(1, 2, OrderSide::Sell, price(0), 1), Error::<Test>::InvalidOrder);
+		assert_noop!(place(1, 2, OrderSide::Sell, price(1), 0), Error::<Test>::InvalidOrder);
+		assert_noop!(
+			place(1, 2, OrderSide::Buy, price(1), 1_000_000),
+			asset::Error::<Test>::BalanceLow
+		);
+
+		for _ in 0..4 {
+			assert_ok!(place(1, 2, OrderSide::Sell, price(5), 10));
+		}
+		assert_noop!(place(1, 2, OrderSide::Sell, price(5), 10), Error::<Test>::OrderBookFull);
+		// An order that fills in full does not need room on the book.
+		assert_ok!(Market::place_limit_order(
+			Origin::signed(2),
+			1,
+			2,
+			OrderSide::Buy,
+			price(5),
+			10,
+			DEADLINE
+		));
+	});
+}
+
+#[test]
+fn limit_orders_should_fill_in_full_or_not_at_all() {
+	new_test_ext().execute_with(|| {
+		setup_traders();
+		assert_ok!(Assets::transfer(Origin::signed(1), 2, 3, 1000));
+		let sell = |who, at| {
+			Market::place_limit_order(Origin::signed(who), 1, 2, OrderSide::Sell, at, 50, DEADLINE)
+		};
+		assert_ok!(sell(2, price(2)));
+		assert_ok!(sell(1, price(3)));
+		// The reserve of the second ask is gone, as if its holding had been destroyed.
+		asset::Reserved::<Test>::remove((1, 1));
+
+		// The first ask fills before the second fails, and is undone with it.
+		assert_noop!(
+			Market::place_limit_order(Origin::signed(3), 1, 2, OrderSide::Buy, price(3), 100, DEADLINE),
+			Error::<Test>::InsufficientReserve
+		);
+		assert_eq!(reserved(1, 2), 50);
+		assert_eq!(Assets::balance(2, 3), 1000);
+	});
+}
+
+#[test]
+fn limit_orders_should_route_between_book_and_pair() {
+	new_test_ext().execute_with(|| {
+		setup_pair();
+		assert_ok!(Assets::transfer(Origin::signed(1), 1, 2, 100_000));
+		assert_ok!(Assets::transfer(Origin::signed(1), 2, 2, 100_000));
+		let rational = |n, d| UQ64x64::checked_from_rational(n, d).unwrap();
+		assert_ok!(Market::place_limit_order(
+			Origin::signed(1),
+			1,
+			2,
+			OrderSide::Sell,
+			rational(105, 100),
+			100,
+			DEADLINE
+		));
+
+		// The pair fills until its price reaches the ask, then the ask fills, then the pair
+		// again up to the limit: 500 for 525, where the pair alone would take 529.
+		assert_eq!(Market::get_amounts_in(500, &[2, 1]), Ok(vec![529, 500]));
+		assert_ok!(Market::place_limit_order(
+			Origin::signed(2),
+			1,
+			2,
+			OrderSide::Buy,
+			rational(110, 100),
+			500,
+			DEADLINE
+		));
+		assert_eq!(Assets::balance(1, 2), 100_500);
+		assert_eq!(Assets::balance(2, 2), 100_000 - 525);
+		assert_eq!(Market::reserves(3), (9_600, 10_420));
+		assert_eq!(Market::order(0), None);
+		assert_eq!(Market::order(1), None);
+	});
+}
+
+#[test]
+fn limit_sells_should_route_between_book_and_pair() {
+	new_test_ext().execute_with(|| {
+		setup_pair();
+		assert_ok!(Assets::transfer(Origin::signed(1), 1, 2, 100_000));
+		assert_ok!(Assets::transfer(Origin::signed(1), 2, 2, 100_000));
+		let rational = |n, d| UQ64x64::checked_from_rational(n, d).unwrap();
+		assert_ok!(Market::place_limit_order(
+			Origin::signed(1),
+			1,
+			2,
+			OrderSide::Buy,
+			rational(95, 100),
+			100,
+			DEADLINE
+		));
+		assert_eq!(reserved(2, 1), 95);
+
+		assert_ok!(Market::place_limit_order(
+			Origin::signed(2),
+			1,
+			2,
+			OrderSide::Sell,
+			rational(90, 100),
+			500,
+			DEADLINE
+		));
+		assert_eq!(Assets::balance(1, 2), 100_000 - 500);
+		assert_eq!(Assets::balance(2, 2), 100_000 + 382 + 94);
+		assert_eq!(Market::reserves(3), (10_400, 9_618));
+		// The bid filled in full, and what rounding left reserved went back.
+		assert_eq!(Market::order(0), None);
+		assert_eq!(reserved(2, 1), 0);
+		assert_eq!(Assets::balance(2, 1), 1_000_000 - 10_000 - 100_000 - 94);
+	});
+}
@@ -1,16 +1,349 @@
-use crate::Trait;
+//! Unsigned binary fixed-point numbers with 64 integer and 64 fractional bits.
+//!
+//! A `UQ64x64` holds `x * 2^64` in a `u128`, so prices keep the same relative precision whatever
+//! the decimals of the assets, and converting to and from integers is a shift. Products and
+//! quotients go through a 256-bit intermediate, so only results beyond the range overflow.
 
-// Decode a UQ64x64 as a u32
-pub fn decode<T: Trait>(y: U64F64) -> T::Balance {
-    T::Balance::From(y.to_num::<u32>())
+use codec::{Decode, Encode};
+use sp_core::U256;
+use sp_runtime::RuntimeDebug;
+
+/// The number of fractional bits.
+const FRACTIONAL_BITS: u32 = 64;
+
+/// A fixed-point number `bits / 2^64`.
+#[derive(Encode, Decode, Clone, Copy, Default, Eq, PartialEq, Ord, PartialOrd, RuntimeDebug)]
+pub struct UQ64x64(u128);
+
+/// A 256-bit intermediate result as a `u128`, if it fits.
+fn to_u128(x: U256) -> Option<u128> {
+    if x > U256::from(u128::max_value()) {
+        None
+    } else {
+        Some(x.low_u128())
+    }
 }
 
-// Encode a Balance to UQ64x64
-pub fn encode<T: Trait>(y: T::Balance) -> U64F64 {
-    U64F64::from_num(y);
+impl UQ64x64 {
+    /// Zero.
+    pub const fn zero() -> Self {
+        UQ64x64(0)
+    }
+
+    /// One.
+    pub const fn one() -> Self {
+        UQ64x64(1 << FRACTIONAL_BITS)
+    }
+
+    /// The largest representable number, just under `2^64`.
+    pub const fn max_value() -> Self {
+        UQ64x64(u128::max_value())
+    }
+
+    /// The number of raw representation `bits`, that is `bits / 2^64`.
+    pub const fn from_bits(bits: u128) -> Self {
+        UQ64x64(bits)
+    }
+
+    /// The raw representation, that is the number times `2^64`.
+    pub const fn to_bits(self) -> u128 {
+        self.0
+    }
+
+    /// Whether the number is zero.
+    pub fn is_zero(self) -> bool {
+        self.0 == 0
+    }
+
+    /// `y` as a fixed-point number. Every `u64` is exactly representable.
+    pub fn encode(y: u64) -> Self {
+        UQ64x64((y as u128) << FRACTIONAL_BITS)
+    }
+
+    /// The integer part of the number.
+    pub fn decode(self) -> u64 {
+        (self.0 >> FRACTIONAL_BITS) as u64
+    }
+
+    /// `n` as a fixed-point number, or `None` if it is `2^64` or more.
+    pub fn checked_from_integer(n: u128) -> Option<Self> {
+        n.checked_mul(1 << FRACTIONAL_BITS).map(UQ64x64)
+    }
+
+    /// `n` as a fixed-point number, or the largest one if it is `2^64` or more.
+    pub fn saturating_from_integer(n: u128) -> Self {
+        Self::checked_from_integer(n).unwrap_or_else(Self::max_value)
+    }
+
+    /// `numerator / denominator`, rounded down, or `None` if `denominator` is zero or the
+    /// quotient is `2^64` or more.
+    pub fn checked_from_rational(numerator: u128, denominator: u128) -> Option<Self> {
+        if denominator == 0 {
+            return None;
+        }
+        to_u128((U256::from(numerator) << FRACTIONAL_BITS) / U256::from(denominator)).map(UQ64x64)
+    }
+
+    /// `numerator / denominator`, rounded down and capped at the largest number, or `None` if
+    /// `denominator` is zero.
+    pub fn saturating_from_rational(numerator: u128, denominator: u128) -> Option<Self> {
+        if denominator == 0 {
+            return None;
+        }
+        Some(Self::checked_from_rational(numerator, denominator).unwrap_or_else(Self::max_value))
+    }
+
+    /// `self + rhs`, or `None` on overflow.
+    pub fn checked_add(self, rhs: Self) -> Option<Self> {
+        self.0.checked_add(rhs.0).map(UQ64x64)
+    }
+
+    /// `self + rhs`, capped at the largest number.
+    pub fn saturating_add(self, rhs: Self) -> Self {
+        UQ64x64(self.0.saturating_add(rhs.0))
+    }
+
+    /// `self + rhs` modulo `2^64`.
+    pub fn wrapping_add(self, rhs: Self) -> Self {
+        UQ64x64(self.0.wrapping_add(rhs.0))
+    }
+
+    /// `self - rhs`, or `None` if `rhs` is larger.
+    pub fn checked_sub(self, rhs: Self) -> Option<Self> {
+        self.0.checked_sub(rhs.0).map(UQ64x64)
+    }
+
+    /// `self - rhs`, or zero if `rhs` is larger.
+    pub fn saturating_sub(self, rhs: Self) -> Self {
+        UQ64x64(self.0.saturating_sub(rhs.0))
+    }
+
+    /// `self - rhs` modulo `2^64`.
+    pub fn wrapping_sub(self, rhs: Self) -> Self {
+        UQ64x64(self.0.wrapping_sub(rhs.0))
+    }
+
+    /// `self * rhs`, rounded down, or `None` on overflow.
+    pub fn checked_mul(self, rhs: Self) -> Option<Self> {
+        to_u128((U256::from(self.0) * U256::from(rhs.0)) >> FRACTIONAL_BITS).map(UQ64x64)
+    }
+
+    /// `self * rhs`, rounded down and capped at the largest number.
+    pub fn saturating_mul(self, rhs: Self) -> Self {
+        self.checked_mul(rhs).unwrap_or_else(Self::max_value)
+    }
+
+    /// `self / rhs`, rounded down, or `None` if `rhs` is zero or the quotient overflows.
+    pub fn checked_div(self, rhs: Self) -> Option<Self> {
+        if rhs.is_zero() {
+            return None;
+        }
+        to_u128((U256::from(self.0) << FRACTIONAL_BITS) / U256::from(rhs.0)).map(UQ64x64)
+    }
+
+    /// `self / rhs`, rounded down and capped at the largest number, or `None` if `rhs` is zero.
+    pub fn saturating_div(self, rhs: Self) -> Option<Self> {
+        if rhs.is_zero() {
+            return None;
+        }
+        Some(self.checked_div(rhs).unwrap_or_else(Self::max_value))
+    }
+
+    /// `self * n`, or `None` on overflow.
+    pub fn checked_mul_int(self, n: u128) -> Option<Self> {
+        self.0.checked_mul(n).map(UQ64x64)
+    }
+
+    /// `self * n`, capped at the largest number.
+    pub fn saturating_mul_int(self, n: u128) -> Self {
+        UQ64x64(self.0.saturating_mul(n))
+    }
+
+    /// `self * n` modulo `2^64`.
+    pub fn wrapping_mul_int(self, n: u128) -> Self {
+        UQ64x64(self.0.wrapping_mul(n))
+    }
+
+    /// `self / n`, rounded down, or `None` if `n` is zero.
+    pub fn uqdiv(self, n: u128) -> Option<Self> {
+        self.0.checked_div(n).map(UQ64x64)
+    }
+
+    /// The integer part of `self * n`, or `None` if it is `2^128` or more.
+    pub fn checked_mul_floor(self, n: u128) -> Option<u128> {
+        to_u128((U256::from(self.0) * U256::from(n)) >> FRACTIONAL_BITS)
+    }
+
+    /// `self * n` rounded up to an integer, or `None` if it is `2^128` or more.
+    pub fn checked_mul_ceil(self, n: u128) -> Option<u128> {
+        let round = (U256::one() << FRACTIONAL_BITS) - U256::one();
+        to_u128((U256::from(self.0) * U256::from(n) + round) >> FRACTIONAL_BITS)
+    }
 }
 
-// Divide a UQ64x64 by a Balance, returning a U64F64
-pub fn uqdiv<T: Trait>(x: U64F64, y: T::Balance) -> U64F64 {
-    x / U64F64::from_num(y);
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sp_core::U512;
+
+    /// The `2^64` scale of the raw representation, as a big integer.
+    fn scale() -> U512 {
+        U512::one() << 64
+    }
+
+    /// The raw representation of a big-integer result, if it fits in a `u128`.
+    fn fits(x: U512) -> Option<UQ64x64> {
+        if x > U512::from(u128::max_value()) {
+            None
+        } else {
+            Some(UQ64x64::from_bits(x.low_u128()))
+        }
+    }
+
+    /// Raw values around the edges of the range, and a spread in between.
+    fn samples() -> Vec<u128> {
+        let mut samples = vec![
+            0,
+            1,
+            2,
+            (1 << 64) - 1,
+            1 << 64,
+            (1 << 64) + 1,
+            u64::max_value() as u128 * 3,
+            1 << 96,
+            (1 << 127) - 1,
+            1 << 127,
+            u128::max_value() - 1,
+            u128::max_value(),
+        ];
+        // A xorshift sequence, so the spread is the same on every run.
+        let mut x: u128 = 0x2545_f491_4f6c_dd1d;
+        for _ in 0..64 {
+            x ^= x << 13;
+            x ^= x >> 7;
+            x ^= x << 17;
+            samples.push(x >> (x % 128));
+        }
+        samples
+    }
+
+    #[test]
+    fn encode_and_decode_work() {
+        assert_eq!(UQ64x64::encode(0), UQ64x64::zero());
+        assert_eq!(UQ64x64::encode(1), UQ64x64::one());
+        assert_eq!(UQ64x64::encode(u64::max_value()).decode(), u64::max_value());
+        assert_eq!(UQ64x64::from_bits((3 << 64) + (1 << 63)).decode(), 3);
+        assert_eq!(UQ64x64::checked_from_integer(1 << 64), None);
+        assert_eq!(UQ64x64::saturating_from_integer(1 << 64), UQ64x64::max_value());
+    }
+
+    #[test]
+    fn from_rational_should_match_reference() {
+        for &n in samples().iter() {
+            for &d in samples().iter() {
+                let expected = if d == 0 {
+                    None
+                } else {
+                    fits(U512::from(n) * scale() / U512::from(d))
+                };
+                assert_eq!(UQ64x64::checked_from_rational(n, d), expected, "{} / {}", n, d);
+                let saturated = if d == 0 {
+                    None
+                } else {
+                    Some(expected.unwrap_or_else(UQ64x64::max_value))
+                };
+                assert_eq!(UQ64x64::saturating_from_rational(n, d), saturated);
+            }
+        }
+    }
+
+    #[test]
+    fn mul_should_match_reference() {
+        for &a in samples().iter() {
+            for &b in samples().iter() {
+                let (x, y) = (UQ64x64::from_bits(a), UQ64x64::from_bits(b));
+                let expected = fits(U512::from(a) * U512::from(b) / scale());
+                assert_eq!(x.checked_mul(y), expected, "{} * {}", a, b);
+                assert_eq!(x.saturating_mul(y), expected.unwrap_or_else(UQ64x64::max_value));
+                let expected_int = fits(U512::from(a) * U512::from(b));
+                assert_eq!(x.checked_mul_int(b), expected_int);
+                assert_eq!(
+                    x.saturating_mul_int(b),
+                    expected_int.unwrap_or_else(UQ64x64::max_value)
+                );
+                assert_eq!(
+                    x.wrapping_mul_int(b).to_bits(),
+                    (U512::from(a) * U512::from(b)).low_u128()
+                );
+                assert_eq!(
+                    x.checked_mul_floor(b),
+                    fits(U512::from(a) * U512::from(b) / scale()).map(UQ64x64::to_bits)
+                );
+                assert_eq!(
+                    x.checked_mul_ceil(b),
+                    fits((U512::from(a) * U512::from(b) + scale() - U512::one()) / scale())
+                        .map(UQ64x64::to_bits)
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn div_should_match_reference() {
+        for &a in samples().iter() {
+            for &b in samples().iter() {
+                let (x, y) = (UQ64x64::from_bits(a), UQ64x64::from_bits(b));
+                let expected = if b == 0 {
+                    None
+                } else {
+                    fits(U512::from(a) * scale() / U512::from(b))
+                };
+                assert_eq!(x.checked_div(y), expected, "{} / {}", a, b);
+                let saturated = if b == 0 {
+                    None
+                } else {
+                    Some(expected.unwrap_or_else(UQ64x64::max_value))
+                };
+                assert_eq!(x.saturating_div(y), saturated);
+                let expected_int = if b == 0 {
+                    None
+                } else {
+                    Some(UQ64x64::from_bits(a / b))
+                };
+                assert_eq!(x.uqdiv(b), expected_int);
+            }
+        }
+    }
+
+    #[test]
+    fn add_and_sub_should_match_reference() {
+        let modulus = U512::one() << 128;
+        for &a in samples().iter() {
+            for &b in samples().iter() {
+                let (x, y) = (UQ64x64::from_bits(a), UQ64x64::from_bits(b));
+                let sum = U512::from(a) + U512::from(b);
+                assert_eq!(x.checked_add(y), fits(sum));
+                assert_eq!(x.saturating_add(y), fits(sum).unwrap_or_else(UQ64x64::max_value));
+                assert_eq!(x.wrapping_add(y).to_bits(), (sum % modulus).low_u128());
+
+                let difference = if a >= b { Some(UQ64x64::from_bits(a - b)) } else { None };
+                assert_eq!(x.checked_sub(y), difference);
+                assert_eq!(x.saturating_sub(y), difference.unwrap_or_else(UQ64x64::zero));
+                assert_eq!(
+                    x.wrapping_sub(y).to_bits(),
+                    ((U512::from(a) + modulus - U512::from(b)) % modulus).low_u128()
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn wrapping_accumulation_should_keep_differences() {
+        // An accumulator that wraps still gives the right difference over a window.
+        let price = UQ64x64::checked_from_rational(3, 2).unwrap();
+        let start = UQ64x64::max_value().wrapping_sub(price.wrapping_mul_int(5));
+        let end = start.wrapping_add(price.wrapping_mul_int(10));
+        assert!(end < start);
+        assert_eq!(end.wrapping_sub(start).uqdiv(10), Some(price));
+    }
 }
@@ -0,0 +1,200 @@
+//! The weighted-product invariant of Balancer, for pools of several assets with arbitrary weights.
+//!
+//! The balances `B_i` of a pool with normalized weights `W_i` keep the invariant
+//!
+//! `V = prod(B_i^W_i)`
+//!
+//! through swaps, so the price of each asset in another is `(B_j / W_j) / (B_i / W_i)`. Fractional
+//! powers come from the binomial series of `(1 + x)^a`, which converges for bases between zero and
+//! two; every formula here keeps its bases in that range as long as a trade stays within the
+//! ratios of its balances that the pallet allows.
+
+use sp_runtime::{FixedPointNumber, FixedU128, PerThing, Perbill, Permill};
+
+/// The size of a term of the binomial series below which it stops.
+const POW_PRECISION: u128 = 100_000_000;
+
+/// The most terms of the binomial series before giving up.
+pub const MAX_POW_ITERATIONS: u32 = 255;
+
+/// `weight` as a fixed-point number.
+fn fixed_weight(weight: Perbill) -> Option<FixedU128> {
+    FixedU128::checked_from_rational(weight.deconstruct(), Perbill::ACCURACY)
+}
+
+/// `base` to the power of `exp`, for `base` between zero and two.
+///
+/// The integer part of `exp` is raised by repeated multiplication, and the fractional part from
+/// the binomial series.
+pub fn pow(base: FixedU128, exp: FixedU128) -> Option<FixedU128> {
+    let div = <FixedU128 as FixedPointNumber>::DIV;
+    let whole = exp.into_inner() / div;
+    let remain = FixedU128::from_inner(exp.into_inner() % div);
+    let mut result = FixedU128::one();
+    for _ in 0..whole {
+        result = result.checked_mul(&base)?;
+    }
+    if remain.is_zero() {
+        return Some(result);
+    }
+    result.checked_mul(&pow_approx(base, remain)?)
+}
+
+/// `base` to the power of `exp`, for `exp` below one, from the binomial series of
+/// `(1 + (base - 1))^exp`.
+fn pow_approx(base: FixedU128, exp: FixedU128) -> Option<FixedU128> {
+    let one = FixedU128::one();
+    let (x, x_negative) = if base >= one {
+        (base.checked_sub(&one)?, false)
+    } else {
+        (one.checked_sub(&base)?, true)
+    };
+    let mut term = one;
+    let mut sum = one;
+    let mut negative = false;
+    for i in 1..=MAX_POW_ITERATIONS {
+        let k = FixedU128::saturating_from_integer(i);
+        let k_minus_one = k.checked_sub(&one)?;
+        let (c, c_negative) = if exp >= k_minus_one {
+            (exp.checked_sub(&k_minus_one)?, false)
+        } else {
+            (k_minus_one.checked_sub(&exp)?, true)
+        };
+        term = term.checked_mul(&c.checked_mul(&x)?)?.checked_div(&k)?;
+        if term.is_zero() {
+            break;
+        }
+        // The sign of each term is that of the one before, times the signs of `c` and `x`.
+        if x_negative {
+            negative = !negative;
+        }
+        if c_negative {
+            negative = !negative;
+        }
+        sum = if negative {
+            sum.checked_sub(&term)?
+        } else {
+            sum.checked_add(&term)?
+        };
+        if term.into_inner() < POW_PRECISION {
+            break;
+        }
+    }
+    Some(sum)
+}
+
+/// The amount of the output asset that `amount_in` buys from a balance of `balance_in` with
+/// `weight_in` and a balance of `balance_out` with `weight_out`, after `fee` on the input.
+///
+/// `B_o * (1 - (B_i / (B_i + A_i * (1 - fee)))^(W_i / W_o))`
+pub fn out_given_in(
+    balance_in: u128,
+    weight_in: Perbill,
+    balance_out: u128,
+    weight_out: Perbill,
+    amount_in: u128,
+    fee: Permill,
+) -> Option<u128> {
+    let adjusted_in = amount_in - fee.mul_ceil(amount_in);
+    let y = FixedU128::checked_from_rational(balance_in, balance_in.checked_add(adjusted_in)?)?;
+    let weight_ratio =
+        FixedU128::checked_from_rational(weight_in.deconstruct(), weight_out.deconstruct())?;
+    let balance_ratio = pow(y, weight_ratio)?;
+    FixedU128::one()
+        .checked_sub(&balance_ratio)
+        .unwrap_or_else(FixedU128::zero)
+        .checked_mul_int(balance_out)
+}
+
+/// The liquidity tokens that depositing `amount_in` of a single asset with a balance of
+/// `balance_in` and `weight_in` mints, out of a supply of `total_supply`.
+///
+/// The part of the deposit beyond the share of the asset in the pool pays `fee`, as if it was
+/// swapped for the other assets. The supply grows by `(1 + A_i / B_i)^W_i`.
+pub fn pool_out_given_single_in(
+    balance_in: u128,
+    weight_in: Perbill,
+    total_supply: u128,
+    amount_in: u128,
+    fee: Permill,
+) -> Option<u128> {
+    let taxable = (Perbill::one() - weight_in).mul_ceil(amount_in);
+    let amount_in_after_fee = amount_in - fee.mul_ceil(taxable);
+    let ratio =
+        FixedU128::checked_from_rational(balance_in.checked_add(amount_in_after_fee)?, balance_in)?;
+    let pool_ratio = pow(ratio, fixed_weight(weight_in)?)?;
+    let new_supply = pool_ratio.checked_mul_int(total_supply)?;
+    Some(new_supply.saturating_sub(total_supply))
+}
+
+/// The amount of a single asset with a balance of `balance_out` and `weight_out` that burning
+/// `pool_amount_in` out of a supply of `total_supply` liquidity tokens withdraws.
+///
+/// The balance shrinks by `(1 - P_i / S)^(1 / W_o)`, and the part of the withdrawal beyond the
+/// share of the asset in the pool pays `fee`.
+pub fn single_out_given_pool_in(
+    balance_out: u128,
+    weight_out: Perbill,
+    total_supply: u128,
+    pool_amount_in: u128,
+    fee: Permill,
+) -> Option<u128> {
+    let new_supply = total_supply.checked_sub(pool_amount_in)?;
+    let pool_ratio = FixedU128::checked_from_rational(new_supply, total_supply)?;
+    let exp = FixedU128::checked_from_rational(Perbill::ACCURACY, weight_out.deconstruct())?;
+    let balance_ratio = pow(pool_ratio, exp)?;
+    let new_balance = balance_ratio.checked_mul_int(balance_out)?;
+    let amount_out_before_fee = balance_out.checked_sub(new_balance)?;
+    let taxable = (Perbill::one() - weight_out).mul_ceil(amount_out_before_fee);
+    Some(amount_out_before_fee - fee.mul_ceil(taxable))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fixed(n: u128, d: u128) -> FixedU128 {
+        FixedU128::checked_from_rational(n, d).unwrap()
+    }
+
+    #[test]
+    fn pow_works() {
+        assert_eq!(pow(fixed(1, 2), fixed(3, 1)), Some(fixed(1, 8)));
+        assert_eq!(pow(fixed(3, 2), FixedU128::zero()), Some(FixedU128::one()));
+        // Fractional powers are close to exact.
+        let root = pow(fixed(25, 16), fixed(1, 2)).unwrap();
+        let error = root.into_inner() as i128 - fixed(5, 4).into_inner() as i128;
+        assert!(error.abs() < 1_000_000_000);
+        let root = pow(fixed(9, 16), fixed(3, 2)).unwrap();
+        let error = root.into_inner() as i128 - fixed(27, 64).into_inner() as i128;
+        assert!(error.abs() < 1_000_000_000);
+    }
+
+    #[test]
+    fn out_given_in_works() {
+        let fee = Permill::from_perthousand(3);
+        let (half, quarter) = (Perbill::from_percent(50), Perbill::from_percent(25));
+        // With equal weights, it is the constant product.
+        assert_eq!(out_given_in(10_000, half, 10_000, half, 1000, fee), Some(906));
+        // 10_000 * (1 - (10_000 / 10_997)^2) = 1731.03
+        assert_eq!(out_given_in(10_000, half, 10_000, quarter, 1000, fee), Some(1731));
+        // 10_000 * (1 - (10_000 / 10_997)^(1/2)) = 464.07
+        assert_eq!(out_given_in(10_000, quarter, 10_000, half, 1000, fee), Some(464));
+    }
+
+    #[test]
+    fn single_asset_joins_and_exits_work() {
+        let fee = Permill::from_perthousand(3);
+        let half = Perbill::from_percent(50);
+        let supply = 100 * <FixedU128 as FixedPointNumber>::DIV;
+        // Half of the deposit pays the fee: the supply grows by (1 + 998 / 10_000)^(1/2).
+        let minted = pool_out_given_single_in(10_000, half, supply, 1000, fee).unwrap();
+        assert_eq!(minted / 1_000_000_000_000, 4_871_349);
+        // Burning a tenth of the supply takes 19% of the balance, less a fee of 3.
+        assert_eq!(
+            single_out_given_pool_in(10_000, half, supply, supply / 10, fee),
+            Some(1897)
+        );
+        assert_eq!(single_out_given_pool_in(10_000, half, supply, supply + 1, fee), None);
+    }
+}
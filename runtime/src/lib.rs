@@ -77,6 +77,84 @@ impl pallet_juggernaut::Trait for Runtime {
 	type Event = Event;
 }
 
+pub use subswap_asset;
+pub use pallet_market;
+pub use subswap_asset_tx_payment;
+
+/// The identifier of an asset issued through `subswap_asset`.
+pub type AssetId = u32;
+
+parameter_types! {
+	pub const NativeAssetId: AssetId = 0;
+	pub const AssetModuleId: ModuleId = ModuleId(*b"py/asset");
+	pub const AssetMetadataDepositBase: Balance = 10 * DOLLARS;
+	pub const AssetMetadataDepositPerByte: Balance = 1 * DOLLARS;
+	pub const AssetStringLimit: u32 = 50;
+	pub const AssetMaxBatchSize: u32 = 100;
+	pub const AssetMaxAttributes: u32 = 16;
+	pub const AssetMaxVestingSchedules: u32 = 8;
+	pub const AssetMinVestedTransferFactor: u32 = 100;
+}
+
+impl subswap_asset::Trait for Runtime {
+	type Event = Event;
+	type Balance = Balance;
+	type AssetId = AssetId;
+	type Currency = Balances;
+	type NativeAssetId = NativeAssetId;
+	type ModuleId = AssetModuleId;
+	type MetadataDepositBase = AssetMetadataDepositBase;
+	type MetadataDepositPerByte = AssetMetadataDepositPerByte;
+	type StringLimit = AssetStringLimit;
+	type OnDust = ();
+	type MaxBatchSize = AssetMaxBatchSize;
+	type ItemId = u32;
+	type MaxAttributes = AssetMaxAttributes;
+	type BlockNumberToBalance = ConvertInto;
+	type MaxVestingSchedules = AssetMaxVestingSchedules;
+	type MinVestedTransferFactor = AssetMinVestedTransferFactor;
+	type WeightInfo = weights::subswap_asset::WeightInfo;
+}
+
+parameter_types! {
+	pub const MarketSwapFee: Permill = Permill::from_perthousand(3);
+	pub const MarketMaxPathLength: u32 = 5;
+	pub const MarketObservationPeriod: Moment = 10 * 60 * 1000;
+	pub const MarketMaxObservations: u32 = 144;
+	pub const MarketMaxStableAssets: u32 = 4;
+	pub const MarketMaxAmplification: u32 = 1_000_000;
+	pub const MarketMinRampTime: Moment = 24 * 60 * 60 * 1000;
+	pub const MarketMaxWeightedAssets: u32 = 8;
+	pub const MarketMaxOrders: u32 = 100;
+	pub const MarketOrderDeposit: Balance = 1 * DOLLARS;
+}
+
+impl pallet_market::Trait for Runtime {
+	type Event = Event;
+	type SwapFee = MarketSwapFee;
+	type MaxPathLength = MarketMaxPathLength;
+	type ObservationPeriod = MarketObservationPeriod;
+	type MaxObservations = MarketMaxObservations;
+	type FeeToOrigin = EnsureRootOrHalfCouncil;
+	type MaxStableAssets = MarketMaxStableAssets;
+	type MaxAmplification = MarketMaxAmplification;
+	type MinRampTime = MarketMinRampTime;
+	type MaxWeightedAssets = MarketMaxWeightedAssets;
+	type MaxOrders = MarketMaxOrders;
+	type OrderDeposit = MarketOrderDeposit;
+}
+
+parameter_types! {
+	pub AssetFeeCollector: AccountId = Treasury::account_id();
+	pub const AssetFeePriceWindow: Moment = 30 * 60 * 1000;
+}
+
+impl subswap_asset_tx_payment::Trait for Runtime {
+	type Event = Event;
+	type FeeCollector = AssetFeeCollector;
+	type PriceWindow = AssetFeePriceWindow;
+}
+
 #[cfg(any(feature = "std", test))]
 pub use frame_system::Call as SystemCall;
 #[cfg(any(feature = "std", test))]
@@ -749,7 +827,7 @@ where
             frame_system::CheckEra::<Runtime>::from(era),
             frame_system::CheckNonce::<Runtime>::from(nonce),
             frame_system::CheckWeight::<Runtime>::new(),
-            pallet_transaction_payment::ChargeTransactionPayment::<Runtime>::from(tip),
+            subswap_asset_tx_payment::ChargeAssetTxPayment::<Runtime>::from(tip, None),
         );
         let raw_payload = SignedPayload::new(call, extra)
             .map_err(|e| {
@@ -949,6 +1027,9 @@ construct_runtime!(
         Proxy: pallet_proxy::{Module, Call, Storage, Event<T>},
 		Multisig: pallet_multisig::{Module, Call, Storage, Event<T>},
 		JuggerNaut: pallet_juggernaut::{Module, Call, Storage, Event<T>},
+		Assets: subswap_asset::{Module, Call, Storage, Event<T>, Config<T>},
+		Market: pallet_market::{Module, Call, Storage, Event<T>, Config},
+		AssetTxPayment: subswap_asset_tx_payment::{Module, Event<T>},
     }
 );
 
@@ -974,7 +1055,7 @@ pub type SignedExtra = (
     frame_system::CheckEra<Runtime>,
     frame_system::CheckNonce<Runtime>,
     frame_system::CheckWeight<Runtime>,
-    pallet_transaction_payment::ChargeTransactionPayment<Runtime>,
+    subswap_asset_tx_payment::ChargeAssetTxPayment<Runtime>,
 );
 /// Unchecked extrinsic type as expected by this runtime.
 pub type UncheckedExtrinsic = generic::UncheckedExtrinsic<Address, Call, Signature, SignedExtra>;
@@ -1183,6 +1264,51 @@ impl_runtime_apis! {
         }
     }
 
+    impl subswap_asset_rpc_runtime_api::AssetApi<
+        Block,
+        AccountId,
+        AssetId,
+        Balance,
+    > for Runtime {
+        fn balance(id: AssetId, who: AccountId) -> Balance {
+            Assets::balance(id, who)
+        }
+
+        fn total_supply(id: AssetId) -> Balance {
+            Assets::total_supply(id)
+        }
+
+        fn metadata(id: AssetId) -> Option<(Vec<u8>, Vec<u8>, u8)> {
+            Assets::metadata(id).map(|m| (m.name, m.symbol, m.decimals))
+        }
+
+        fn account_balances(who: AccountId) -> Vec<(AssetId, Balance)> {
+            Assets::account_balances(&who)
+        }
+    }
+
+    impl pallet_market_rpc_runtime_api::MarketApi<Block, AssetId, Balance> for Runtime {
+        fn get_amounts_out(amount_in: Balance, path: Vec<AssetId>) -> Option<Vec<Balance>> {
+            Market::get_amounts_out(amount_in, &path).ok()
+        }
+
+        fn get_amounts_in(amount_out: Balance, path: Vec<AssetId>) -> Option<Vec<Balance>> {
+            Market::get_amounts_in(amount_out, &path).ok()
+        }
+
+        fn order_book(
+            token0: AssetId,
+            token1: AssetId,
+            depth: u32,
+        ) -> (Vec<(u128, Balance)>, Vec<(u128, Balance)>) {
+            let book = Market::order_book(token0, token1, depth);
+            let levels = |levels: Vec<(pallet_market::UQ64x64, Balance)>| {
+                levels.into_iter().map(|(price, amount)| (price.to_bits(), amount)).collect()
+            };
+            (levels(book.bids), levels(book.asks))
+        }
+    }
+
     impl sp_session::SessionKeys<Block> for Runtime {
         fn generate_session_keys(seed: Option<Vec<u8>>) -> Vec<u8> {
             SessionKeys::generate(seed)
@@ -1251,6 +1377,7 @@ impl_runtime_apis! {
             add_benchmark!(params, batches, pallet_treasury, Treasury);
             add_benchmark!(params, batches, pallet_utility, Utility);
             add_benchmark!(params, batches, pallet_vesting, Vesting);
+            add_benchmark!(params, batches, subswap_asset, Assets);
 
             if batches.is_empty() { return Err("Benchmark not found for this pallet.".into()) }
             Ok(batches)